@@ -0,0 +1,302 @@
+use crate::{Number, utils::divisibility::prime_factorization};
+use num::{BigInt, Integer, One, Signed, ToPrimitive, Zero, rational::Ratio};
+
+/// A FRACTRAN program. At each step the state is multiplied by the first fraction in the list that produces an integer. The program halts when no fraction produces an integer.
+pub struct Fractran {
+    fractions: Vec<Ratio<BigInt>>,
+}
+
+impl Fractran {
+    /// Panics if any fraction is not positive.
+    pub fn new(fractions: &[Ratio<BigInt>]) -> Self {
+        assert!(
+            fractions.iter().all(|f| f.is_positive()),
+            "all fractions must be positive"
+        );
+        Self {
+            fractions: fractions.to_vec(),
+        }
+    }
+
+    /// Create a program from pairs of numerators and denominators.
+    /// Panics if any fraction is not positive.
+    pub fn from_pairs(fractions: &[(Number, Number)]) -> Self {
+        Self::new(
+            &fractions
+                .iter()
+                .map(|(n, d)| Ratio::new(BigInt::from(*n), BigInt::from(*d)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Conway's PRIMEGAME. Starting from 2 the powers of 2 that appear are exactly the powers with a prime exponent, in increasing order.
+    pub fn primegame() -> Self {
+        Self::from_pairs(&[
+            (17, 91),
+            (78, 85),
+            (19, 51),
+            (23, 38),
+            (29, 33),
+            (77, 29),
+            (95, 23),
+            (77, 19),
+            (1, 17),
+            (11, 13),
+            (13, 11),
+            (15, 14),
+            (15, 2),
+            (55, 1),
+        ])
+    }
+
+    pub fn fractions(&self) -> &[Ratio<BigInt>] {
+        &self.fractions
+    }
+
+    /// The state after one step from n, or None if the program halts at n.
+    pub fn step(&self, n: &BigInt) -> Option<BigInt> {
+        self.fractions
+            .iter()
+            .find(|f| n.is_multiple_of(f.denom()))
+            .map(|f| n / f.denom() * f.numer())
+    }
+
+    /// Run the program on an input. The final state is returned before the iterator ends if the program halts.
+    pub fn create_iter(&self, n: BigInt) -> FractranIter<'_> {
+        FractranIter {
+            program: self,
+            state: Some(n),
+        }
+    }
+
+    /// The program as a register machine where each register holds the exponent of one prime. This is much faster than multiplying integers.
+    /// Panics if any numerator or denominator does not fit in a Number.
+    pub fn registers(&self) -> FractranRegisters {
+        let factor = |n: &BigInt| {
+            let n = n
+                .to_i64()
+                .expect("numerators and denominators must fit in a Number");
+            prime_factorization(n)
+        };
+
+        let mut primes: Vec<Number> = Vec::new();
+        for f in self.fractions.iter() {
+            for (p, _) in factor(f.numer()).into_iter().chain(factor(f.denom())) {
+                if !primes.contains(&p) {
+                    primes.push(p);
+                }
+            }
+        }
+        primes.sort();
+
+        let exponents = |n: &BigInt| {
+            let mut e = vec![0; primes.len()];
+            for (p, m) in factor(n) {
+                let i = primes.binary_search(&p).unwrap();
+                e[i] = m as u64;
+            }
+            e
+        };
+        let rules = self
+            .fractions
+            .iter()
+            .map(|f| (exponents(f.numer()), exponents(f.denom())))
+            .collect();
+
+        FractranRegisters { primes, rules }
+    }
+}
+
+pub struct FractranIter<'a> {
+    program: &'a Fractran,
+    state: Option<BigInt>,
+}
+
+impl<'a> Iterator for FractranIter<'a> {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.state.take()?;
+        self.state = self.program.step(&out);
+        Some(out)
+    }
+}
+
+/// A FRACTRAN program acting on the exponents of the primes that appear in its fractions. Each fraction adds the exponents of its numerator and subtracts those of its denominator, and can only be applied if no register would become negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FractranRegisters {
+    primes: Vec<Number>,
+    // exponents of the numerator and denominator for each fraction
+    rules: Vec<(Vec<u64>, Vec<u64>)>,
+}
+
+/// The state of a FRACTRAN program in register form. Any part of the state not divisible by the primes of the program is never changed and is kept separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterState {
+    pub exponents: Vec<u64>,
+    pub cofactor: BigInt,
+}
+
+impl FractranRegisters {
+    /// The primes of the program, in the same order as the registers.
+    pub fn primes(&self) -> &[Number] {
+        &self.primes
+    }
+
+    /// Convert a positive integer into a register state.
+    /// Panics if n is not positive.
+    pub fn to_registers(&self, n: &BigInt) -> RegisterState {
+        assert!(n.is_positive(), "n must be positive");
+        let mut cofactor = n.clone();
+        let exponents = self
+            .primes
+            .iter()
+            .map(|p| {
+                let p = BigInt::from(*p);
+                let mut e = 0;
+                while cofactor.is_multiple_of(&p) {
+                    cofactor /= &p;
+                    e += 1;
+                }
+                e
+            })
+            .collect();
+        RegisterState {
+            exponents,
+            cofactor,
+        }
+    }
+
+    /// Convert a register state back into an integer.
+    pub fn to_integer(&self, state: &RegisterState) -> BigInt {
+        self.primes
+            .iter()
+            .zip(state.exponents.iter())
+            .fold(state.cofactor.clone(), |acc, (p, e)| {
+                acc * BigInt::from(*p).pow(*e as u32)
+            })
+    }
+
+    /// Update the registers in place. Returns false if the program halts.
+    pub fn step(&self, exponents: &mut [u64]) -> bool {
+        for (num, den) in self.rules.iter() {
+            if exponents.iter().zip(den.iter()).all(|(e, d)| e >= d) {
+                for ((e, n), d) in exponents.iter_mut().zip(num.iter()).zip(den.iter()) {
+                    *e = *e - d + n;
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Run the program on an input. The final state is returned before the iterator ends if the program halts.
+    pub fn create_iter(&self, n: &BigInt) -> FractranRegistersIter<'_> {
+        FractranRegistersIter {
+            program: self,
+            state: Some(self.to_registers(n)),
+        }
+    }
+}
+
+pub struct FractranRegistersIter<'a> {
+    program: &'a FractranRegisters,
+    state: Option<RegisterState>,
+}
+
+impl<'a> Iterator for FractranRegistersIter<'a> {
+    type Item = RegisterState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.state.take()?;
+        let mut next = out.clone();
+        if self.program.step(&mut next.exponents) {
+            self.state = Some(next);
+        }
+        Some(out)
+    }
+}
+
+/// The powers of two reached by Conway's PRIMEGAME after the starting value of 2. Their exponents are the prime numbers.
+///
+/// ```text
+/// 4, 8, 32, 128, 2048, 8192, 131072, 524288, 8388608, 536870912...
+/// ```
+pub struct PrimeGame {
+    program: FractranRegisters,
+    exponents: Vec<u64>,
+}
+
+impl PrimeGame {
+    pub fn new() -> Self {
+        let program = Fractran::primegame().registers();
+        let exponents = program.to_registers(&BigInt::from(2)).exponents;
+        Self { program, exponents }
+    }
+}
+
+impl Iterator for PrimeGame {
+    type Item = BigInt;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The prime 2 is always the first register
+        loop {
+            if !self.program.step(&mut self.exponents) {
+                return None;
+            }
+            if self.exponents[1..].iter().all(|e| e.is_zero()) {
+                return Some(BigInt::one() << self.exponents[0]);
+            }
+        }
+    }
+}
+
+crate::check_sequences!(
+    PrimeGame::new(), [4, 8, 32, 128, 2048, 8192, 131072, 524288, 8388608, 536870912];
+    Fractran::primegame().create_iter(BigInt::from(2)), [2, 15, 825, 725, 1925, 2275, 425, 390, 330, 290, 770, 910, 170, 156, 132, 116, 308, 364, 68, 4, 30, 225];
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Primes;
+
+    #[test]
+    fn primegame_exponents_are_primes() {
+        for (power, p) in PrimeGame::new().zip(Primes::<Number>::new()).take(15) {
+            assert_eq!(power, BigInt::one() << p);
+        }
+    }
+
+    #[test]
+    fn registers_agree_with_integers() {
+        let program = Fractran::primegame();
+        let registers = program.registers();
+        assert_eq!(registers.primes(), &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        for (a, b) in program
+            .create_iter(BigInt::from(2))
+            .zip(registers.create_iter(&BigInt::from(2)))
+            .take(5000)
+        {
+            assert_eq!(a, registers.to_integer(&b));
+        }
+    }
+
+    #[test]
+    fn halting_and_cofactor() {
+        // Addition: 2^a * 3^b halts at 3^(a+b)
+        let program = Fractran::from_pairs(&[(3, 2)]);
+        let n = BigInt::from(2_i64.pow(4) * 3_i64.pow(5));
+        assert_eq!(
+            program.create_iter(n.clone()).last(),
+            Some(BigInt::from(3).pow(9))
+        );
+
+        // Primes that do not appear in the program are left unchanged
+        let registers = program.registers();
+        let state = registers.create_iter(&(n * 7)).last().unwrap();
+        assert_eq!(state.exponents, vec![0, 9]);
+        assert_eq!(state.cofactor, BigInt::from(7));
+        assert_eq!(registers.to_integer(&state), BigInt::from(3).pow(9) * 7);
+    }
+}
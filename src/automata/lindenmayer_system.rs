@@ -1,3 +1,6 @@
+use crate::rngs::Mt19937;
+use std::collections::HashMap;
+
 /// An L-system defined by a function on chars.
 pub struct Lindenmayer {
     transition: Box<dyn Fn(char) -> Option<String>>,
@@ -45,6 +48,231 @@ impl<'a> Iterator for LindenmayerIter<'a> {
     }
 }
 
+/// A stochastic L-system. Each variable symbol has one or more weighted productions and at each step one of them is chosen at random with probability proportional to its weight. Random choices are made by a Mersenne Twister so the same seed always produces the same sequence of strings.
+pub struct StochasticLindenmayer {
+    productions: HashMap<char, Vec<(f64, String)>>,
+    seed: u32,
+}
+
+impl StochasticLindenmayer {
+    /// Panics if any weight is negative or if the weights for a symbol sum to zero.
+    pub fn new(productions: &[(char, &[(f64, &str)])], seed: u32) -> Self {
+        let mut map = HashMap::new();
+        for (c, options) in productions.iter() {
+            assert!(
+                options.iter().all(|(w, _)| *w >= 0.0),
+                "weights must be non-negative"
+            );
+            assert!(
+                options.iter().map(|(w, _)| w).sum::<f64>() > 0.0,
+                "weights for {c} must not sum to zero"
+            );
+            map.insert(
+                *c,
+                options.iter().map(|(w, s)| (*w, s.to_string())).collect(),
+            );
+        }
+        Self {
+            productions: map,
+            seed,
+        }
+    }
+
+    /// Run the automaton on an input.
+    pub fn create_iter(&self, initital_string: &str) -> StochasticLindenmayerIter<'_> {
+        StochasticLindenmayerIter {
+            string: initital_string.to_string(),
+            productions: &self.productions,
+            rng: Mt19937::new(self.seed),
+        }
+    }
+}
+
+pub struct StochasticLindenmayerIter<'a> {
+    string: String,
+    productions: &'a HashMap<char, Vec<(f64, String)>>,
+    rng: Mt19937,
+}
+
+impl<'a> Iterator for StochasticLindenmayerIter<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.string.clone();
+        let mut next = String::with_capacity(self.string.len());
+        for c in self.string.chars() {
+            if let Some(options) = self.productions.get(&c) {
+                let total: f64 = options.iter().map(|(w, _)| w).sum();
+                let mut r = (self.rng.next()? as f64 / 4294967296.0) * total;
+                // Fall back to the last option in case of rounding
+                let mut chosen = &options[options.len() - 1].1;
+                for (w, s) in options.iter() {
+                    if r < *w {
+                        chosen = s;
+                        break;
+                    }
+                    r -= w;
+                }
+                next.push_str(chosen);
+            } else {
+                next.push(c);
+            }
+        }
+        self.string = next;
+        Some(out)
+    }
+}
+
+/// A production for a context-sensitive L-system. The predecessor symbol is replaced by the successor only if it is preceded by the left context and followed by the right context. An empty context always matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextRule {
+    left: Vec<char>,
+    predecessor: char,
+    right: Vec<char>,
+    successor: String,
+}
+
+impl ContextRule {
+    pub fn new(left: &str, predecessor: char, right: &str, successor: &str) -> Self {
+        Self {
+            left: left.chars().collect(),
+            predecessor,
+            right: right.chars().collect(),
+            successor: successor.to_string(),
+        }
+    }
+
+    /// Search backward from position i, skipping ignored symbols and any bracketed branches, and moving out of the branch containing i.
+    fn left_matches(&self, s: &[char], i: usize, ignore: &[char]) -> bool {
+        let mut j = i;
+        for x in self.left.iter().rev() {
+            loop {
+                if j == 0 {
+                    return false;
+                }
+                j -= 1;
+                match s[j] {
+                    ']' => {
+                        let mut depth = 1;
+                        while depth > 0 {
+                            if j == 0 {
+                                return false;
+                            }
+                            j -= 1;
+                            match s[j] {
+                                ']' => depth += 1,
+                                '[' => depth -= 1,
+                                _ => (),
+                            }
+                        }
+                    }
+                    '[' => (),
+                    c if ignore.contains(&c) => (),
+                    _ => break,
+                }
+            }
+            if s[j] != *x {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Search forward from position i, skipping ignored symbols and any bracketed branches. The right context cannot extend past the end of the branch containing i.
+    fn right_matches(&self, s: &[char], i: usize, ignore: &[char]) -> bool {
+        let mut j = i + 1;
+        for x in self.right.iter() {
+            loop {
+                if j >= s.len() {
+                    return false;
+                }
+                match s[j] {
+                    '[' => {
+                        let mut depth = 1;
+                        while depth > 0 {
+                            j += 1;
+                            if j >= s.len() {
+                                return false;
+                            }
+                            match s[j] {
+                                '[' => depth += 1,
+                                ']' => depth -= 1,
+                                _ => (),
+                            }
+                        }
+                        j += 1;
+                    }
+                    ']' => return false,
+                    c if ignore.contains(&c) => j += 1,
+                    _ => break,
+                }
+            }
+            if s[j] != *x {
+                return false;
+            }
+            j += 1;
+        }
+        true
+    }
+
+    fn matches(&self, s: &[char], i: usize, ignore: &[char]) -> bool {
+        s[i] == self.predecessor
+            && self.left_matches(s, i, ignore)
+            && self.right_matches(s, i, ignore)
+    }
+}
+
+/// A context-sensitive L-system. For each symbol the first rule that matches is applied, symbols with no matching rule are left unchanged. Symbols in the ignore set are skipped over when matching contexts, as are the brackets that delimit branches.
+pub struct ContextLindenmayer {
+    rules: Vec<ContextRule>,
+    ignore: Vec<char>,
+}
+
+impl ContextLindenmayer {
+    pub fn new(rules: &[ContextRule], ignore: &str) -> Self {
+        Self {
+            rules: rules.to_vec(),
+            ignore: ignore.chars().collect(),
+        }
+    }
+
+    /// Run the automaton on an input.
+    pub fn create_iter(&self, initital_string: &str) -> ContextLindenmayerIter<'_> {
+        ContextLindenmayerIter {
+            string: initital_string.chars().collect(),
+            rules: &self.rules,
+            ignore: &self.ignore,
+        }
+    }
+}
+
+pub struct ContextLindenmayerIter<'a> {
+    string: Vec<char>,
+    rules: &'a Vec<ContextRule>,
+    ignore: &'a Vec<char>,
+}
+
+impl<'a> Iterator for ContextLindenmayerIter<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.string.iter().collect();
+        let mut next = Vec::with_capacity(self.string.len());
+        for i in 0..self.string.len() {
+            match self
+                .rules
+                .iter()
+                .find(|r| r.matches(&self.string, i, self.ignore))
+            {
+                Some(rule) => next.extend(rule.successor.chars()),
+                None => next.push(self.string[i]),
+            }
+        }
+        self.string = next;
+        Some(out)
+    }
+}
+
 /// Create a transition function that defines an L-system on strings. The transition for each variable symbol must be given. All other symbols are treated as constant symbols.
 ///
 /// Example:
@@ -152,4 +380,45 @@ mod tests {
         Lindenmayer::new(fibonacci).create_iter("_"), 7, "{}", "\n";
         Lindenmayer::new(tribonacci).create_iter("a"), 7, "{}", "\n";
     );
+
+    #[test]
+    fn stochastic() {
+        let productions: &[(char, &[(f64, &str)])] = &[(
+            'F',
+            &[(1.0, "F[+F]F[-F]F"), (1.0, "F[+F]F"), (1.0, "F[-F]F")],
+        )];
+        let a = StochasticLindenmayer::new(productions, 1);
+        let b = StochasticLindenmayer::new(productions, 1);
+        let x: Vec<String> = a.create_iter("F").take(4).collect();
+        let y: Vec<String> = b.create_iter("F").take(4).collect();
+        assert_eq!(x, y);
+
+        // A symbol with only one production is deterministic
+        let c = StochasticLindenmayer::new(&[('a', &[(1.0, "ab")]), ('b', &[(2.0, "a")])], 7);
+        let d = Lindenmayer::new(algae);
+        assert!(c.create_iter("a").take(10).eq(d.create_iter("a").take(10)));
+    }
+
+    #[test]
+    fn context_sensitive() {
+        // A signal propagating to the right
+        let system = ContextLindenmayer::new(
+            &[
+                ContextRule::new("b", 'a', "", "b"),
+                ContextRule::new("", 'b', "", "a"),
+            ],
+            "",
+        );
+        let gens: Vec<String> = system.create_iter("baaaa").take(5).collect();
+        assert_eq!(gens, vec!["baaaa", "abaaa", "aabaa", "aaaba", "aaaab"]);
+
+        // The signal enters branches and skips over them
+        let gens: Vec<String> = system.create_iter("b[a]a").take(2).collect();
+        assert_eq!(gens, vec!["b[a]a", "a[b]b"]);
+
+        // Ignored symbols are skipped and a branch cannot see its siblings as right context
+        let system = ContextLindenmayer::new(&[ContextRule::new("", 'a', "c", "x")], "+-");
+        let gens: Vec<String> = system.create_iter("a+-c a[b]c [a]c").take(2).collect();
+        assert_eq!(gens[1], "x+-c x[b]c [a]c");
+    }
 }
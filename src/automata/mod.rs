@@ -1,9 +1,13 @@
 //! These sequences are related to automata rather than numbers.
 pub mod elementary_cellular_automata;
 pub mod finite_state_machine;
+pub mod fractran;
 pub mod lindenmayer_system;
 pub mod markov_algorithm;
+pub mod morphism;
+pub mod parametric_lindenmayer;
 pub mod playground;
 pub mod pushdown;
 pub mod tag_machine;
 pub mod turing_machine;
+pub mod turtle;
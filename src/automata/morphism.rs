@@ -0,0 +1,366 @@
+use std::{collections::HashSet, hash::Hash};
+
+/// A morphism (substitution) on the alphabet 0..k given by the image of each letter, along with a coding that maps each letter to an output symbol.
+///
+/// ```text
+/// Thue-Morse
+/// 0 -> 01, 1 -> 10
+/// 0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1...
+///
+/// Rudin-Shapiro
+/// 0 -> 01, 1 -> 02, 2 -> 31, 3 -> 32 with coding 0 -> 1, 1 -> 1, 2 -> -1, 3 -> -1
+/// 1, 1, 1, -1, 1, 1, -1, 1, 1, 1, 1, -1, -1, -1, 1, -1, 1, 1, 1, -1...
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Morphism<T> {
+    images: Vec<Vec<usize>>,
+    coding: Vec<T>,
+}
+
+impl Morphism<usize> {
+    /// Morphism where each letter is its own output symbol.
+    /// Panics if any image contains a letter outside of the alphabet.
+    pub fn new(images: Vec<Vec<usize>>) -> Self {
+        let coding = (0..images.len()).collect();
+        Self::with_coding(images, coding)
+    }
+
+    /// 0 -> 01, 1 -> 10
+    pub fn thue_morse() -> Self {
+        Self::new(vec![vec![0, 1], vec![1, 0]])
+    }
+
+    /// 0 -> 01, 1 -> 00
+    pub fn period_doubling() -> Self {
+        Self::new(vec![vec![0, 1], vec![0, 0]])
+    }
+
+    /// 0 -> 01, 1 -> 0
+    pub fn fibonacci() -> Self {
+        Self::new(vec![vec![0, 1], vec![0]])
+    }
+
+    /// 0 -> 01, 1 -> 02, 2 -> 0
+    pub fn tribonacci() -> Self {
+        Self::new(vec![vec![0, 1], vec![0, 2], vec![0]])
+    }
+}
+
+impl<T: Clone> Morphism<T> {
+    /// Morphism where letter i is written out as coding\[i\].
+    /// Panics if any image contains a letter outside of the alphabet or if the coding does not have one symbol per letter.
+    pub fn with_coding(images: Vec<Vec<usize>>, coding: Vec<T>) -> Self {
+        assert_eq!(
+            images.len(),
+            coding.len(),
+            "the coding must give a symbol for every letter"
+        );
+        for image in images.iter() {
+            for letter in image.iter() {
+                assert!(
+                    *letter < images.len(),
+                    "letter {letter} is not in the alphabet"
+                );
+            }
+        }
+        Self { images, coding }
+    }
+
+    /// Number of letters in the alphabet.
+    pub fn alphabet_size(&self) -> usize {
+        self.images.len()
+    }
+
+    /// The image of a letter.
+    pub fn image(&self, letter: usize) -> &[usize] {
+        &self.images[letter]
+    }
+
+    /// Apply the morphism to a word.
+    pub fn apply(&self, word: &[usize]) -> Vec<usize> {
+        word.iter()
+            .flat_map(|letter| self.images[*letter].iter().copied())
+            .collect()
+    }
+
+    /// The word produced by applying the morphism n times to a letter.
+    pub fn iterate(&self, letter: usize, n: usize) -> Vec<usize> {
+        let mut word = vec![letter];
+        for _ in 0..n {
+            word = self.apply(&word);
+        }
+        word
+    }
+
+    /// Write out a word using the coding.
+    pub fn code(&self, word: &[usize]) -> Vec<T> {
+        word.iter()
+            .map(|letter| self.coding[*letter].clone())
+            .collect()
+    }
+
+    /// The incidence matrix of the morphism. Entry (i, j) is the number of times letter i occurs in the image of letter j.
+    pub fn incidence_matrix(&self) -> Vec<Vec<usize>> {
+        let k = self.alphabet_size();
+        let mut matrix = vec![vec![0; k]; k];
+        for (j, image) in self.images.iter().enumerate() {
+            for i in image.iter() {
+                matrix[*i][j] += 1;
+            }
+        }
+        matrix
+    }
+
+    /// Lengths of the images of every letter after one more application of the morphism.
+    fn next_lengths(&self, lengths: &[u64]) -> Vec<u64> {
+        self.images
+            .iter()
+            .map(|image| {
+                image
+                    .iter()
+                    .fold(0_u64, |acc, letter| acc.saturating_add(lengths[*letter]))
+            })
+            .collect()
+    }
+
+    /// Check that the morphism is prolongable on the letter and that the fixed point is infinite.
+    fn assert_prolongable(&self, letter: usize) {
+        assert!(
+            self.images[letter].first() == Some(&letter),
+            "the image of {letter} must begin with {letter}"
+        );
+        // Every letter that is not mortal must survive k applications of the morphism so if the fixed point is infinite the length must increase at that step.
+        let k = self.alphabet_size();
+        let mut lengths = vec![1; k];
+        for _ in 0..k {
+            lengths = self.next_lengths(&lengths);
+        }
+        let a = lengths[letter];
+        let b = self.next_lengths(&lengths)[letter];
+        assert!(b > a, "the fixed point starting with {letter} is finite");
+    }
+
+    /// Iterate over the fixed point of the morphism beginning with the given letter. Terms are produced one at a time without creating whole generations.
+    /// Panics if the image of the letter does not begin with the letter or if the fixed point is finite.
+    pub fn fixed_point(&self, letter: usize) -> MorphicWord<T> {
+        self.assert_prolongable(letter);
+        MorphicWord {
+            morphism: self.clone(),
+            start: letter,
+            level: 0,
+            stack: vec![(letter, 0)],
+        }
+    }
+
+    /// The letter at position n (starting from zero) of the fixed point beginning with the given letter. Only O(log(n)) images need to be examined.
+    /// Panics if the image of the letter does not begin with the letter or if the fixed point is finite.
+    pub fn nth_letter(&self, letter: usize, n: u64) -> usize {
+        self.assert_prolongable(letter);
+
+        // lengths[i][c] is the length of the word produced by applying the morphism i times to c
+        let mut lengths = vec![vec![1_u64; self.alphabet_size()]];
+        while lengths.last().unwrap()[letter] <= n {
+            let next = self.next_lengths(lengths.last().unwrap());
+            lengths.push(next);
+        }
+
+        // Descend through the images, selecting the letter whose image contains position n
+        let mut n = n;
+        let mut cur = letter;
+        for level in (0..lengths.len() - 1).rev() {
+            for c in self.images[cur].iter() {
+                let len = lengths[level][*c];
+                if n < len {
+                    cur = *c;
+                    break;
+                }
+                n -= len;
+            }
+        }
+        cur
+    }
+
+    /// The symbol at position n (starting from zero) of the fixed point beginning with the given letter.
+    /// Panics if the image of the letter does not begin with the letter or if the fixed point is finite.
+    pub fn nth_term(&self, letter: usize, n: u64) -> T {
+        self.coding[self.nth_letter(letter, n)].clone()
+    }
+
+    /// Approximate frequency of each letter in the fixed point beginning with the given letter. These are the limiting proportions of each letter in the iterated images of the letter, normalized to sum to one. For a primitive morphism this is the Perron-Frobenius eigenvector of the incidence matrix.
+    pub fn letter_frequencies(&self, letter: usize) -> Vec<f64> {
+        let matrix = self.incidence_matrix();
+        let k = self.alphabet_size();
+        let mut freqs = vec![0.0; k];
+        freqs[letter] = 1.0;
+        for _ in 0..10_000 {
+            let mut next = vec![0.0; k];
+            for i in 0..k {
+                for j in 0..k {
+                    next[i] += matrix[i][j] as f64 * freqs[j];
+                }
+            }
+            let total: f64 = next.iter().sum();
+            next.iter_mut().for_each(|x| *x /= total);
+            let change: f64 = next
+                .iter()
+                .zip(freqs.iter())
+                .map(|(a, b)| (a - b).abs())
+                .sum();
+            freqs = next;
+            if change < 1e-15 {
+                break;
+            }
+        }
+        freqs
+    }
+}
+
+/// Fixed point of a morphism, produced lazily. Memory use is proportional to the logarithm of the number of terms produced.
+pub struct MorphicWord<T> {
+    morphism: Morphism<T>,
+    start: usize,
+    level: usize,
+    // Path from the root letter, which is at the current level, down to the letter being produced. Each entry has the index of the next child to visit.
+    stack: Vec<(usize, usize)>,
+}
+
+impl<T: Clone> Iterator for MorphicWord<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.stack.len() == self.level + 1 {
+                let (letter, _) = self.stack.pop()?;
+                return Some(self.morphism.coding[letter].clone());
+            }
+            match self.stack.last_mut() {
+                Some((letter, idx)) => {
+                    let image = &self.morphism.images[*letter];
+                    if *idx < image.len() {
+                        let child = image[*idx];
+                        *idx += 1;
+                        self.stack.push((child, 0));
+                    } else {
+                        self.stack.pop();
+                    }
+                }
+                None => {
+                    // The image of the start letter at this level begins with the image at the previous level, which has already been produced.
+                    self.level += 1;
+                    self.stack.push((self.start, 1));
+                }
+            }
+        }
+    }
+}
+
+/// Number of distinct factors (contiguous subwords) of length n in the word.
+pub fn subword_complexity<T: Eq + Hash>(word: &[T], n: usize) -> usize {
+    if n == 0 {
+        return 1;
+    }
+    word.windows(n).collect::<HashSet<&[T]>>().len()
+}
+
+/// Number of distinct factors of each length from 0 to n (inclusive) in the word. The prefix used should be long enough that every factor of length n has already appeared.
+pub fn complexity_function<T: Eq + Hash>(word: &[T], n: usize) -> Vec<usize> {
+    (0..=n).map(|i| subword_complexity(word, i)).collect()
+}
+
+/// Number of times each letter of the alphabet 0..k appears in the word.
+pub fn letter_counts(word: &[usize], k: usize) -> Vec<usize> {
+    let mut counts = vec![0; k];
+    for letter in word.iter() {
+        counts[*letter] += 1;
+    }
+    counts
+}
+
+crate::check_sequences!(
+    Morphism::thue_morse().fixed_point(0), [0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 0, 0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0, 0, 1];
+    Morphism::period_doubling().fixed_point(0), [0, 1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 1, 0, 1, 0, 1, 0, 0, 0, 1];
+    Morphism::fibonacci().fixed_point(0), [0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 0, 1];
+    Morphism::tribonacci().fixed_point(0), [0, 1, 0, 2, 0, 1, 0, 0, 1, 0, 2, 0, 1, 0, 1, 0, 2, 0, 1, 0, 0, 1, 0, 2, 0, 1, 0, 2, 0, 1, 0, 0, 1, 0, 2, 0, 1, 0, 1, 0, 2, 0, 1];
+    Morphism::with_coding(vec![vec![0, 1], vec![0, 2], vec![3, 1], vec![3, 2]], vec![1, 1, -1, -1]).fixed_point(0), [1, 1, 1, -1, 1, 1, -1, 1, 1, 1, 1, -1, -1, -1, 1, -1, 1, 1, 1, -1, 1, 1, -1, 1, -1, -1, -1, 1, 1, 1, -1, 1];
+);
+
+crate::sample_sequences!(
+    Morphism::thue_morse().fixed_point(0);
+    Morphism::fibonacci().fixed_point(0);
+    Morphism::tribonacci().fixed_point(0);
+    Morphism::new(vec![vec![0, 0, 1], vec![1, 1, 0]]).fixed_point(0);
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::thue_morse::ThueMorse;
+
+    #[test]
+    fn nth_letter_agrees_with_iterator() {
+        for m in [
+            Morphism::thue_morse(),
+            Morphism::period_doubling(),
+            Morphism::fibonacci(),
+            Morphism::tribonacci(),
+            // Includes an erasing letter
+            Morphism::new(vec![vec![0, 1, 2], vec![2, 0], vec![]]),
+        ] {
+            for (n, letter) in m.fixed_point(0).take(5000).enumerate() {
+                assert_eq!(letter, m.nth_letter(0, n as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn agrees_with_thue_morse() {
+        let m = Morphism::thue_morse();
+        for (a, b) in m.fixed_point(0).zip(ThueMorse::new()).take(100_000) {
+            assert_eq!(a as i64, b)
+        }
+    }
+
+    #[test]
+    fn distant_terms() {
+        // The Thue-Morse sequence is the parity of the binary weight of n
+        let m = Morphism::thue_morse();
+        for n in [1_000_000_000_u64, 123_456_789_012, 9_876_543_210_987] {
+            assert_eq!(m.nth_letter(0, n), (n.count_ones() % 2) as usize);
+        }
+        // The period doubling sequence is the parity of the 2-adic valuation of n + 1
+        let m = Morphism::period_doubling();
+        for n in [1_000_000_000_u64, 123_456_789_012, 9_876_543_210_987] {
+            assert_eq!(m.nth_letter(0, n), ((n + 1).trailing_zeros() % 2) as usize);
+        }
+    }
+
+    #[test]
+    fn complexity() {
+        // Sturmian words have exactly n + 1 factors of length n
+        let word: Vec<usize> = Morphism::fibonacci().fixed_point(0).take(10_000).collect();
+        assert_eq!(
+            complexity_function(&word, 10),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+        // Arnoux-Rauzy words over three letters have exactly 2n + 1 factors of length n
+        let word: Vec<usize> = Morphism::tribonacci().fixed_point(0).take(10_000).collect();
+        assert_eq!(
+            complexity_function(&word, 10),
+            vec![1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21]
+        );
+    }
+
+    #[test]
+    fn frequencies() {
+        let m = Morphism::fibonacci();
+        let f = m.letter_frequencies(0);
+        let phi = (1.0 + 5.0_f64.sqrt()) / 2.0;
+        assert!((f[0] - 1.0 / phi).abs() < 1e-12);
+        assert!((f[1] - 1.0 / (phi * phi)).abs() < 1e-12);
+
+        let word = m.iterate(0, 20);
+        let counts = letter_counts(&word, 2);
+        assert_eq!(counts, vec![10946, 6765]);
+        assert_eq!(m.incidence_matrix(), vec![vec![1, 1], vec![1, 0]]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt::Display;
+
+/// A symbol of a parametric L-system along with its numeric parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub symbol: char,
+    pub params: Vec<f64>,
+}
+
+impl Module {
+    pub fn new(symbol: char, params: &[f64]) -> Self {
+        Self {
+            symbol,
+            params: params.to_vec(),
+        }
+    }
+
+    /// A module with no parameters.
+    pub fn symbol(symbol: char) -> Self {
+        Self {
+            symbol,
+            params: Vec::new(),
+        }
+    }
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.params.is_empty() {
+            write!(f, "{}", self.symbol)
+        } else {
+            let params: Vec<String> = self.params.iter().map(|p| p.to_string()).collect();
+            write!(f, "{}({})", self.symbol, params.join(","))
+        }
+    }
+}
+
+/// Write a word of modules as a string.
+pub fn modules_to_string(modules: &[Module]) -> String {
+    modules.iter().map(|m| m.to_string()).collect()
+}
+
+type Condition = Box<dyn Fn(&[f64]) -> bool>;
+type Successor = Box<dyn Fn(&[f64]) -> Vec<Module>>;
+
+/// A production for a parametric L-system. Applies to modules with the given symbol whose parameters satisfy the condition and replaces them with the modules produced by the successor function.
+pub struct ParametricRule {
+    symbol: char,
+    condition: Condition,
+    successor: Successor,
+}
+
+impl ParametricRule {
+    pub fn new<C, S>(symbol: char, condition: C, successor: S) -> Self
+    where
+        C: Fn(&[f64]) -> bool + 'static,
+        S: Fn(&[f64]) -> Vec<Module> + 'static,
+    {
+        Self {
+            symbol,
+            condition: Box::new(condition),
+            successor: Box::new(successor),
+        }
+    }
+
+    /// A production with no condition.
+    pub fn unconditional<S>(symbol: char, successor: S) -> Self
+    where
+        S: Fn(&[f64]) -> Vec<Module> + 'static,
+    {
+        Self::new(symbol, |_| true, successor)
+    }
+}
+
+/// A parametric L-system. For each module the first rule that applies is used, modules with no applicable rule are left unchanged.
+pub struct ParametricLindenmayer {
+    rules: Vec<ParametricRule>,
+}
+
+impl ParametricLindenmayer {
+    pub fn new(rules: Vec<ParametricRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Run the automaton on an input.
+    pub fn create_iter(&self, axiom: &[Module]) -> ParametricLindenmayerIter<'_> {
+        ParametricLindenmayerIter {
+            word: axiom.to_vec(),
+            rules: &self.rules,
+        }
+    }
+}
+
+pub struct ParametricLindenmayerIter<'a> {
+    word: Vec<Module>,
+    rules: &'a Vec<ParametricRule>,
+}
+
+impl<'a> Iterator for ParametricLindenmayerIter<'a> {
+    type Item = Vec<Module>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.word.clone();
+        let mut next = Vec::with_capacity(self.word.len());
+        for m in self.word.iter() {
+            match self
+                .rules
+                .iter()
+                .find(|r| r.symbol == m.symbol && (r.condition)(&m.params))
+            {
+                Some(rule) => next.extend((rule.successor)(&m.params)),
+                None => next.push(m.clone()),
+            }
+        }
+        self.word = next;
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditional_productions() {
+        // Example from The Algorithmic Beauty of Plants, section 1.10
+        let system = ParametricLindenmayer::new(vec![
+            ParametricRule::new(
+                'A',
+                |p| p[1] <= 3.0,
+                |p| vec![Module::new('A', &[p[0] * 2.0, p[0] + p[1]])],
+            ),
+            ParametricRule::new(
+                'A',
+                |p| p[1] > 3.0,
+                |p| {
+                    vec![
+                        Module::new('B', &[p[0]]),
+                        Module::new('A', &[p[0] / p[1], 0.0]),
+                    ]
+                },
+            ),
+            ParametricRule::new('B', |p| p[0] < 1.0, |_| vec![Module::symbol('C')]),
+            ParametricRule::new(
+                'B',
+                |p| p[0] >= 1.0,
+                |p| vec![Module::new('B', &[p[0] - 1.0])],
+            ),
+        ]);
+        let gens: Vec<String> = system
+            .create_iter(&[Module::new('B', &[2.0]), Module::new('A', &[4.0, 4.0])])
+            .take(5)
+            .map(|w| modules_to_string(&w))
+            .collect();
+        assert_eq!(
+            gens,
+            vec![
+                "B(2)A(4,4)",
+                "B(1)B(4)A(1,0)",
+                "B(0)B(3)A(2,1)",
+                "CB(2)A(4,3)",
+                "CB(1)A(8,7)"
+            ]
+        );
+    }
+}
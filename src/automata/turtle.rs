@@ -0,0 +1,272 @@
+use crate::automata::parametric_lindenmayer::Module;
+use std::io::Write;
+
+/// A line segment given by its start and end points.
+pub type Segment = ((f64, f64), (f64, f64));
+
+/// A turtle that interprets the output of an L-system as drawing instructions. The turtle starts at the origin facing along the positive x-axis.
+///
+/// ```text
+/// F, G    move forward and draw a line (the symbols can be changed)
+/// f       move forward without drawing
+/// +       turn left
+/// -       turn right
+/// |       turn around
+/// [       save the current position and heading
+/// ]       restore the most recently saved position and heading
+/// ```
+///
+/// All other symbols are ignored. When interpreting modules from a parametric L-system the first parameter, if there is one, replaces the step length or the turning angle.
+pub struct Turtle {
+    step: f64,
+    angle: f64,
+    draw: Vec<char>,
+}
+
+impl Turtle {
+    /// Step length and turning angle in degrees.
+    pub fn new(step: f64, angle: f64) -> Self {
+        Self {
+            step,
+            angle,
+            draw: vec!['F', 'G'],
+        }
+    }
+
+    /// Set which symbols draw a line when moving forward.
+    pub fn with_draw_symbols(mut self, symbols: &str) -> Self {
+        self.draw = symbols.chars().collect();
+        self
+    }
+
+    fn run<I>(&self, instructions: I) -> Vec<Segment>
+    where
+        I: Iterator<Item = (char, Option<f64>)>,
+    {
+        let mut segments = Vec::new();
+        let mut pos = (0.0, 0.0);
+        let mut heading: f64 = 0.0;
+        let mut stack = Vec::new();
+        for (c, param) in instructions {
+            match c {
+                '+' => heading += param.unwrap_or(self.angle),
+                '-' => heading -= param.unwrap_or(self.angle),
+                '|' => heading += 180.0,
+                '[' => stack.push((pos, heading)),
+                ']' => {
+                    if let Some((p, h)) = stack.pop() {
+                        pos = p;
+                        heading = h;
+                    }
+                }
+                'f' => {
+                    let len = param.unwrap_or(self.step);
+                    let (s, c) = heading.to_radians().sin_cos();
+                    pos = (pos.0 + len * c, pos.1 + len * s);
+                }
+                c if self.draw.contains(&c) => {
+                    let len = param.unwrap_or(self.step);
+                    let (s, c) = heading.to_radians().sin_cos();
+                    let next = (pos.0 + len * c, pos.1 + len * s);
+                    segments.push((pos, next));
+                    pos = next;
+                }
+                _ => (),
+            }
+        }
+        segments
+    }
+
+    /// Line segments drawn by following the symbols of the string.
+    pub fn interpret(&self, s: &str) -> Vec<Segment> {
+        self.run(s.chars().map(|c| (c, None)))
+    }
+
+    /// Line segments drawn by following a word produced by a parametric L-system.
+    pub fn interpret_modules(&self, modules: &[Module]) -> Vec<Segment> {
+        self.run(
+            modules
+                .iter()
+                .map(|m| (m.symbol, m.params.first().copied())),
+        )
+    }
+}
+
+/// Smallest and largest x and y coordinates of the segments.
+pub fn bounding_box(segments: &[Segment]) -> ((f64, f64), (f64, f64)) {
+    let mut min = (f64::INFINITY, f64::INFINITY);
+    let mut max = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for (a, b) in segments.iter() {
+        for p in [a, b] {
+            min = (min.0.min(p.0), min.1.min(p.1));
+            max = (max.0.max(p.0), max.1.max(p.1));
+        }
+    }
+    (min, max)
+}
+
+/// An SVG image of the segments. The y-axis is flipped so that the image has the same orientation as the turtle's coordinates.
+pub fn segments_to_svg(segments: &[Segment], stroke_width: f64) -> String {
+    let ((x0, y0), (x1, y1)) = if segments.is_empty() {
+        ((0.0, 0.0), (0.0, 0.0))
+    } else {
+        bounding_box(segments)
+    };
+    let margin = stroke_width * 2.0;
+    let width = x1 - x0 + 2.0 * margin;
+    let height = y1 - y0 + 2.0 * margin;
+
+    let mut path = String::new();
+    let mut last: Option<(f64, f64)> = None;
+    for (a, b) in segments.iter() {
+        if last != Some(*a) {
+            path.push_str(&format!(
+                "M{:.3} {:.3} ",
+                a.0 - x0 + margin,
+                y1 - a.1 + margin
+            ));
+        }
+        path.push_str(&format!(
+            "L{:.3} {:.3} ",
+            b.0 - x0 + margin,
+            y1 - b.1 + margin
+        ));
+        last = Some(*b);
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.3}\" height=\"{height:.3}\" viewBox=\"0 0 {width:.3} {height:.3}\">\n<path d=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"{stroke_width}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n</svg>\n",
+        path.trim_end()
+    )
+}
+
+/// Write an SVG image of the segments to a file.
+pub fn write_svg(path: &str, segments: &[Segment], stroke_width: f64) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(segments_to_svg(segments, stroke_width).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        automata::lindenmayer_system::Lindenmayer, l_system_rules,
+        paperfolding::RegularPaperfolding,
+    };
+    use std::collections::HashSet;
+
+    l_system_rules!(
+        dragon;
+        'F' => "F+G"
+        'G' => "F-G"
+    );
+
+    l_system_rules!(
+        hilbert;
+        'A' => "+BF-AFA-FB+"
+        'B' => "-AF+BFB+FA-"
+    );
+
+    l_system_rules!(
+        koch;
+        'F' => "F+F--F+F"
+    );
+
+    fn lattice_points(segments: &[Segment]) -> Vec<(i64, i64)> {
+        let mut points = vec![(0, 0)];
+        for (_, b) in segments.iter() {
+            points.push((b.0.round() as i64, b.1.round() as i64));
+        }
+        points
+    }
+
+    #[test]
+    fn dragon_curve_turns() {
+        // The turns of the dragon curve follow the regular paperfolding sequence
+        let s = Lindenmayer::new(dragon).create_iter("F").nth(10).unwrap();
+        let turns: Vec<i64> = s
+            .chars()
+            .filter_map(|c| match c {
+                '+' => Some(1),
+                '-' => Some(0),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(turns.len(), 1023);
+        assert!(turns.into_iter().eq(RegularPaperfolding::new().take(1023)));
+
+        // The curve never traces the same segment twice
+        let segments = Turtle::new(1.0, 90.0).interpret(&s);
+        assert_eq!(segments.len(), 1024);
+        let points = lattice_points(&segments);
+        let edges: HashSet<((i64, i64), (i64, i64))> = points
+            .windows(2)
+            .map(|w| (w[0].min(w[1]), w[0].max(w[1])))
+            .collect();
+        assert_eq!(edges.len(), 1024);
+    }
+
+    #[test]
+    fn hilbert_curve_fills_square() {
+        for n in 1..6 {
+            let s = Lindenmayer::new(hilbert).create_iter("A").nth(n).unwrap();
+            let segments = Turtle::new(1.0, 90.0).interpret(&s);
+            let side = 1 << n;
+            assert_eq!(segments.len(), side * side - 1);
+            let points: HashSet<(i64, i64)> = lattice_points(&segments).into_iter().collect();
+            assert_eq!(points.len(), side * side);
+            let ((x0, y0), (x1, y1)) = bounding_box(&segments);
+            assert_eq!((x1 - x0).round() as usize, side - 1);
+            assert_eq!((y1 - y0).round() as usize, side - 1);
+        }
+    }
+
+    #[test]
+    fn branches_and_parameters() {
+        let segments = Turtle::new(1.0, 90.0).interpret("F[+F]F");
+        let points = lattice_points(&segments);
+        assert_eq!(points, vec![(0, 0), (1, 0), (1, 1), (2, 0)]);
+
+        let modules = [
+            Module::new('F', &[3.0]),
+            Module::new('+', &[90.0]),
+            Module::symbol('f'),
+            Module::new('F', &[2.0]),
+        ];
+        let segments = Turtle::new(1.0, 45.0).interpret_modules(&modules);
+        assert_eq!(lattice_points(&segments), vec![(0, 0), (3, 0), (3, 3)]);
+    }
+
+    #[test]
+    fn svg_output() {
+        let svg = segments_to_svg(&Turtle::new(1.0, 90.0).interpret("F+F"), 0.1);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("M0.200 1.200 L1.200 1.200 L1.200 0.200"));
+    }
+
+    #[test]
+    #[ignore = "visualization"]
+    fn draw_curves() {
+        let dir = std::env::temp_dir();
+        let s = Lindenmayer::new(dragon).create_iter("F").nth(12).unwrap();
+        let segments = Turtle::new(1.0, 90.0).interpret(&s);
+        let path = dir.join("dragon_curve.svg");
+        write_svg(path.to_str().unwrap(), &segments, 0.3).unwrap();
+        println!("{}", path.display());
+
+        let s = Lindenmayer::new(hilbert).create_iter("A").nth(6).unwrap();
+        let segments = Turtle::new(1.0, 90.0).interpret(&s);
+        let path = dir.join("hilbert_curve.svg");
+        write_svg(path.to_str().unwrap(), &segments, 0.3).unwrap();
+        println!("{}", path.display());
+
+        let s = Lindenmayer::new(koch)
+            .create_iter("F--F--F")
+            .nth(4)
+            .unwrap();
+        let segments = Turtle::new(1.0, 60.0).interpret(&s);
+        let path = dir.join("koch_snowflake.svg");
+        write_svg(path.to_str().unwrap(), &segments, 0.5).unwrap();
+        println!("{}", path.display());
+    }
+}
@@ -3,6 +3,8 @@ pub mod collatz;
 pub mod heights;
 pub mod lengths;
 pub mod mapping;
+pub mod residue_class_map;
+pub mod scan;
 pub mod trajectories;
 pub mod tree;
 
@@ -11,5 +13,7 @@ pub use collatz::*;
 pub use heights::*;
 pub use lengths::*;
 pub use mapping::*;
+pub use residue_class_map::*;
+pub use scan::*;
 pub use trajectories::*;
 pub use tree::*;
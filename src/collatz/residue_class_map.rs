@@ -0,0 +1,280 @@
+use num::{CheckedAdd, CheckedMul, Integer, ToPrimitive};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A map that applies a different affine function to each residue class. For n congruent to r modulo m the map sends n to (a_r * n + b_r) / c_r, where every division must be exact. This includes the Collatz function and its variants as well as Conway's generalized Collatz functions.
+///
+/// ```text
+/// m = 2
+/// n ≡ 0: (1n + 0) / 2
+/// n ≡ 1: (3n + 1) / 1
+///
+/// n = 7
+/// 7, 22, 11, 34, 17, 52, 26, 13, 40, 20, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1...
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResidueClassMap<T> {
+    modulus: T,
+    rules: Vec<(T, T, T)>,
+}
+
+impl<T: Clone + Integer + ToPrimitive> ResidueClassMap<T> {
+    /// Rule r, given as (a, b, c), applies to numbers congruent to r modulo the modulus.
+    /// Panics if the modulus is not positive, if there is not exactly one rule for each residue class, or if any rule does not always produce an integer.
+    pub fn new(modulus: T, rules: Vec<(T, T, T)>) -> Self {
+        assert!(modulus > T::zero(), "modulus must be positive");
+        assert_eq!(
+            modulus.to_usize(),
+            Some(rules.len()),
+            "there must be exactly one rule for each residue class"
+        );
+        let mut r = T::zero();
+        for (a, b, c) in rules.iter() {
+            assert!(!c.is_zero(), "divisors must be nonzero");
+            // Division is exact for every n = r + km exactly when c divides both a*r + b and a*m
+            assert!(
+                (a.clone() * r.clone() + b.clone()).is_multiple_of(c)
+                    && (a.clone() * modulus.clone()).is_multiple_of(c),
+                "rule for residue class {} does not always produce an integer",
+                r.to_i64().unwrap_or_default()
+            );
+            r = r + T::one();
+        }
+        Self { modulus, rules }
+    }
+
+    /// Apply the map to n. Returns None if the calculation overflows.
+    pub fn apply(&self, n: &T) -> Option<T>
+    where
+        T: CheckedMul + CheckedAdd,
+    {
+        let r = n.mod_floor(&self.modulus).to_usize()?;
+        let (a, b, c) = &self.rules[r];
+        Some(a.checked_mul(n)?.checked_add(b)? / c.clone())
+    }
+
+    /// The trajectory of n under the map.
+    pub fn trajectory(&self, n: T) -> ResidueClassMapIter<'_, T> {
+        ResidueClassMapIter {
+            map: self,
+            n: Some(n),
+        }
+    }
+
+    /// The cycle eventually reached by the trajectory of n, beginning with its least element. Returns None if no value repeats within max_steps steps or if the calculation overflows.
+    pub fn find_cycle(&self, n: T, max_steps: usize) -> Option<Vec<T>>
+    where
+        T: CheckedMul + CheckedAdd + Hash,
+    {
+        let mut positions = HashMap::new();
+        let mut path = Vec::new();
+        let mut val = n;
+        for step in 0..=max_steps {
+            if let Some(start) = positions.get(&val) {
+                return Some(normalize_cycle(path.split_off(*start)));
+            }
+            positions.insert(val.clone(), step);
+            path.push(val.clone());
+            val = self.apply(&val)?;
+        }
+        None
+    }
+
+    /// Every cycle reached by trajectories beginning at the given starting values, sorted by their least element. Trajectories that do not repeat within max_steps steps or that overflow are abandoned.
+    pub fn cycles<I>(&self, starts: I, max_steps: usize) -> Vec<Vec<T>>
+    where
+        I: IntoIterator<Item = T>,
+        T: CheckedMul + CheckedAdd + Hash,
+    {
+        // Any value already seen belongs to a trajectory whose outcome is known
+        let mut seen = HashSet::new();
+        let mut cycles = Vec::new();
+        for start in starts {
+            let mut positions = HashMap::new();
+            let mut path = Vec::new();
+            let mut val = Some(start);
+            // A trajectory cut off by max_steps has an unknown outcome, so its values must not stop later trajectories
+            let mut resolved = false;
+            for step in 0..=max_steps {
+                let v = match val {
+                    Some(v) => v,
+                    None => {
+                        resolved = true;
+                        break;
+                    }
+                };
+                if let Some(start) = positions.get(&v) {
+                    cycles.push(normalize_cycle(path[*start..].to_vec()));
+                    resolved = true;
+                    break;
+                }
+                if seen.contains(&v) {
+                    resolved = true;
+                    break;
+                }
+                positions.insert(v.clone(), step);
+                val = self.apply(&v);
+                path.push(v);
+            }
+            if resolved {
+                seen.extend(path);
+            }
+        }
+        cycles.sort_by(|a, b| a[0].cmp(&b[0]));
+        cycles
+    }
+}
+
+impl<T: Clone + Integer + ToPrimitive + From<i32>> ResidueClassMap<T> {
+    fn from_rules(modulus: i32, rules: &[(i32, i32, i32)]) -> Self {
+        Self::new(
+            T::from(modulus),
+            rules
+                .iter()
+                .map(|(a, b, c)| (T::from(*a), T::from(*b), T::from(*c)))
+                .collect(),
+        )
+    }
+
+    /// n/2 for even n and 3n+1 for odd n. On the negative integers this is equivalent to the 3n-1 map.
+    pub fn collatz() -> Self {
+        Self::from_rules(2, &[(1, 0, 2), (3, 1, 1)])
+    }
+
+    /// n/2 for even n and (3n+1)/2 for odd n.
+    pub fn collatz_shortcut() -> Self {
+        Self::from_rules(2, &[(1, 0, 2), (3, 1, 2)])
+    }
+
+    /// n/2 for even n and 3n-1 for odd n.
+    pub fn three_x_minus_one() -> Self {
+        Self::from_rules(2, &[(1, 0, 2), (3, -1, 1)])
+    }
+
+    /// n/2 for even n and 5n+1 for odd n.
+    pub fn five_x_plus_one() -> Self {
+        Self::from_rules(2, &[(1, 0, 2), (5, 1, 1)])
+    }
+
+    /// Conway's permutation of the integers. 2n/3 for n ≡ 0, (4n-1)/3 for n ≡ 1, and (4n+1)/3 for n ≡ 2 modulo 3.
+    pub fn conway_permutation() -> Self {
+        Self::from_rules(3, &[(2, 0, 3), (4, -1, 3), (4, 1, 3)])
+    }
+}
+
+/// Rotate a cycle so that it begins with its least element.
+fn normalize_cycle<T: Ord>(mut cycle: Vec<T>) -> Vec<T> {
+    let min = cycle
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    cycle.rotate_left(min);
+    cycle
+}
+
+pub struct ResidueClassMapIter<'a, T> {
+    map: &'a ResidueClassMap<T>,
+    n: Option<T>,
+}
+
+impl<'a, T: Clone + Integer + ToPrimitive + CheckedMul + CheckedAdd> Iterator
+    for ResidueClassMapIter<'a, T>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.n.take()?;
+        self.n = self.map.apply(&out);
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Number, collatz::Collatz};
+    use num::BigInt;
+
+    #[test]
+    fn agrees_with_collatz() {
+        let map = ResidueClassMap::<Number>::collatz();
+        for n in [-5, 19, 27] {
+            assert!(
+                map.trajectory(n)
+                    .take(50)
+                    .eq(Collatz::new_big(n).take(50).map(|x| x.to_i64().unwrap()))
+            );
+        }
+    }
+
+    #[test]
+    fn collatz_cycles() {
+        let map = ResidueClassMap::<Number>::collatz();
+        assert_eq!(map.cycles(1..1000, 1000), vec![vec![1, 4, 2]]);
+        assert_eq!(
+            map.cycles(-1000..0, 1000),
+            vec![
+                vec![
+                    -272, -136, -68, -34, -17, -50, -25, -74, -37, -110, -55, -164, -82, -41, -122,
+                    -61, -182, -91
+                ],
+                vec![-20, -10, -5, -14, -7],
+                vec![-2, -1],
+            ]
+        );
+        assert_eq!(map.find_cycle(0, 10), Some(vec![0]));
+        // 7 is cut off before reaching 1 but passes through 20, whose trajectory still finds the cycle
+        assert_eq!(map.cycles([7, 20], 10), vec![vec![1, 4, 2]]);
+        assert_eq!(map.cycles([20, 7], 10), vec![vec![1, 4, 2]]);
+
+        // The same cycles appear for 3n-1 with the signs reversed
+        let map = ResidueClassMap::<Number>::three_x_minus_one();
+        let cycles = map.cycles(1..1000, 1000);
+        assert_eq!(cycles.len(), 3);
+        assert_eq!(cycles[0], vec![1, 2]);
+        assert_eq!(cycles[1], vec![5, 14, 7, 20, 10]);
+        assert_eq!(cycles[2].len(), 18);
+    }
+
+    #[test]
+    fn five_x_plus_one() {
+        let map = ResidueClassMap::<BigInt>::five_x_plus_one();
+        let cycles = map.cycles((1..100).map(BigInt::from), 500);
+        let expected: Vec<Vec<BigInt>> = [
+            vec![1, 6, 3, 16, 8, 4, 2],
+            vec![13, 66, 33, 166, 83, 416, 208, 104, 52, 26],
+            vec![17, 86, 43, 216, 108, 54, 27, 136, 68, 34],
+        ]
+        .into_iter()
+        .map(|c| c.into_iter().map(BigInt::from).collect())
+        .collect();
+        assert_eq!(cycles, expected);
+        // 7 is believed to diverge
+        assert_eq!(map.find_cycle(BigInt::from(7), 1000), None);
+    }
+
+    #[test]
+    fn conway_permutation() {
+        let map = ResidueClassMap::<Number>::conway_permutation();
+        assert!(map.trajectory(1).take(5).eq([1, 1, 1, 1, 1]));
+        assert_eq!(map.find_cycle(2, 100), Some(vec![2, 3]));
+        assert_eq!(map.find_cycle(4, 100), Some(vec![4, 5, 7, 9, 6]));
+        assert_eq!(
+            map.find_cycle(44, 100),
+            Some(vec![44, 59, 79, 105, 70, 93, 62, 83, 111, 74, 99, 66])
+        );
+        // The map is a permutation so each value has exactly one preimage
+        let images: HashSet<Number> = (-300..300).map(|n| map.apply(&n).unwrap()).collect();
+        assert_eq!(images.len(), 600);
+    }
+
+    #[test]
+    #[should_panic]
+    fn inexact_rule() {
+        ResidueClassMap::<Number>::new(2, vec![(1, 0, 2), (3, 0, 2)]);
+    }
+}
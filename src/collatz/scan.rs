@@ -0,0 +1,304 @@
+use num::{BigInt, Integer, One, ToPrimitive};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+/// Measurements of the Collatz trajectory of a positive integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollatzStats<T> {
+    pub start: T,
+    /// Number of steps until the trajectory first falls below the start, also called the glide. Taken to be zero for 1.
+    pub stopping_time: u64,
+    /// Number of steps until the trajectory reaches 1, also called the delay.
+    pub total_stopping_time: u64,
+    /// The largest value in the trajectory.
+    pub max_excursion: T,
+}
+
+/// Starting values that set a new record in the scanned range along with the record value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollatzRecords<T> {
+    /// Records for the total stopping time.
+    pub delay: Vec<(T, u64)>,
+    /// Records for the stopping time.
+    pub glide: Vec<(T, u64)>,
+    /// Records for the maximum excursion.
+    pub path: Vec<(T, T)>,
+}
+
+impl<T> CollatzRecords<T> {
+    fn new() -> Self {
+        Self {
+            delay: Vec::new(),
+            glide: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialOrd> CollatzRecords<T> {
+    fn update(&mut self, stats: &CollatzStats<T>) {
+        if self
+            .delay
+            .last()
+            .is_none_or(|r| stats.total_stopping_time > r.1)
+        {
+            self.delay
+                .push((stats.start.clone(), stats.total_stopping_time));
+        }
+        if self.glide.last().is_none_or(|r| stats.stopping_time > r.1) {
+            self.glide.push((stats.start.clone(), stats.stopping_time));
+        }
+        if self.path.last().is_none_or(|r| stats.max_excursion > r.1) {
+            self.path
+                .push((stats.start.clone(), stats.max_excursion.clone()));
+        }
+    }
+}
+
+// Number of starting values given to the parallel workers at once when searching for records.
+const BLOCK: u64 = 1 << 16;
+
+/// Computes Collatz trajectory statistics for ranges of starting values in parallel. The total stopping time and maximum excursion of every value below the cache size are stored so that trajectories can stop as soon as they fall into the cache.
+///
+/// Values are handled as u128, which is enough for starting values past 2^60, and trajectories that would overflow are reported as None. The BigInt methods have no such limit.
+pub struct CollatzScanner {
+    delays: Vec<u32>,
+    peaks: Vec<u64>,
+    max_steps: u64,
+}
+
+impl CollatzScanner {
+    /// Build the cache for all values less than cache_size.
+    /// Panics if cache_size is zero or greater than 2^32.
+    pub fn new(cache_size: usize) -> Self {
+        assert!(cache_size > 0, "cache_size must be positive");
+        assert!(
+            cache_size <= 1 << 32,
+            "cache_size must not be greater than 2^32"
+        );
+        let mut scanner = Self {
+            delays: Vec::with_capacity(cache_size),
+            peaks: Vec::with_capacity(cache_size),
+            max_steps: u64::MAX,
+        };
+        // Zero has no trajectory to 1 so its entry is never used
+        scanner.delays.push(0);
+        scanner.peaks.push(0);
+        // Each entry only depends on the entries for smaller values
+        for n in 1..cache_size as u128 {
+            let s = scanner
+                .stats(n)
+                .expect("trajectories of small values do not overflow");
+            scanner.delays.push(s.total_stopping_time as u32);
+            scanner.peaks.push(s.max_excursion as u64);
+        }
+        scanner
+    }
+
+    /// Give up on any trajectory that takes more than max_steps steps, reporting it as None.
+    pub fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Total stopping time and maximum excursion of val, if known.
+    fn cached(&self, val: u128) -> Option<(u64, u64)> {
+        if val == 1 {
+            Some((0, 1))
+        } else if val < self.delays.len() as u128 {
+            Some((self.delays[val as usize] as u64, self.peaks[val as usize]))
+        } else {
+            None
+        }
+    }
+
+    /// Trajectory statistics for n. Returns None if the trajectory would overflow a u128 or takes more than the maximum number of steps.
+    /// Panics if n is zero.
+    pub fn stats(&self, n: u128) -> Option<CollatzStats<u128>> {
+        assert!(n > 0, "n must be positive");
+        let mut val = n;
+        let mut steps = 0;
+        let mut peak = n;
+        let mut glide = if n == 1 { Some(0) } else { None };
+        loop {
+            if glide.is_none() && val < n {
+                glide = Some(steps);
+            }
+            if let Some(glide) = glide
+                && let Some((delay, cached_peak)) = self.cached(val)
+            {
+                return Some(CollatzStats {
+                    start: n,
+                    stopping_time: glide,
+                    total_stopping_time: steps + delay,
+                    max_excursion: peak.max(cached_peak as u128),
+                });
+            }
+            if steps >= self.max_steps {
+                return None;
+            }
+            if val.is_even() {
+                val /= 2;
+            } else {
+                val = val.checked_mul(3)?.checked_add(1)?;
+                peak = peak.max(val);
+            }
+            steps += 1;
+        }
+    }
+
+    /// Trajectory statistics for n. Returns None if the trajectory takes more than the maximum number of steps.
+    /// Panics if n is not positive.
+    pub fn stats_big(&self, n: &BigInt) -> Option<CollatzStats<BigInt>> {
+        assert!(*n > BigInt::ZERO, "n must be positive");
+        let mut val = n.clone();
+        let mut steps = 0;
+        let mut peak = n.clone();
+        let mut glide = if n.is_one() { Some(0) } else { None };
+        loop {
+            if glide.is_none() && val < *n {
+                glide = Some(steps);
+            }
+            if let Some(glide) = glide
+                && let Some((delay, cached_peak)) = val.to_u128().and_then(|v| self.cached(v))
+            {
+                return Some(CollatzStats {
+                    start: n.clone(),
+                    stopping_time: glide,
+                    total_stopping_time: steps + delay,
+                    max_excursion: peak.max(BigInt::from(cached_peak)),
+                });
+            }
+            if steps >= self.max_steps {
+                return None;
+            }
+            if val.is_even() {
+                val /= 2;
+            } else {
+                val = val * 3 + 1;
+                if val > peak {
+                    peak = val.clone();
+                }
+            }
+            steps += 1;
+        }
+    }
+
+    /// Statistics for the count values beginning at start, computed in parallel.
+    pub fn scan(&self, start: u128, count: u64) -> Vec<Option<CollatzStats<u128>>> {
+        (0..count)
+            .into_par_iter()
+            .map(|i| self.stats(start + i as u128))
+            .collect()
+    }
+
+    /// Statistics for the count values beginning at start, computed in parallel.
+    pub fn scan_big(&self, start: &BigInt, count: u64) -> Vec<Option<CollatzStats<BigInt>>> {
+        (0..count)
+            .into_par_iter()
+            .map(|i| self.stats_big(&(start + i)))
+            .collect()
+    }
+
+    /// Record holders among the count values beginning at start. Values whose statistics cannot be computed are skipped.
+    pub fn records(&self, start: u128, count: u64) -> CollatzRecords<u128> {
+        let mut records = CollatzRecords::new();
+        for block in (0..count).step_by(BLOCK as usize) {
+            let len = BLOCK.min(count - block);
+            for stats in self.scan(start + block as u128, len).iter().flatten() {
+                records.update(stats);
+            }
+        }
+        records
+    }
+
+    /// Record holders among the count values beginning at start. Values whose statistics cannot be computed are skipped.
+    pub fn records_big(&self, start: &BigInt, count: u64) -> CollatzRecords<BigInt> {
+        let mut records = CollatzRecords::new();
+        for block in (0..count).step_by(BLOCK as usize) {
+            let len = BLOCK.min(count - block);
+            for stats in self.scan_big(&(start + block), len).iter().flatten() {
+                records.update(stats);
+            }
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz::{CollatzHeights, CollatzLength};
+
+    #[test]
+    fn agrees_with_iterators() {
+        let scanner = CollatzScanner::new(1000);
+        let stats = scanner.scan(1, 5000);
+        let lengths = CollatzLength::new().take(5000);
+        let heights = CollatzHeights::new().take(5000);
+        for ((s, l), h) in stats.into_iter().zip(lengths).zip(heights) {
+            let s = s.unwrap();
+            assert_eq!(s.total_stopping_time as i64, l);
+            assert_eq!(s.max_excursion as i64, h);
+        }
+        let big = scanner.scan_big(&BigInt::one(), 5000);
+        for (n, s) in big.into_iter().enumerate() {
+            let s = s.unwrap();
+            let t = scanner.stats(n as u128 + 1).unwrap();
+            assert_eq!(s.total_stopping_time, t.total_stopping_time);
+            assert_eq!(s.stopping_time, t.stopping_time);
+            assert_eq!(s.max_excursion, BigInt::from(t.max_excursion));
+        }
+    }
+
+    #[test]
+    fn records() {
+        let records = CollatzScanner::new(1 << 16).records(1, 1_000_000);
+        let delay: Vec<u128> = records.delay.iter().map(|r| r.0).collect();
+        assert_eq!(
+            delay[..24],
+            [
+                1, 2, 3, 6, 7, 9, 18, 25, 27, 54, 73, 97, 129, 171, 231, 313, 327, 649, 703, 871,
+                1161, 2223, 2463, 2919
+            ]
+        );
+        let glide: Vec<u128> = records.glide.iter().map(|r| r.0).collect();
+        assert_eq!(
+            glide,
+            [
+                1, 2, 3, 7, 27, 703, 10087, 35655, 270271, 362343, 381727, 626331
+            ]
+        );
+        let path: Vec<u128> = records.path.iter().map(|r| r.0).collect();
+        assert_eq!(
+            path[..20],
+            [
+                1, 2, 3, 7, 15, 27, 255, 447, 639, 703, 1819, 4255, 4591, 9663, 20895, 26623,
+                31911, 60975, 77671, 113383
+            ]
+        );
+        assert_eq!(records.delay.last(), Some(&(837799, 524)));
+    }
+
+    #[test]
+    fn large_starts() {
+        let scanner = CollatzScanner::new(1 << 10);
+        // Path record holder whose maximum excursion exceeds 2^125
+        let n = 1980976057694848447_u128;
+        let s = scanner.stats(n).unwrap();
+        assert_eq!(s.max_excursion, 64024667322193133530165877294264738020_u128);
+        let b = scanner.stats_big(&BigInt::from(n)).unwrap();
+        assert_eq!(b.max_excursion, BigInt::from(s.max_excursion));
+        assert_eq!(b.total_stopping_time, s.total_stopping_time);
+
+        // Overflowing a u128 is reported but BigInt has no limit
+        let n = (1_u128 << 127) - 1;
+        assert!(scanner.stats(n).is_none());
+        assert!(scanner.stats_big(&BigInt::from(n)).is_some());
+
+        // Trajectories can be cut off early
+        let scanner = CollatzScanner::new(1).with_max_steps(100);
+        assert!(scanner.stats(27).is_none());
+        assert!(scanner.stats(97).is_none());
+        assert!(scanner.stats(3).is_some());
+    }
+}
@@ -22,6 +22,7 @@ pub mod alternating;
 pub mod combinations;
 pub mod continued_fraction;
 pub mod traits;
+pub mod triangle;
 pub mod unit;
 
 pub use alternating::*;
@@ -44,4 +45,5 @@ pub use rational_digits::*;
 pub use rationals::*;
 pub use recurrence::*;
 pub use roots::*;
+pub use triangle::*;
 pub use unit::*;
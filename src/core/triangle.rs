@@ -0,0 +1,237 @@
+use std::cell::RefCell;
+
+use num::{CheckedAdd, CheckedMul, CheckedSub, Integer, Zero};
+
+/// A number triangle given by an iterator over its rows, which are computed as they are needed and kept for random access.
+/// This is the shape produced by PascalsTriangle, StirlingSecond and many others, so for example the row sums of StirlingSecond::new_big().triangle() are the Bell numbers.
+pub struct Triangle<T> {
+    rows: RefCell<Vec<Vec<T>>>,
+    source: RefCell<Box<dyn Iterator<Item = Vec<T>>>>,
+}
+
+impl<T: Clone + 'static> Triangle<T> {
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Vec<T>> + 'static,
+    {
+        Self {
+            rows: RefCell::new(Vec::new()),
+            source: RefCell::new(Box::new(iter)),
+        }
+    }
+
+    /// A finite triangle with the given rows.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        Self::new(rows.into_iter())
+    }
+
+    /// Row n, or None if the triangle ends before it.
+    pub fn row(&self, n: usize) -> Option<Vec<T>> {
+        let mut rows = self.rows.borrow_mut();
+        while rows.len() <= n {
+            let row = self.source.borrow_mut().next()?;
+            rows.push(row);
+        }
+        Some(rows[n].clone())
+    }
+
+    /// The entry T(n, k), or None if it does not exist.
+    pub fn get(&self, n: usize, k: usize) -> Option<T> {
+        let rows = self.rows.borrow();
+        if let Some(row) = rows.get(n) {
+            return row.get(k).cloned();
+        }
+        drop(rows);
+        self.row(n)?.get(k).cloned()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Vec<T>> + '_ {
+        (0..).map_while(|n| self.row(n))
+    }
+
+    /// The entries read by rows, which is the order used by the OEIS.
+    pub fn flatten(&self) -> impl Iterator<Item = T> + '_ {
+        self.rows().flatten()
+    }
+
+    /// The entries T(n, k) for every row n that has a kth entry.
+    pub fn column(&self, k: usize) -> impl Iterator<Item = T> + '_ {
+        self.rows().filter_map(move |row| row.get(k).cloned())
+    }
+
+    /// The entries T(n + d, n), with the main diagonal at d = 0.
+    pub fn diagonal(&self, d: usize) -> impl Iterator<Item = T> + '_ {
+        (0..).map_while(move |n| self.get(n + d, n))
+    }
+
+    /// All rows, consuming the triangle.
+    pub fn into_rows(self) -> impl Iterator<Item = Vec<T>> {
+        self.rows
+            .into_inner()
+            .into_iter()
+            .chain(self.source.into_inner())
+    }
+
+    /// The triangle with every row reversed.
+    pub fn reversed(self) -> Triangle<T> {
+        Triangle::new(self.into_rows().map(|mut row| {
+            row.reverse();
+            row
+        }))
+    }
+}
+
+impl<T: Clone + Zero + CheckedAdd + 'static> Triangle<T> {
+    pub fn row_sums(&self) -> impl Iterator<Item = T> + '_ {
+        self.rows()
+            .map_while(|row| row.iter().try_fold(T::zero(), |acc, x| acc.checked_add(x)))
+    }
+
+    /// The sums of T(m - k, k) over k for each m, such as the Fibonacci numbers for Pascal's triangle.
+    pub fn antidiagonal_sums(&self) -> impl Iterator<Item = T> + '_ {
+        (0..).map_while(|m| {
+            self.row(m)?;
+            (0..=m).try_fold(T::zero(), |acc, k| match self.get(m - k, k) {
+                Some(x) => acc.checked_add(&x),
+                None => Some(acc),
+            })
+        })
+    }
+}
+
+impl<T: Clone + Integer + CheckedMul + CheckedSub + 'static> Triangle<T> {
+    /// The inverse of the triangle as an infinite lower triangular matrix, whose row n depends only on the first n + 1 rows. Row n must have n + 1 entries.
+    /// The inverse ends at the first row that cannot be computed, because a diagonal entry does not divide exactly or the values overflow.
+    pub fn inverse(self) -> Triangle<T> {
+        let mut rows = self.into_rows();
+        let mut inverse: Vec<Vec<T>> = Vec::new();
+        Triangle::new(std::iter::from_fn(move || {
+            let n = inverse.len();
+            let m = rows.next().filter(|row| row.len() == n + 1)?;
+            if m[n].is_zero() {
+                return None;
+            }
+            // Solve the nth row of M X = I from the previous rows of X
+            let mut out = Vec::with_capacity(n + 1);
+            for k in 0..=n {
+                let mut value = if k == n { T::one() } else { T::zero() };
+                for (a, x) in m[k..n].iter().zip(&inverse[k..]) {
+                    value = value.checked_sub(&a.checked_mul(&x[k])?)?;
+                }
+                let (q, r) = value.div_rem(&m[n]);
+                if !r.is_zero() {
+                    return None;
+                }
+                out.push(q);
+            }
+            inverse.push(out.clone());
+            Some(out)
+        }))
+    }
+}
+
+/// Turn any iterator over rows into a Triangle.
+pub trait IntoTriangle<T> {
+    fn triangle(self) -> Triangle<T>;
+}
+
+impl<T: Clone + 'static, I: Iterator<Item = Vec<T>> + 'static> IntoTriangle<T> for I {
+    fn triangle(self) -> Triangle<T> {
+        Triangle::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bell::Bell;
+    use crate::fibonacci::Fibonacci;
+    use crate::narayana_triangle::NarayanaTriangle;
+    use crate::pascal::PascalsTriangle;
+    use crate::sierpinsky_triangle::SierpinskyTriangle;
+    use crate::stirling::{StirlingFirstSigned, StirlingSecond};
+    use itertools::Itertools;
+
+    #[test]
+    fn views() {
+        let pascal = PascalsTriangle::new().triangle();
+        assert_eq!(pascal.get(4, 2), Some(6));
+        assert_eq!(pascal.get(4, 5), None);
+        assert_eq!(pascal.row(3), Some(vec![1, 3, 3, 1]));
+        assert_eq!(
+            pascal.column(2).take(5).collect_vec(),
+            vec![1, 3, 6, 10, 15]
+        );
+        assert_eq!(
+            pascal.diagonal(1).take(5).collect_vec(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            pascal.row_sums().take(6).collect_vec(),
+            vec![1, 2, 4, 8, 16, 32]
+        );
+        assert_eq!(
+            pascal.antidiagonal_sums().take(10).collect_vec(),
+            Fibonacci::new().skip(1).take(10).collect_vec()
+        );
+        assert!(
+            SierpinskyTriangle::new()
+                .triangle()
+                .flatten()
+                .take(100)
+                .eq(SierpinskyTriangle::flattened().take(100))
+        );
+
+        let finite = Triangle::from_rows(vec![vec![1], vec![2, 3]]);
+        assert_eq!(finite.row_sums().collect_vec(), vec![1, 5]);
+        assert_eq!(finite.get(2, 0), None);
+    }
+
+    #[test]
+    fn relations() {
+        assert!(
+            StirlingSecond::new_big()
+                .triangle()
+                .row_sums()
+                .take(20)
+                .eq(Bell::new_big().take(20))
+        );
+        // The Stirling numbers of the first and second kind are inverse matrices
+        assert!(
+            StirlingSecond::new_big()
+                .triangle()
+                .inverse()
+                .into_rows()
+                .take(12)
+                .eq(StirlingFirstSigned::new_big().take(12))
+        );
+        let inverse_pascal = PascalsTriangle::new_big().triangle().inverse();
+        for n in 0..10 {
+            for (k, x) in inverse_pascal.row(n).unwrap().into_iter().enumerate() {
+                let sign = if (n - k) % 2 == 0 { 1 } else { -1 };
+                assert_eq!(
+                    x,
+                    PascalsTriangle::new_big().triangle().get(n, k).unwrap() * sign
+                );
+            }
+        }
+        assert!(
+            NarayanaTriangle::new_big()
+                .triangle()
+                .reversed()
+                .into_rows()
+                .take(10)
+                .eq(NarayanaTriangle::new_big().take(10))
+        );
+        // The inverse stops where it stops being integral
+        let halves = Triangle::from_rows(vec![vec![1], vec![1, 2]]).inverse();
+        assert_eq!(halves.rows().count(), 1);
+    }
+}
+
+crate::check_sequences!(
+    crate::pascal::PascalsTriangle::new().triangle().flatten(), [1, 1, 1, 1, 2, 1, 1, 3, 3, 1, 1, 4, 6, 4, 1];
+    crate::stirling::StirlingSecond::new_big().triangle().row_sums(), [1, 1, 2, 5, 15, 52, 203, 877, 4140, 21147];
+    crate::stirling::StirlingSecond::new_big().triangle().column(2), [1, 3, 7, 15, 31, 63, 127, 255];
+    crate::pascal::PascalsTriangle::new().triangle().antidiagonal_sums(), [1, 1, 2, 3, 5, 8, 13, 21, 34, 55];
+);
@@ -0,0 +1,854 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use itertools::Itertools;
+use num::{BigInt, BigRational, One, Zero};
+use rayon::prelude::*;
+
+use crate::a005243::{A005243, A048973};
+use crate::a005244::{A005244, A171413};
+use crate::abelian_groups::AbelianGroups;
+use crate::antifibonacci::{AntiFibonacci, NonAntiFibonacci};
+use crate::arithmetic_derivative::ArithmeticDerivative;
+use crate::automata::fractran::PrimeGame;
+use crate::automata::morphism::Morphism;
+use crate::automorphic::{Automorphic, AutomorphicDigits};
+use crate::baum_sweet::BaumSweet;
+use crate::bell::{Bell, BellTriangle};
+use crate::binary_runs::BinaryRuns;
+use crate::binary_weight::BinaryWeight;
+use crate::catalan::Catalan;
+use crate::champernowne::Champernowne;
+use crate::collatz::{
+    Collatz, CollatzHeights, CollatzLength, CollatzMap, CollatzTrajectories, CollatzTree,
+};
+use crate::copeland_erdos::CopelandErdos;
+use crate::core::{
+    Composites, EvenIntegers, Evens, GreatestPrimeFactor, Integers, LeastPrimeFactor, Naturals,
+    NthPowers, NumberOfDistinctPrimeFactors, NumberOfPrimeFactors, OddIntegers, Odds, Parity,
+    Powers, PrimePowers, PrimeSignatures, Primes, Primorial, UnitSequence,
+};
+use crate::curzon::Curzon;
+use crate::de_bruijn::DeBruijn;
+use crate::derangement::Derangements;
+use crate::digital_product::{DigitalProds, MultiplicativeDigitalRoots, MultiplicativePersistence};
+use crate::digital_sum::{AdditivePersistence, DigitalRoots, DigitalSums};
+use crate::divisor::{
+    Abundance, Abundant, AliquotSequence, AliquotSums, Deficient, DivisorSummatory, Duffian,
+    HighlyAbundant, HighlyComposite, LargelyComposite, NumberOfDivisors, PrimitiveAbundant,
+    Refactorable, Sigma, SumOfDivisors, Superabundant,
+};
+use crate::ecg::Ecg;
+use crate::euclid_mullin::{EuclidMullinLargest, EuclidMullinSmallest};
+use crate::evil_and_odious::{Evil, Odious};
+use crate::factorial::{AlternatingFactorial, DoubleFactorial, Factorial};
+use crate::fermat::Fermat;
+use crate::fibbinary::Fibbinary;
+use crate::fibonacci::{Fibonacci, FibonacciWord};
+use crate::figurate::{
+    CenteredPolygonal, Cube, GeneralizedPentagonal, GeneralizedPolygonal, Oblong, Polygonal,
+    Square, SquareTriangular, Tetrahedral, Triangular,
+};
+use crate::figure_figure::FigureFigure;
+use crate::fly_straight::{A255140, FlyStraight};
+use crate::forest_fire::ForestFire;
+use crate::fortunate::Fortunate;
+use crate::golden_rectangle::GoldenRectangle;
+use crate::golomb::Golomb;
+use crate::gray::Gray;
+use crate::guess::SequenceTransform;
+use crate::hofstadter_g::HofstadterG;
+use crate::hofstadter_h::HofstadterH;
+use crate::hofstadter_q::HofstadterQ;
+use crate::hyperfactorial::HyperFactorial;
+use crate::hyperprimorial::HyperPrimorial;
+use crate::hypotenuse::{Hypotenuse, Nonhypotenuse};
+use crate::jordan_polya::JordanPolya;
+use crate::juggler::Juggler;
+use crate::lattice_paths::{LatticePaths, PathCounts, StatisticTriangle, peaks};
+use crate::leonardo::Leonardo;
+use crate::liouville::Liouville;
+use crate::look_and_say::LookAndSay;
+use crate::lucas::Lucas;
+use crate::lucas_sequence::{LucasU, LucasV};
+use crate::lucky::{Lucky, Unlucky};
+use crate::ludic::{Ludic, NonLudic};
+use crate::mersenne::Mersenne;
+use crate::mobius::{Mertens, Mobius};
+use crate::mountain::Mountain;
+use crate::narayana_general::NarayanaGeneral;
+use crate::narayana_triangle::NarayanaTriangle;
+use crate::narayanas_cows::NarayanasCows;
+use crate::odd_part::OddPart;
+use crate::ore_numbers::Ore;
+use crate::padic_valuation::{PadicValuation, PadicValuationRational};
+use crate::paperfolding::RegularPaperfolding;
+use crate::partition::Partition;
+use crate::pascal::{BernoullisTriangle, PascalsTriangle};
+use crate::pell::{CompanionPell, Pell};
+use crate::period_doubling::PeriodDoubling;
+use crate::permutation::{EulerianTriangle, MahonianTriangle, PatternAvoiding};
+use crate::perrin_padovan::{Padovan, Perrin};
+use crate::pi_mu::{PiMu, ReverseAndIncrement};
+use crate::pisano::PisanoPeriod;
+use crate::powerful::{Achilles, Powerful};
+use crate::prime_counting::PrimeCounting;
+use crate::prime_gaps::PrimeGaps;
+use crate::pseudoprime::{EulerPseudoprimes, FermatPseudoprimes};
+use crate::recaman::Recaman;
+use crate::repint::Repint;
+use crate::restricted_partitions::{
+    Overpartition, PartitionRestriction, PlanePartition, RestrictedPartition,
+};
+use crate::rngs::{Blum, BlumBlumShubMaximum, BlumBlumShubPrimes};
+use crate::rowland::{Rowland, RowlandPrime, RowlandPrimeGenerating};
+use crate::rueppel::Rueppel;
+use crate::ruler::Ruler;
+use crate::safe_primes::{SafePrimes, SophieGermainPrimes};
+use crate::semiprime::{AlmostPrime, Semiprime};
+use crate::set_partitions::{NoncrossingPartitionsN, SetPartitionsN};
+use crate::sierpinsky_triangle::SierpinskyTriangle;
+use crate::smooth::{Regular, Smooth};
+use crate::squarefree::{Radicals, Squarefree, Squareful};
+use crate::stirling::{StirlingFirst, StirlingFirstSigned, StirlingSecond};
+use crate::sum_prime_factors::{SumOfDistinctPrimeFactors, SumOfPrimeFactors};
+use crate::sylvester::Sylvester;
+use crate::taxicab::Taxicab;
+use crate::thue_morse::ThueMorse;
+use crate::totient::{
+    Cototients, EvenNontotient, HighlyTotient, JordanTotients, Nontotient, ReducedTotient,
+    TotientCount, TotientRange, Totients,
+};
+use crate::transforms::BinomialTransform;
+use crate::twin_primes::{TwinPrimesGreater, TwinPrimesLesser, TwinPrimesMiddle};
+
+// Terms kept for each sequence unless its entry says otherwise
+const TERMS: usize = 100;
+
+type Constructor = fn() -> Box<dyn Iterator<Item = BigInt>>;
+
+/// A sequence of the crate that is included in the index, named by the expression that constructs it.
+pub struct Entry {
+    pub name: &'static str,
+    constructor: Constructor,
+    terms: usize,
+}
+
+impl Entry {
+    /// The terms kept in the index, fewer if the sequence ends or overflows first.
+    pub fn terms(&self) -> Vec<BigInt> {
+        (self.constructor)().take(self.terms).collect()
+    }
+}
+
+// Each sequence may be followed by the number of terms to keep, for those that grow or run too slowly for the default or whose later terms are not reliable
+macro_rules! entries {
+    ($($seq:expr $(; $terms:literal)?),+ $(,)?) => {
+        &[$(Entry {
+            name: stringify!($seq),
+            constructor: || Box::new($seq.map(BigInt::from)),
+            terms: entries!(@terms $($terms)?),
+        }),+]
+    };
+    (@terms) => {
+        TERMS
+    };
+    (@terms $terms:literal) => {
+        $terms
+    };
+}
+
+/// Every sequence in the index. Triangles are read by rows as in the OEIS, and families that take a parameter appear with the parameters the OEIS lists them under. The integer sequences of the crate that are left out are listed in EXCLUDED.
+pub const ENTRIES: &[Entry] = entries![
+    A005243::new_big(),
+    A048973::new_big(),
+    A005244::new_big(),
+    A171413::new_big(),
+    AbelianGroups::new(),
+    AntiFibonacci::new_big(),
+    NonAntiFibonacci::new_big(),
+    ArithmeticDerivative::new(),
+    PrimeGame::new(); 12,
+    Morphism::tribonacci().fixed_point(0),
+    Automorphic::new_big(5, 10); 30,
+    Automorphic::new_big(6, 10); 30,
+    AutomorphicDigits::new_big(5, 10),
+    AutomorphicDigits::new_big(6, 10),
+    BaumSweet::new_big(),
+    Bell::new_big(),
+    BellTriangle::new_big().flatten(),
+    BinaryRuns::new(),
+    BinaryWeight::new(),
+    Catalan::new_big(),
+    Champernowne::new_big(10),
+    Collatz::new_big(27),
+    CollatzHeights::new_big(),
+    CollatzLength::new_big(),
+    CollatzMap::new_big(),
+    CollatzMap::reduced_big(),
+    CollatzTrajectories::new_big().flatten(),
+    CollatzTree::new_big().flatten(),
+    CopelandErdos::new_big(10),
+    Composites::new_big(),
+    Integers::new_big(),
+    Naturals::new_big(),
+    NthPowers::new_big(4),
+    NthPowers::new_big(5),
+    Evens::new_big(),
+    Odds::new_big(),
+    EvenIntegers::new_big(),
+    OddIntegers::new_big(),
+    Parity::new_big(),
+    Powers::new_big(3),
+    GreatestPrimeFactor::new(),
+    LeastPrimeFactor::new(),
+    PrimeSignatures::new().flatten(),
+    NumberOfPrimeFactors::new(),
+    NumberOfDistinctPrimeFactors::new(),
+    Primes::new_big(),
+    PrimePowers::new_big(),
+    Primorial::new_big(),
+    UnitSequence::new_big(),
+    Curzon::new_big(2),
+    DeBruijn::new_big(2); 12,
+    Derangements::new_big(),
+    DigitalProds::new_big(10),
+    MultiplicativeDigitalRoots::new_big(10),
+    MultiplicativePersistence::new_big(10),
+    DigitalSums::new_big(10),
+    DigitalRoots::new_big(10),
+    AdditivePersistence::new_big(10),
+    Abundance::new(),
+    Abundant::new(),
+    PrimitiveAbundant::new(),
+    HighlyAbundant::new(),
+    Superabundant::new(); 30,
+    AliquotSums::new(),
+    AliquotSequence::new(276); 40,
+    Deficient::new(),
+    Duffian::new(),
+    HighlyComposite::new(); 20,
+    LargelyComposite::new(); 40,
+    NumberOfDivisors::new(),
+    DivisorSummatory::new(),
+    Refactorable::new(),
+    Sigma::new(2),
+    SumOfDivisors::new(),
+    Ecg::new_big(),
+    EuclidMullinSmallest::new(),
+    EuclidMullinLargest::new(),
+    Evil::new_big(),
+    Odious::new_big(),
+    Factorial::new_big(),
+    AlternatingFactorial::new_big(),
+    DoubleFactorial::new_big(),
+    Fermat::new_big(); 12,
+    Fibbinary::new(),
+    Fibonacci::new_big(),
+    FibonacciWord::new_big(),
+    CenteredPolygonal::new_big(3),
+    CenteredPolygonal::new_big(4),
+    CenteredPolygonal::new_big(5),
+    CenteredPolygonal::new_big(6),
+    Cube::new_big(),
+    GeneralizedPentagonal::new_big(),
+    GeneralizedPolygonal::new_big(6),
+    Oblong::new_big(),
+    Polygonal::new_big(5),
+    Polygonal::new_big(6),
+    Square::new_big(),
+    SquareTriangular::new_big(); 40,
+    Tetrahedral::new_big(),
+    Triangular::new_big(),
+    FigureFigure::new_big(),
+    FlyStraight::new_big(),
+    A255140::new_big(),
+    ForestFire::new_big(),
+    Fortunate::new(); 15,
+    GoldenRectangle::new_big(),
+    Golomb::new(),
+    Gray::new_big(),
+    HofstadterG::new(),
+    HofstadterH::new(),
+    HofstadterQ::new(),
+    HyperFactorial::new_big(); 30,
+    HyperPrimorial::new_big(); 30,
+    Hypotenuse::new(),
+    Nonhypotenuse::new(),
+    JordanPolya::new(),
+    Juggler::new_big(37),
+    PathCounts::new_big(LatticePaths::motzkin); 40,
+    PathCounts::new_big(LatticePaths::schroder); 40,
+    PathCounts::new_big(|n| LatticePaths::delannoy(n, n)); 40,
+    StatisticTriangle::new_big(LatticePaths::motzkin, peaks).flatten(); 45,
+    Leonardo::new_big(),
+    Liouville::new_big(),
+    LookAndSay::new_big(); 20,
+    Lucas::new_big(),
+    LucasU::new_big(1, -2),
+    LucasV::new_big(1, -2),
+    LucasU::new_big(2, -1),
+    LucasV::new_big(2, -1),
+    Lucky::new_big(),
+    Unlucky::new_big(),
+    Ludic::new_big(),
+    NonLudic::new_big(),
+    Mersenne::new_big(),
+    Mobius::new(),
+    Mertens::new(),
+    Mountain::new_big(),
+    NarayanaGeneral::new_big(3),
+    NarayanaTriangle::new_big().flatten(),
+    NarayanasCows::new_big(),
+    OddPart::new_big(),
+    Ore::new(); 16,
+    PadicValuation::new_big(2),
+    PadicValuation::new_big(3),
+    PadicValuationRational::new_big(2),
+    RegularPaperfolding::new_big(),
+    Partition::new_big(),
+    PascalsTriangle::new_big().flatten(),
+    BernoullisTriangle::new_big().flatten(),
+    Pell::new_big(),
+    CompanionPell::new_big(),
+    PeriodDoubling::new_big(),
+    EulerianTriangle::new_big().flatten(),
+    MahonianTriangle::new_big().flatten(),
+    PatternAvoiding::new(&[0, 1, 2, 3]); 8,
+    PatternAvoiding::new(&[0, 2, 3, 1]); 8,
+    PatternAvoiding::new(&[1, 0, 3, 2]); 8,
+    Perrin::new_big(),
+    Padovan::new_big(),
+    ReverseAndIncrement::new_big(),
+    PiMu::new_big(),
+    PisanoPeriod::new(),
+    Powerful::new(),
+    Achilles::new(),
+    PrimeCounting::new_big(),
+    PrimeGaps::new_big(),
+    FermatPseudoprimes::new(2),
+    EulerPseudoprimes::new(2),
+    Recaman::new_big(),
+    Repint::new_big(1, 10),
+    PlanePartition::new_big(),
+    Overpartition::new_big(),
+    RestrictedPartition::new_big(PartitionRestriction::Distinct),
+    RestrictedPartition::new_big(PartitionRestriction::Odd),
+    RestrictedPartition::new_big(PartitionRestriction::AtMostParts(3)),
+    Blum::new(),
+    BlumBlumShubPrimes::new(),
+    BlumBlumShubMaximum::new(),
+    Rowland::new_big(7),
+    RowlandPrimeGenerating::new_big(7),
+    RowlandPrime::new_big(7); 30,
+    Rueppel::new_big(),
+    Ruler::new_big(),
+    SafePrimes::new_big(),
+    SophieGermainPrimes::new_big(),
+    Semiprime::new(),
+    AlmostPrime::new(3),
+    (0..).map(|n| SetPartitionsN::new(n).count()); 11,
+    (0..).map(|n| NoncrossingPartitionsN::new(n).count()); 12,
+    SierpinskyTriangle::new().flatten(),
+    Regular::new_big(),
+    Smooth::new_big(3),
+    Smooth::new_big(7),
+    Radicals::new(),
+    Squarefree::new_big(),
+    Squareful::new_big(),
+    StirlingFirst::new_big().flatten(),
+    StirlingFirstSigned::new_big().flatten(),
+    StirlingSecond::new_big().flatten(),
+    SumOfPrimeFactors::new(),
+    SumOfDistinctPrimeFactors::new(),
+    Sylvester::new_big(); 12,
+    Taxicab::new(); 10,
+    ThueMorse::new_big(),
+    Totients::new(),
+    Cototients::new(),
+    Nontotient::new(),
+    EvenNontotient::new(),
+    HighlyTotient::new(); 11,
+    JordanTotients::new(2),
+    ReducedTotient::new(),
+    TotientCount::new(),
+    TotientRange::new(),
+    TwinPrimesLesser::new(),
+    TwinPrimesGreater::new(),
+    TwinPrimesMiddle::new(),
+];
+
+/// The integer sequences of the crate left out of ENTRIES on purpose, each named by its type or by its whole module, with the reason.
+pub const EXCLUDED: &[(&str, &str)] = &[
+    (
+        "rngs",
+        "random number generators, whose output depends on a seed (the Blum integers and the primes for Blum-Blum-Shub are indexed)",
+    ),
+    ("transforms", "transforms of a sequence given by the caller"),
+    (
+        "Arithmetic",
+        "any arithmetic progression, chosen by the caller",
+    ),
+    (
+        "Multiples",
+        "the multiples of a number chosen by the caller",
+    ),
+    (
+        "Geometric",
+        "any geometric progression, chosen by the caller",
+    ),
+    ("Constant", "a single value repeated"),
+    ("Polynomial", "any polynomial, chosen by the caller"),
+    (
+        "RationalDigits",
+        "the digits of a rational chosen by the caller",
+    ),
+    (
+        "AdditiveLinearRecurrence",
+        "any recurrence, chosen by the caller",
+    ),
+    ("AdditiveRecurrence", "any recurrence, chosen by the caller"),
+    (
+        "CollatzGeneral",
+        "constants and a start chosen by the caller",
+    ),
+    ("ResidueClassMapIter", "a map chosen by the caller"),
+    ("FractranIter", "a program chosen by the caller"),
+    (
+        "MorphicWord",
+        "a morphism chosen by the caller (the tribonacci word is indexed)",
+    ),
+    (
+        "Paperfolding",
+        "folds chosen by the caller (the regular folding is indexed)",
+    ),
+    ("Ducci", "a starting tuple chosen by the caller"),
+    (
+        "EuclideanRhythm",
+        "a short finite word for parameters chosen by the caller",
+    ),
+    (
+        "WeylMarsaglia",
+        "a periodic permutation for parameters chosen by the caller",
+    ),
+    (
+        "Combinations",
+        "rows are combinations of items rather than rows of a triangle",
+    ),
+    (
+        "Factoradic",
+        "rows are the digits of numbers rather than rows of a triangle",
+    ),
+    ("FibonacciLucky", "not implemented yet"),
+];
+
+/// Which differences are allowed between the terms searched for and a run of an indexed sequence.
+/// With scale and offset both set the terms may be any affine image of the run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// The terms may be the negatives of the run.
+    pub sign: bool,
+    /// The terms may be a nonzero rational multiple of the run.
+    pub scale: bool,
+    /// The terms may differ from the run by a constant.
+    pub offset: bool,
+}
+
+/// A run of an indexed sequence that matches the terms searched for, which equal scale times the run plus offset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchMatch {
+    pub name: String,
+    /// The index within the sequence of the first term of the run.
+    pub position: usize,
+    pub scale: BigRational,
+    pub offset: BigRational,
+}
+
+/// The first terms of every sequence in ENTRIES, which can be searched without access to the OEIS.
+pub struct SequenceIndex {
+    sequences: Vec<(String, Vec<BigInt>)>,
+}
+
+impl SequenceIndex {
+    /// Compute the terms of every entry, in parallel.
+    pub fn build() -> Self {
+        Self {
+            sequences: ENTRIES
+                .par_iter()
+                .map(|entry| (entry.name.to_string(), entry.terms()))
+                .collect(),
+        }
+    }
+
+    /// The index, built the first time it is needed and then shared.
+    pub fn global() -> &'static Self {
+        static INDEX: OnceLock<SequenceIndex> = OnceLock::new();
+        INDEX.get_or_init(Self::build)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sequences.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The indexed terms of the named sequence.
+    pub fn terms(&self, name: &str) -> Option<&[BigInt]> {
+        self.sequences
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, terms)| terms.as_slice())
+    }
+
+    /// Write the index as text with one sequence per line, its name and its terms separated by tabs.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        for (name, terms) in &self.sequences {
+            writeln!(out, "{name}\t{}", terms.iter().join("\t"))?;
+        }
+        out.flush()
+    }
+
+    /// Read an index written by save.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let invalid = |line: &str| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid index line: {line}"),
+            )
+        };
+        let mut sequences = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let mut fields = line.split('\t');
+            let name = fields.next().ok_or_else(|| invalid(&line))?;
+            let terms = fields
+                .filter(|field| !field.is_empty())
+                .map(|field| field.parse::<BigInt>().map_err(|_| invalid(&line)))
+                .collect::<Result<Vec<_>, _>>()?;
+            sequences.push((name.to_string(), terms));
+        }
+        Ok(Self { sequences })
+    }
+
+    /// Every indexed sequence with a contiguous run that matches the terms under the options, at the first position where it does. Nothing matches an empty list.
+    /// Short lists match many sequences, especially when scaling and offsets are allowed since any two terms are an affine image of any other two that differ.
+    pub fn search(&self, terms: &[BigInt], options: SearchOptions) -> Vec<SearchMatch> {
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        self.sequences
+            .iter()
+            .filter_map(|(name, sequence)| {
+                sequence
+                    .windows(terms.len())
+                    .enumerate()
+                    .find_map(|(position, run)| {
+                        let (scale, offset) = relate(run, terms, options)?;
+                        Some(SearchMatch {
+                            name: name.clone(),
+                            position,
+                            scale,
+                            offset,
+                        })
+                    })
+            })
+            .collect()
+    }
+
+    /// Search for sequences that the terms are a transform of, by searching for the inverse transform of the terms.
+    /// The partial sums and differences may start anywhere in a sequence, while the binomial transform must start at its beginning.
+    /// The terms are the differences of a run only up to a constant, so offsets are always allowed for Diffs.
+    pub fn search_transformed(
+        &self,
+        terms: &[BigInt],
+        options: SearchOptions,
+    ) -> Vec<(SequenceTransform, SearchMatch)> {
+        let mut out = Vec::new();
+        let mut record = |transform, matches: Vec<SearchMatch>| {
+            out.extend(matches.into_iter().map(|m| (transform, m)));
+        };
+        record(SequenceTransform::Identity, self.search(terms, options));
+        if terms.len() > 1 {
+            let diffs = terms
+                .iter()
+                .tuple_windows()
+                .map(|(a, b)| b - a)
+                .collect_vec();
+            record(SequenceTransform::PartialSums, self.search(&diffs, options));
+        }
+        let sums = std::iter::once(BigInt::zero())
+            .chain(terms.iter().scan(BigInt::zero(), |sum, t| {
+                *sum += t;
+                Some(sum.clone())
+            }))
+            .collect_vec();
+        let with_offset = SearchOptions {
+            offset: true,
+            ..options
+        };
+        record(SequenceTransform::Diffs, self.search(&sums, with_offset));
+        // The binomial transform is an involution
+        let owned = terms.to_vec();
+        let inverse = BinomialTransform::new(owned.into_iter()).collect_vec();
+        let starts = self
+            .search(&inverse, options)
+            .into_iter()
+            .filter(|m| m.position == 0)
+            .collect();
+        record(SequenceTransform::BinomialTransform, starts);
+        out
+    }
+}
+
+// The scale and offset relating the run to the terms, if the options allow one
+// Relations are checked by cross multiplying integers, and rationals are only made for a match, as many terms are huge
+fn relate(
+    run: &[BigInt],
+    terms: &[BigInt],
+    options: SearchOptions,
+) -> Option<(BigRational, BigRational)> {
+    let rational = |x: &BigInt| BigRational::from_integer(x.clone());
+    if options.scale {
+        // A reference term of the run, whose change from the base term fixes the scale
+        let (base, reference) = if options.offset {
+            match (1..run.len()).find(|&i| run[i] != run[0]) {
+                Some(i) => (Some(0), i),
+                None => {
+                    return terms
+                        .iter()
+                        .all(|t| *t == terms[0])
+                        .then(|| (BigRational::one(), rational(&(&terms[0] - &run[0]))));
+                }
+            }
+        } else {
+            match (0..run.len()).find(|&i| !run[i].is_zero()) {
+                Some(i) => (None, i),
+                None => {
+                    return terms
+                        .iter()
+                        .all(|t| t.is_zero())
+                        .then(|| (BigRational::one(), BigRational::zero()));
+                }
+            }
+        };
+        let zero = BigInt::zero();
+        let (r0, q0) = match base {
+            Some(b) => (&run[b], &terms[b]),
+            None => (&zero, &zero),
+        };
+        let dr = &run[reference] - r0;
+        let dq = &terms[reference] - q0;
+        if dq.is_zero() {
+            return None;
+        }
+        let related = run
+            .iter()
+            .zip(terms)
+            .all(|(r, q)| (q - q0) * &dr == (r - r0) * &dq);
+        if !related {
+            return None;
+        }
+        let scale = BigRational::new(dq, dr);
+        let offset = rational(q0) - &scale * rational(r0);
+        return Some((scale, offset));
+    }
+    let signs: &[i64] = if options.sign { &[1, -1] } else { &[1] };
+    signs.iter().find_map(|&s| {
+        let offset = if options.offset {
+            &terms[0] - &run[0] * s
+        } else {
+            BigInt::zero()
+        };
+        run.iter()
+            .zip(terms)
+            .all(|(r, q)| *q == r * s + &offset)
+            .then(|| (BigRational::from_integer(s.into()), rational(&offset)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn big(terms: &[i64]) -> Vec<BigInt> {
+        terms.iter().map(|&t| BigInt::from(t)).collect()
+    }
+
+    fn names(matches: &[SearchMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn index() {
+        let index = SequenceIndex::global();
+        assert_eq!(index.len(), ENTRIES.len());
+        assert!(index.names().all_unique());
+        // Some sequences end or overflow early, but none should be nearly empty
+        for (name, terms) in &index.sequences {
+            assert!(!name.contains(['\t', '\n']), "{name} cannot be saved");
+            assert!(terms.len() >= 8, "{name} has only {} terms", terms.len());
+        }
+        assert_eq!(
+            index.terms("Catalan::new_big()").unwrap()[..6],
+            big(&[1, 1, 2, 5, 14, 42])
+        );
+        assert_eq!(index.terms("Fermat::new_big()").unwrap().len(), 12);
+
+        let path = std::env::temp_dir().join(format!("sequence_index_{}.txt", std::process::id()));
+        index.save(&path).unwrap();
+        let loaded = SequenceIndex::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.sequences, index.sequences);
+    }
+
+    // Every public type with an Iterator implementation over integers, or over rows of integers, along with its module
+    fn integer_sequences() -> Vec<(String, String)> {
+        let item = Regex::new(
+            r"impl(?:<[^{]*?>)?\s+Iterator\s+for\s+(\w+)[^{]*\{\s*type Item\s*=\s*(?:T|Number|UNumber|BigInt|u8|u32|u64|usize|Vec<(?:T|Number|BigInt)>);",
+        )
+        .unwrap();
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut dirs = vec![src.clone()];
+        let mut out = Vec::new();
+        while let Some(dir) = dirs.pop() {
+            for path in std::fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()) {
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let module = path.strip_prefix(&src).unwrap().iter().next().unwrap();
+                let module = module.to_str().unwrap().trim_end_matches(".rs").to_string();
+                let text = std::fs::read_to_string(&path).unwrap();
+                for caps in item.captures_iter(&text) {
+                    let name = caps[1].to_string();
+                    if text.contains(&format!("pub struct {name}")) {
+                        out.push((module.clone(), name));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn every_sequence_is_indexed() {
+        let word = Regex::new(r"(\w+)::").unwrap();
+        let indexed: Vec<&str> = ENTRIES
+            .iter()
+            .flat_map(|e| {
+                word.captures_iter(e.name)
+                    .map(|c| c.get(1).unwrap().as_str())
+            })
+            .collect();
+        let excluded: Vec<&str> = EXCLUDED.iter().map(|(name, _)| *name).collect();
+        let sequences = integer_sequences();
+        assert!(sequences.len() > ENTRIES.len());
+        let missing = sequences
+            .iter()
+            .filter(|(module, name)| {
+                !indexed.contains(&name.as_str())
+                    && !excluded.contains(&name.as_str())
+                    && !excluded.contains(&module.as_str())
+            })
+            .map(|(_, name)| name)
+            .collect_vec();
+        assert!(
+            missing.is_empty(),
+            "add these to ENTRIES or EXCLUDED: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn search() {
+        let index = SequenceIndex::global();
+        let exact = index.search(&big(&[2, 5, 14, 42, 132, 429]), SearchOptions::default());
+        assert_eq!(
+            names(&exact),
+            vec![
+                "Catalan::new_big()",
+                "(0..).map(|n| NoncrossingPartitionsN::new(n).count())"
+            ]
+        );
+        assert_eq!(exact[0].position, 2);
+        assert!(index.search(&[], SearchOptions::default()).is_empty());
+
+        let negated = big(&[-13, -21, -34, -55, -89, -144]);
+        assert!(index.search(&negated, SearchOptions::default()).is_empty());
+        let sign = SearchOptions {
+            sign: true,
+            ..Default::default()
+        };
+        let matches = index.search(&negated, sign);
+        assert!(names(&matches).contains(&"Fibonacci::new_big()"));
+
+        let scaled = big(&[22, 26, 34, 38, 46, 58, 62, 74]);
+        let scale = SearchOptions {
+            scale: true,
+            ..Default::default()
+        };
+        let m = index
+            .search(&scaled, scale)
+            .into_iter()
+            .find(|m| m.name == "Primes::new_big()")
+            .unwrap();
+        assert_eq!((m.position, m.scale.to_integer()), (4, BigInt::from(2)));
+
+        // 3n^2 + 1
+        let affine = big(&[28, 49, 76, 109, 148, 193]);
+        let both = SearchOptions {
+            scale: true,
+            offset: true,
+            ..Default::default()
+        };
+        let m = index
+            .search(&affine, both)
+            .into_iter()
+            .find(|m| m.name == "Square::new_big()")
+            .unwrap();
+        assert_eq!(m.position, 3);
+        assert_eq!(m.scale, BigRational::from_integer(3.into()));
+        assert_eq!(m.offset, BigRational::from_integer(1.into()));
+    }
+
+    #[test]
+    fn transformed() {
+        let index = SequenceIndex::global();
+        let found = |terms: &[i64], transform| {
+            index
+                .search_transformed(&big(terms), SearchOptions::default())
+                .into_iter()
+                .filter(|(t, _)| *t == transform)
+                .map(|(_, m)| m.name)
+                .collect_vec()
+        };
+        // Running totals whose differences are the primes from 7
+        assert!(
+            found(&[5, 12, 23, 36, 53, 72, 95], SequenceTransform::PartialSums)
+                .contains(&"Primes::new_big()".to_string())
+        );
+        // Differences of the Bell numbers
+        assert!(
+            found(&[1, 3, 10, 37, 151, 674], SequenceTransform::Diffs)
+                .contains(&"Bell::new_big()".to_string())
+        );
+        // The binomial transform of the Catalan numbers
+        let catalan = Catalan::new_big().take(10).collect_vec();
+        let transformed = BinomialTransform::new(catalan.into_iter())
+            .map(|x| x.try_into().unwrap())
+            .collect_vec();
+        assert!(
+            found(&transformed, SequenceTransform::BinomialTransform)
+                .contains(&"Catalan::new_big()".to_string())
+        );
+    }
+}
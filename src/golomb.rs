@@ -1,16 +1,15 @@
+/// Golomb's sequence. Each n appears a(n) times and the sequence is nondecreasing.
+///
+/// ```text
+/// 1, 2, 2, 3, 3, 4, 4, 4, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 7, 8, 8, 8, 8, 9, 9, 9, 9, 9...
+/// ```
 pub struct Golomb {
-    ctrs: Vec<u64>,
-    idx: usize,
-    ctr: u64,
+    terms: Vec<u64>,
 }
 
 impl Golomb {
     pub fn new() -> Self {
-        Self {
-            ctrs: vec![],
-            idx: 0,
-            ctr: 1,
-        }
+        Self { terms: vec![] }
     }
 }
 
@@ -18,7 +17,14 @@ impl Iterator for Golomb {
     type Item = u64;
 
     fn next(&mut self) -> Option<Self::Item> {
-        todo!()
+        // a(n + 1) = 1 + a(n + 1 - a(a(n))) with the terms indexed from one
+        let n = self.terms.len();
+        let out = match self.terms.last() {
+            None => 1,
+            Some(&last) => 1 + self.terms[n - self.terms[last as usize - 1] as usize],
+        };
+        self.terms.push(out);
+        Some(out)
     }
 }
 
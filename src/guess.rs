@@ -0,0 +1,736 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+use num::{BigInt, BigRational, Integer, One, Signed, Zero};
+
+use crate::catalan::Catalan;
+use crate::core::primes::Primes;
+use crate::transforms::{BinomialTransform, Diffs, PartialSums};
+use crate::utils::polynomial::Polynomial;
+
+// Equations beyond the number of unknowns that a fit must also satisfy before it is believed
+const SPARE: usize = 3;
+
+// Matching a transform needs at least this many terms
+const MIN_MATCH: usize = 6;
+
+// Terms of a transformed known sequence may be skipped before the match begins
+const MAX_OFFSET: usize = 3;
+
+type KnownSequence = (&'static str, fn() -> Box<dyn Iterator<Item = BigInt>>);
+
+/// The sequences whose transforms are compared against the terms.
+pub const KNOWN_SEQUENCES: &[KnownSequence] = &[
+    ("Catalan", || Box::new(Catalan::new_big())),
+    ("Fibonacci", || {
+        Box::new(crate::fibonacci::Fibonacci::new_big())
+    }),
+    ("Lucas", || Box::new(crate::lucas::Lucas::new_big())),
+    ("Pell", || Box::new(crate::pell::Pell::new_big())),
+    ("Bell", || Box::new(crate::bell::Bell::new_big())),
+    ("Factorial", || {
+        Box::new(crate::factorial::Factorial::new_big())
+    }),
+    ("Derangements", || {
+        Box::new(crate::derangement::Derangements::new_big())
+    }),
+    ("Partition", || {
+        Box::new(crate::partition::Partition::new_big())
+    }),
+    ("Primes", || Box::new(Primes::new_big())),
+    ("Motzkin", || {
+        Box::new(crate::lattice_paths::PathCounts::new_big(
+            crate::lattice_paths::LatticePaths::motzkin,
+        ))
+    }),
+];
+
+/// A transform from the transforms module applied to a known sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequenceTransform {
+    Identity,
+    PartialSums,
+    BinomialTransform,
+    Diffs,
+}
+
+impl SequenceTransform {
+    fn apply(&self, terms: Vec<BigInt>) -> Vec<BigInt> {
+        match self {
+            Self::Identity => terms,
+            Self::PartialSums => PartialSums::new(terms.into_iter()).collect(),
+            Self::BinomialTransform => BinomialTransform::new(terms.into_iter()).collect(),
+            Self::Diffs => Diffs::new(terms.into_iter()).collect(),
+        }
+    }
+}
+
+/// An explanation of a list of terms a(0), a(1), a(2)... Every fit also holds for several terms beyond those needed to find it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Guess {
+    /// a(n) = p(n)
+    Polynomial(Polynomial<BigRational>),
+    /// a(n) = c_1 a(n-1) + c_2 a(n-2) + ... + c_d a(n-d) for n >= d, with the coefficients c_1, c_2...
+    LinearRecurrence(Vec<BigRational>),
+    /// The sum of a(n) x^n is numerator(x) / denominator(x), with a constant term of one in the denominator.
+    RationalGf {
+        numerator: Polynomial<BigRational>,
+        denominator: Polynomial<BigRational>,
+    },
+    /// denominator(n) a(n+1) = numerator(n) a(n)
+    Hypergeometric {
+        numerator: Polynomial<BigRational>,
+        denominator: Polynomial<BigRational>,
+    },
+    /// p_0(n) a(n) + p_1(n) a(n+1) + ... + p_r(n) a(n+r) = 0, with the polynomials p_0, p_1...
+    PRecursive(Vec<Polynomial<BigRational>>),
+    /// The terms are a transform of a known sequence, starting from the given term of the transform.
+    Transform {
+        transform: SequenceTransform,
+        sequence: &'static str,
+        offset: usize,
+    },
+}
+
+impl Guess {
+    /// The count terms following the given terms according to the guess, or None if a term cannot be computed or is not an integer.
+    pub fn extend(&self, terms: &[BigInt], count: usize) -> Option<Vec<BigInt>> {
+        let mut all = terms.to_vec();
+        let len = terms.len();
+        match self {
+            Self::Transform {
+                transform,
+                sequence,
+                offset,
+            } => {
+                let (_, f) = KNOWN_SEQUENCES.iter().find(|(name, _)| name == sequence)?;
+                let known = f().take(offset + len + count + 1).collect_vec();
+                let out = transform
+                    .apply(known)
+                    .into_iter()
+                    .skip(offset + len)
+                    .take(count)
+                    .collect_vec();
+                return (out.len() == count).then_some(out);
+            }
+            Self::Polynomial(p) => {
+                for n in len..len + count {
+                    all.push(to_integer(p.eval(&rational(n)))?);
+                }
+            }
+            Self::LinearRecurrence(c) => {
+                for n in len..len + count {
+                    let value: BigRational = c
+                        .iter()
+                        .enumerate()
+                        .map(|(i, ci)| ci * BigRational::from_integer(all[n - i - 1].clone()))
+                        .sum();
+                    all.push(to_integer(value)?);
+                }
+            }
+            Self::RationalGf { denominator, .. } => {
+                for n in len..len + count {
+                    let value: BigRational = denominator
+                        .iter()
+                        .enumerate()
+                        .skip(1)
+                        .filter(|(i, _)| *i <= n)
+                        .map(|(i, qi)| qi * BigRational::from_integer(all[n - i].clone()))
+                        .sum();
+                    all.push(to_integer(-value)?);
+                }
+            }
+            Self::Hypergeometric {
+                numerator,
+                denominator,
+            } => {
+                for n in len..len + count {
+                    let x = rational(n - 1);
+                    let q = denominator.eval(&x);
+                    if q.is_zero() {
+                        return None;
+                    }
+                    let value =
+                        numerator.eval(&x) / q * BigRational::from_integer(all[n - 1].clone());
+                    all.push(to_integer(value)?);
+                }
+            }
+            Self::PRecursive(p) => {
+                let r = p.len() - 1;
+                for n in len..len + count {
+                    let x = rational(n - r);
+                    let lead = p[r].eval(&x);
+                    if lead.is_zero() {
+                        return None;
+                    }
+                    let value: BigRational = p[..r]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, pi)| {
+                            pi.eval(&x) * BigRational::from_integer(all[n - r + i].clone())
+                        })
+                        .sum();
+                    all.push(to_integer(-value / lead)?);
+                }
+            }
+        }
+        Some(all.split_off(len))
+    }
+}
+
+// A polynomial shown in the variable n
+fn in_n(p: &Polynomial<BigRational>) -> String {
+    p.to_string().replace('x', "n")
+}
+
+impl Display for Guess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Polynomial(p) => write!(f, "a(n) = {}", in_n(p)),
+            Self::LinearRecurrence(c) => {
+                let terms = c
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, ci)| !ci.is_zero())
+                    .map(|(i, ci)| format!("{ci}*a(n-{})", i + 1))
+                    .join(" + ");
+                write!(f, "a(n) = {terms}")
+            }
+            Self::RationalGf {
+                numerator,
+                denominator,
+            } => write!(f, "sum of a(n) x^n = ({numerator}) / ({denominator})"),
+            Self::Hypergeometric {
+                numerator,
+                denominator,
+            } => write!(
+                f,
+                "({}) a(n+1) = ({}) a(n)",
+                in_n(denominator),
+                in_n(numerator)
+            ),
+            Self::PRecursive(p) => {
+                let terms = p
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pi)| !is_zero(pi))
+                    .map(|(i, pi)| match i {
+                        0 => format!("({}) a(n)", in_n(pi)),
+                        _ => format!("({}) a(n+{i})", in_n(pi)),
+                    })
+                    .join(" + ");
+                write!(f, "{terms} = 0")
+            }
+            Self::Transform {
+                transform,
+                sequence,
+                offset,
+            } => {
+                write!(f, "{transform:?} of {sequence}")?;
+                if *offset > 0 {
+                    write!(f, " from term {offset}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Polynomial::new keeps a single zero coefficient
+fn is_zero(p: &Polynomial<BigRational>) -> bool {
+    p.iter().all(|c| c.is_zero())
+}
+
+fn rational(n: usize) -> BigRational {
+    BigRational::from_integer(BigInt::from(n))
+}
+
+fn to_integer(x: BigRational) -> Option<BigInt> {
+    x.is_integer().then(|| x.to_integer())
+}
+
+// The Mersenne prime 2^61 - 1
+const MODULUS: u128 = (1 << 61) - 1;
+
+fn pow_mod(mut b: u128, mut e: u128) -> u128 {
+    let mut out = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            out = out * b % MODULUS;
+        }
+        b = b * b % MODULUS;
+        e >>= 1;
+    }
+    out
+}
+
+// The rank of the matrix over the integers modulo a large prime, which is never more than its rank over the rationals
+// Eliminating modulo the prime first avoids the slow exact elimination for the many systems that have no solution
+fn rank_mod_p(rows: &[Vec<BigRational>], cols: usize) -> usize {
+    let modulus = BigInt::from(MODULUS);
+    let reduce_mod = |x: &BigInt| -> u128 { x.mod_floor(&modulus).try_into().unwrap() };
+    // Without an inverse for some denominator nothing is known
+    if rows.iter().flatten().any(|x| reduce_mod(x.denom()) == 0) {
+        return 0;
+    }
+    let mut m = rows
+        .iter()
+        .map(|row| {
+            row[..cols]
+                .iter()
+                .map(|x| {
+                    let d = reduce_mod(x.denom());
+                    reduce_mod(x.numer()) * pow_mod(d, MODULUS - 2) % MODULUS
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+    let mut rank = 0;
+    for c in 0..cols {
+        let Some(p) = (rank..m.len()).find(|&i| m[i][c] != 0) else {
+            continue;
+        };
+        m.swap(rank, p);
+        let inv = pow_mod(m[rank][c], MODULUS - 2);
+        let pivot_row = m[rank].iter().map(|x| x * inv % MODULUS).collect_vec();
+        for row in m[rank + 1..].iter_mut() {
+            let factor = row[c];
+            if factor != 0 {
+                for (x, y) in row.iter_mut().zip(&pivot_row) {
+                    *x = (*x + MODULUS - factor * y % MODULUS) % MODULUS;
+                }
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+// Gauss-Jordan elimination in place, returning the pivot column of each nonzero row
+fn reduce(rows: &mut [Vec<BigRational>], cols: usize) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    for c in 0..cols {
+        let r = pivots.len();
+        let Some(p) = (r..rows.len()).find(|&i| !rows[i][c].is_zero()) else {
+            continue;
+        };
+        rows.swap(r, p);
+        let inv = rows[r][c].recip();
+        for x in rows[r].iter_mut() {
+            *x = &*x * &inv;
+        }
+        let pivot_row = rows[r].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != r && !row[c].is_zero() {
+                let factor = row[c].clone();
+                for (x, y) in row.iter_mut().zip(&pivot_row) {
+                    *x -= &factor * y;
+                }
+            }
+        }
+        pivots.push(c);
+    }
+    pivots
+}
+
+// A nonzero solution of the homogeneous system, taken from the first free column
+fn kernel_vector(mut rows: Vec<Vec<BigRational>>, cols: usize) -> Option<Vec<BigRational>> {
+    if rank_mod_p(&rows, cols) == cols {
+        return None;
+    }
+    let pivots = reduce(&mut rows, cols);
+    let free = (0..cols).find(|c| !pivots.contains(c))?;
+    let mut v = vec![BigRational::zero(); cols];
+    v[free] = BigRational::one();
+    for (row, &c) in rows.iter().zip(&pivots) {
+        v[c] = -row[free].clone();
+    }
+    Some(v)
+}
+
+// A solution of the system whose last column is the right hand side, with free variables set to zero
+fn solve(mut rows: Vec<Vec<BigRational>>, cols: usize) -> Option<Vec<BigRational>> {
+    if rank_mod_p(&rows, cols + 1) == cols + 1 {
+        return None;
+    }
+    let pivots = reduce(&mut rows, cols + 1);
+    if pivots.contains(&cols) {
+        return None;
+    }
+    let mut v = vec![BigRational::zero(); cols];
+    for (row, &c) in rows.iter().zip(&pivots) {
+        v[c] = row[cols].clone();
+    }
+    Some(v)
+}
+
+// Scale to coprime integers with the last nonzero entry positive
+fn primitive(v: Vec<BigRational>) -> Vec<BigRational> {
+    let lcm = v.iter().fold(BigInt::one(), |acc, x| acc.lcm(x.denom()));
+    let ints = v.iter().map(|x| (x * &lcm).to_integer()).collect_vec();
+    let mut gcd = ints.iter().fold(BigInt::zero(), |acc, x| acc.gcd(x));
+    if ints
+        .iter()
+        .rev()
+        .find(|x| !x.is_zero())
+        .is_some_and(|x| x.is_negative())
+    {
+        gcd = -gcd;
+    }
+    if gcd.is_zero() {
+        return v;
+    }
+    ints.into_iter()
+        .map(|x| BigRational::from_integer(x / &gcd))
+        .collect()
+}
+
+/// The polynomial of least degree through all the terms, found from their finite differences.
+pub fn fit_polynomial(terms: &[BigInt]) -> Option<Polynomial<BigRational>> {
+    // leading holds the first entry of each row of differences
+    let mut row = terms
+        .iter()
+        .map(|t| BigRational::from_integer(t.clone()))
+        .collect_vec();
+    let mut leading = Vec::new();
+    while row.iter().any(|x| !x.is_zero()) {
+        leading.push(row[0].clone());
+        row = row.iter().tuple_windows().map(|(a, b)| b - a).collect();
+    }
+    if row.len() < SPARE {
+        return None;
+    }
+    // Newton's forward difference formula with the binomial coefficients C(n, j)
+    let mut out = Polynomial::new(vec![BigRational::zero()]);
+    let mut binomial = Polynomial::new(vec![BigRational::one()]);
+    for (j, d) in leading.into_iter().enumerate() {
+        out = out + binomial.clone() * Polynomial::new(vec![d]);
+        let next = rational(j + 1);
+        binomial = binomial * Polynomial::new(vec![-rational(j) / &next, next.recip()]);
+    }
+    Some(out)
+}
+
+/// The linear recurrence with constant coefficients and least order that holds from the start of the terms.
+pub fn fit_linear_recurrence(terms: &[BigInt]) -> Option<Vec<BigRational>> {
+    let a = terms
+        .iter()
+        .map(|t| BigRational::from_integer(t.clone()))
+        .collect_vec();
+    (1..)
+        .take_while(|d| a.len() >= 2 * d + SPARE)
+        .find_map(|d| {
+            let rows = (d..a.len())
+                .map(|n| {
+                    (1..=d)
+                        .map(|i| a[n - i].clone())
+                        .chain([a[n].clone()])
+                        .collect()
+                })
+                .collect();
+            // A zero last coefficient means a lower order recurrence that only holds after some exceptional terms
+            solve(rows, d).filter(|c| !c[d - 1].is_zero())
+        })
+}
+
+/// A rational generating function with the least total degree, whose denominator has constant term one.
+pub fn fit_rational_gf(
+    terms: &[BigInt],
+) -> Option<(Polynomial<BigRational>, Polynomial<BigRational>)> {
+    let a = terms
+        .iter()
+        .map(|t| BigRational::from_integer(t.clone()))
+        .collect_vec();
+    let at = |k: usize, i: usize| match k.checked_sub(i) {
+        Some(j) => a[j].clone(),
+        None => BigRational::zero(),
+    };
+    for s in 1..a.len() {
+        for e in 1..=s {
+            // The numerator has degree m and the coefficients of x^k in Q A vanish above it
+            let m = s - e;
+            if a.len() < m + 1 + e + SPARE {
+                continue;
+            }
+            let rows = (m + 1..a.len())
+                .map(|k| (1..=e).map(|i| at(k, i)).chain([-a[k].clone()]).collect())
+                .collect();
+            let Some(q) = solve(rows, e) else {
+                continue;
+            };
+            let denominator = std::iter::once(BigRational::one()).chain(q).collect_vec();
+            let numerator = (0..=m)
+                .map(|k| {
+                    denominator
+                        .iter()
+                        .enumerate()
+                        .map(|(i, qi)| qi * at(k, i))
+                        .sum()
+                })
+                .collect();
+            return Some((Polynomial::new(numerator), Polynomial::new(denominator)));
+        }
+    }
+    None
+}
+
+/// Polynomials p and q of least degree with q(n) a(n+1) = p(n) a(n), returned as (p, q).
+pub fn fit_hypergeometric(
+    terms: &[BigInt],
+) -> Option<(Polynomial<BigRational>, Polynomial<BigRational>)> {
+    fit_p_recursive_with(terms, 1).map(|mut p| {
+        let q = p.pop().unwrap();
+        (-p.pop().unwrap(), q)
+    })
+}
+
+/// A recurrence with polynomial coefficients of order at least two, with the fewest unknown coefficients.
+pub fn fit_p_recursive(terms: &[BigInt]) -> Option<Vec<Polynomial<BigRational>>> {
+    fit_p_recursive_with(terms, 2)
+}
+
+// Search orders from min_order upwards, except that order one is searched alone as the hypergeometric case
+fn fit_p_recursive_with(
+    terms: &[BigInt],
+    min_order: usize,
+) -> Option<Vec<Polynomial<BigRational>>> {
+    let a = terms
+        .iter()
+        .map(|t| BigRational::from_integer(t.clone()))
+        .collect_vec();
+    let max_order = if min_order == 1 { 1 } else { a.len() };
+    // Candidate (order, degree) pairs ordered by the number of unknowns
+    let candidates = (min_order..=max_order)
+        .flat_map(|r| (0..a.len()).map(move |d| (r, d)))
+        .filter(|(r, d)| a.len() >= r + (r + 1) * (d + 1) - 1 + SPARE)
+        .sorted_by_key(|(r, d)| ((r + 1) * (d + 1), *r));
+    for (r, d) in candidates {
+        let rows = (0..a.len() - r)
+            .map(|n| {
+                let x = rational(n);
+                (0..=r)
+                    .flat_map(|i| {
+                        let x = x.clone();
+                        let ai = a[n + i].clone();
+                        (0..=d).map(move |j| num::pow(x.clone(), j) * &ai)
+                    })
+                    .collect()
+            })
+            .collect();
+        let Some(v) = kernel_vector(rows, (r + 1) * (d + 1)) else {
+            continue;
+        };
+        let v = primitive(v);
+        let p = v
+            .chunks(d + 1)
+            .map(|c| Polynomial::new(c.to_vec()))
+            .collect_vec();
+        if is_zero(&p[r]) {
+            continue;
+        }
+        return Some(p);
+    }
+    None
+}
+
+/// A transform of a known sequence that begins with the terms, within a few terms of its start.
+pub fn match_transform(terms: &[BigInt]) -> Option<(SequenceTransform, &'static str, usize)> {
+    if terms.len() < MIN_MATCH {
+        return None;
+    }
+    let transforms = [
+        SequenceTransform::Identity,
+        SequenceTransform::PartialSums,
+        SequenceTransform::BinomialTransform,
+        SequenceTransform::Diffs,
+    ];
+    for transform in transforms {
+        for (name, f) in KNOWN_SEQUENCES {
+            let known = transform.apply(f().take(terms.len() + MAX_OFFSET + 1).collect());
+            if let Some(offset) = (0..=MAX_OFFSET)
+                .find(|&k| known.len() >= k + terms.len() && known[k..k + terms.len()] == *terms)
+            {
+                return Some((transform, name, offset));
+            }
+        }
+    }
+    None
+}
+
+/// Try to explain the terms a(0), a(1), a(2)... by, in order, a polynomial, a linear recurrence, a rational generating function, a hypergeometric ratio, a P-recursive recurrence and finally a transform of a known sequence.
+/// The first explanation found is returned, each kind is tried from its simplest form up to what the number of terms can confirm.
+pub fn guess<T: Clone + Into<BigInt>>(terms: &[T]) -> Option<Guess> {
+    let terms = terms.iter().cloned().map(Into::into).collect_vec();
+    if let Some(p) = fit_polynomial(&terms) {
+        return Some(Guess::Polynomial(p));
+    }
+    if let Some(c) = fit_linear_recurrence(&terms) {
+        return Some(Guess::LinearRecurrence(c));
+    }
+    if let Some((numerator, denominator)) = fit_rational_gf(&terms) {
+        return Some(Guess::RationalGf {
+            numerator,
+            denominator,
+        });
+    }
+    if let Some((numerator, denominator)) = fit_hypergeometric(&terms) {
+        return Some(Guess::Hypergeometric {
+            numerator,
+            denominator,
+        });
+    }
+    if let Some(p) = fit_p_recursive(&terms) {
+        return Some(Guess::PRecursive(p));
+    }
+    match_transform(&terms).map(|(transform, sequence, offset)| Guess::Transform {
+        transform,
+        sequence,
+        offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bell::Bell;
+    use crate::factorial::Factorial;
+    use crate::fibonacci::Fibonacci;
+    use crate::lattice_paths::{LatticePaths, PathCounts};
+
+    fn big(terms: &[i64]) -> Vec<BigInt> {
+        terms.iter().map(|&t| BigInt::from(t)).collect()
+    }
+
+    fn ratios(v: &[i64]) -> Vec<BigRational> {
+        v.iter()
+            .map(|&x| BigRational::from_integer(x.into()))
+            .collect()
+    }
+
+    // Check that a guess made from the first terms predicts the rest
+    fn predicts(terms: &[BigInt], known: usize) -> Guess {
+        let g = guess(&terms[..known]).unwrap();
+        assert_eq!(
+            g.extend(&terms[..known], terms.len() - known).unwrap(),
+            terms[known..],
+            "{g}"
+        );
+        g
+    }
+
+    #[test]
+    fn polynomials() {
+        let triangular = (0..20).map(|n| n * (n + 1) / 2).collect_vec();
+        let g = guess(&triangular).unwrap();
+        assert_eq!(
+            g,
+            Guess::Polynomial(Polynomial::new(vec![
+                BigRational::zero(),
+                BigRational::new(1.into(), 2.into()),
+                BigRational::new(1.into(), 2.into())
+            ]))
+        );
+        assert_eq!(g.to_string(), "a(n) = 1/2n + 1/2n^2");
+        predicts(
+            &big(&(0..30).map(|n| n * n * n - 7 * n + 3).collect_vec()),
+            10,
+        );
+        // A cubic is not confirmed by five terms
+        assert_eq!(fit_polynomial(&big(&[1, 2, 5, 12, 27])), None);
+        assert_eq!(
+            guess(&[0; 10]),
+            Some(Guess::Polynomial(Polynomial::new(
+                vec![BigRational::zero()]
+            )))
+        );
+    }
+
+    #[test]
+    fn recurrences() {
+        let fibonacci = Fibonacci::new_big().take(30).collect_vec();
+        let g = predicts(&fibonacci, 12);
+        assert_eq!(g, Guess::LinearRecurrence(ratios(&[1, 1])));
+        assert_eq!(g.to_string(), "a(n) = 1*a(n-1) + 1*a(n-2)");
+        // 2^n + n has a rational generating function with denominator (1 - 2x)(1 - x)^2
+        let g = predicts(&big(&(0..30).map(|n| (1 << n) + n).collect_vec()), 15);
+        assert_eq!(g, Guess::LinearRecurrence(ratios(&[4, -5, 2])));
+        // Exceptional initial terms need a numerator of higher degree
+        let mut terms = big(&[5, 7]);
+        terms.extend(Fibonacci::new_big().take(25));
+        let g = predicts(&terms, 15);
+        let Guess::RationalGf {
+            numerator,
+            denominator,
+        } = &g
+        else {
+            panic!("{g}")
+        };
+        assert_eq!(denominator.coef, ratios(&[1, -1, -1]));
+        assert_eq!(numerator.coef, ratios(&[5, 2, -12, -6]));
+    }
+
+    #[test]
+    fn holonomic() {
+        // The factorials and Catalan numbers have ratios n + 1 and (4n + 2) / (n + 2)
+        let g = predicts(&Factorial::new_big().take(30).collect_vec(), 12);
+        assert_eq!(
+            g,
+            Guess::Hypergeometric {
+                numerator: Polynomial::new(ratios(&[1, 1])),
+                denominator: Polynomial::new(ratios(&[1])),
+            }
+        );
+        let g = predicts(&Catalan::new_big().take(30).collect_vec(), 12);
+        assert_eq!(g.to_string(), "(2 + n) a(n+1) = (2 + 4n) a(n)");
+        // The Motzkin numbers satisfy (n + 4) a(n+2) = (2n + 5) a(n+1) + (3n + 3) a(n)
+        let motzkin = PathCounts::new_big(LatticePaths::motzkin)
+            .take(30)
+            .collect_vec();
+        let g = predicts(&motzkin, 20);
+        assert_eq!(
+            g,
+            Guess::PRecursive(vec![
+                Polynomial::new(ratios(&[-3, -3])),
+                Polynomial::new(ratios(&[-5, -2])),
+                Polynomial::new(ratios(&[4, 1])),
+            ])
+        );
+    }
+
+    #[test]
+    fn transforms() {
+        let primes = Primes::new_big().take(40).collect_vec();
+        let sums = PartialSums::new(primes.clone().into_iter())
+            .skip(2)
+            .take(30)
+            .collect_vec();
+        let g = guess(&sums).unwrap();
+        assert_eq!(
+            g,
+            Guess::Transform {
+                transform: SequenceTransform::PartialSums,
+                sequence: "Primes",
+                offset: 2
+            }
+        );
+        assert_eq!(g.to_string(), "PartialSums of Primes from term 2");
+        assert_eq!(
+            g.extend(&sums, 3).unwrap(),
+            PartialSums::new(primes.into_iter())
+                .skip(32)
+                .take(3)
+                .collect_vec()
+        );
+        // The Bell numbers are not P-recursive so only the table of known sequences finds them
+        let bell = Bell::new_big().skip(1).take(25).collect_vec();
+        assert_eq!(
+            guess(&bell),
+            Some(Guess::Transform {
+                transform: SequenceTransform::Identity,
+                sequence: "Bell",
+                offset: 1
+            })
+        );
+        assert_eq!(guess(&big(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3])), None);
+    }
+}
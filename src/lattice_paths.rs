@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use num::{BigInt, CheckedAdd, Integer, One, Zero};
+
+use crate::Number;
+
+/// A step of a lattice path, moving dx to the right and dy up.
+pub type Step = (i64, i64);
+
+/// The lattice paths from the origin to a given point that use steps from a given set, optionally never going below the x-axis.
+/// The familiar families are available as presets: Dyck, Motzkin and Schroder paths return to the x-axis, ballot paths may end above it and Delannoy paths cross a rectangle.
+///
+/// ```text
+/// LatticePaths::dyck(3)
+/// [(1, 1), (1, 1), (1, 1), (1, -1), (1, -1), (1, -1)], [(1, 1), (1, 1), (1, -1), (1, 1), (1, -1), (1, -1)]...
+/// ```
+#[derive(Clone, Debug)]
+pub struct LatticePaths {
+    steps: Vec<Step>,
+    end: (i64, i64),
+    nonnegative: bool,
+    // the most any one step can lower the path, which bounds how high a point can be and still reach the end
+    max_drop: i64,
+}
+
+impl LatticePaths {
+    /// Paths from (0, 0) to end made of the given steps, which are tried in the order given.
+    /// Panics unless every step moves to the right or straight up, as otherwise a path could go on forever.
+    pub fn new(steps: &[Step], end: (i64, i64), nonnegative: bool) -> Self {
+        assert!(
+            steps.iter().all(|&(dx, dy)| dx > 0 || (dx == 0 && dy > 0)),
+            "every step must move to the right or straight up"
+        );
+        let max_drop = steps
+            .iter()
+            .filter(|(dx, _)| *dx > 0)
+            .map(|(_, dy)| -dy)
+            .max()
+            .unwrap_or(0)
+            .max(0);
+        Self {
+            steps: steps.to_vec(),
+            end,
+            nonnegative,
+            max_drop,
+        }
+    }
+
+    /// Paths of n up steps (1, 1) and n down steps (1, -1) that never go below the x-axis, counted by the Catalan numbers.
+    pub fn dyck(n: usize) -> Self {
+        Self::new(&[(1, 1), (1, -1)], (2 * n as i64, 0), true)
+    }
+
+    /// Paths from (0, 0) to (n, 0) of up, flat and down steps that never go below the x-axis, counted by the Motzkin numbers.
+    pub fn motzkin(n: usize) -> Self {
+        Self::new(&[(1, 1), (1, 0), (1, -1)], (n as i64, 0), true)
+    }
+
+    /// Paths from (0, 0) to (2n, 0) of up, down and double length flat steps (2, 0) that never go below the x-axis, counted by the large Schroder numbers.
+    pub fn schroder(n: usize) -> Self {
+        Self::new(&[(1, 1), (2, 0), (1, -1)], (2 * n as i64, 0), true)
+    }
+
+    /// Paths from (0, 0) to (m, n) of east, north and northeast steps, counted by the Delannoy numbers.
+    pub fn delannoy(m: usize, n: usize) -> Self {
+        Self::new(&[(1, 0), (0, 1), (1, 1)], (m as i64, n as i64), false)
+    }
+
+    /// Paths of n up and down steps that end at height h and never go below the x-axis, counted by the ballot numbers.
+    pub fn ballot(n: usize, h: usize) -> Self {
+        Self::new(&[(1, 1), (1, -1)], (n as i64, h as i64), true)
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn end(&self) -> (i64, i64) {
+        self.end
+    }
+
+    // Whether a point is allowed and not ruled out by a quick bound, points that pass may still be dead ends
+    fn feasible(&self, (x, y): (i64, i64)) -> bool {
+        x <= self.end.0
+            && !(self.nonnegative && y < 0)
+            && y - (self.end.0 - x) * self.max_drop <= self.end.1
+    }
+
+    fn count_from<T: Clone + Zero + One + CheckedAdd>(
+        &self,
+        (x, y): (i64, i64),
+        memo: &mut HashMap<(i64, i64), T>,
+    ) -> Option<T> {
+        if !self.feasible((x, y)) {
+            return Some(T::zero());
+        }
+        if let Some(count) = memo.get(&(x, y)) {
+            return Some(count.clone());
+        }
+        let mut total = if (x, y) == self.end {
+            T::one()
+        } else {
+            T::zero()
+        };
+        for &(dx, dy) in &self.steps {
+            total = total.checked_add(&self.count_from((x + dx, y + dy), memo)?)?;
+        }
+        memo.insert((x, y), total.clone());
+        Some(total)
+    }
+
+    /// The number of paths, without enumerating them, or None if it overflows.
+    pub fn count<T: Clone + Zero + One + CheckedAdd>(&self) -> Option<T> {
+        self.count_from((0, 0), &mut HashMap::new())
+    }
+
+    // Whether some path reaches the end from this point
+    fn viable(&self, (x, y): (i64, i64), memo: &mut HashMap<(i64, i64), bool>) -> bool {
+        if !self.feasible((x, y)) {
+            return false;
+        }
+        if let Some(&v) = memo.get(&(x, y)) {
+            return v;
+        }
+        let v = (x, y) == self.end
+            || self
+                .steps
+                .iter()
+                .any(|&(dx, dy)| self.viable((x + dx, y + dy), memo));
+        memo.insert((x, y), v);
+        v
+    }
+
+    /// Every path as its sequence of steps, in lexicographic order of the step set.
+    pub fn paths(&self) -> Paths {
+        Paths {
+            lattice: self.clone(),
+            viable: HashMap::new(),
+            choices: Vec::new(),
+            positions: Vec::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    /// The number of paths with each value of the statistic, indexed by that value, or None if a count overflows.
+    pub fn distribution<T, F>(&self, statistic: F) -> Option<Vec<T>>
+    where
+        T: Clone + Zero + One + CheckedAdd,
+        F: Fn(&[Step]) -> usize,
+    {
+        let mut counts: Vec<T> = Vec::new();
+        for path in self.paths() {
+            let value = statistic(&path);
+            if counts.len() <= value {
+                counts.resize(value + 1, T::zero());
+            }
+            counts[value] = counts[value].checked_add(&T::one())?;
+        }
+        Some(counts)
+    }
+}
+
+/// The paths of a LatticePaths, produced by backtracking only through points from which the end can be reached.
+pub struct Paths {
+    lattice: LatticePaths,
+    viable: HashMap<(i64, i64), bool>,
+    // the index of each step taken, with positions holding the point before each step and the current point
+    choices: Vec<usize>,
+    positions: Vec<(i64, i64)>,
+    started: bool,
+    done: bool,
+}
+
+impl Paths {
+    // The first step at or after index from that leads to a viable point
+    fn next_choice(&mut self, (x, y): (i64, i64), from: usize) -> Option<usize> {
+        (from..self.lattice.steps.len()).find(|&i| {
+            let (dx, dy) = self.lattice.steps[i];
+            self.lattice.viable((x + dx, y + dy), &mut self.viable)
+        })
+    }
+
+    fn push(&mut self, i: usize) {
+        let (x, y) = *self.positions.last().unwrap();
+        let (dx, dy) = self.lattice.steps[i];
+        self.choices.push(i);
+        self.positions.push((x + dx, y + dy));
+    }
+
+    // Extend the current path with the first possible steps until it reaches the end
+    fn descend(&mut self) {
+        loop {
+            let pos = *self.positions.last().unwrap();
+            if pos == self.lattice.end {
+                return;
+            }
+            let i = self.next_choice(pos, 0).unwrap();
+            self.push(i);
+        }
+    }
+
+    // Move to the next path by changing the last step that has an alternative
+    fn advance(&mut self) -> bool {
+        while let Some(last) = self.choices.pop() {
+            self.positions.pop();
+            let pos = *self.positions.last().unwrap();
+            if let Some(i) = self.next_choice(pos, last + 1) {
+                self.push(i);
+                self.descend();
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Iterator for Paths {
+    type Item = Vec<Step>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            if !self.advance() {
+                self.done = true;
+                return None;
+            }
+        } else {
+            self.started = true;
+            if !self.lattice.viable((0, 0), &mut self.viable) {
+                self.done = true;
+                return None;
+            }
+            self.positions.push((0, 0));
+            self.descend();
+        }
+        Some(
+            self.choices
+                .iter()
+                .map(|&i| self.lattice.steps[i])
+                .collect(),
+        )
+    }
+}
+
+/// The heights of the points of a path after each step.
+pub fn heights(path: &[Step]) -> impl Iterator<Item = i64> + '_ {
+    path.iter().scan(0, |y, (_, dy)| {
+        *y += dy;
+        Some(*y)
+    })
+}
+
+/// The number of peaks, an up step immediately followed by a down step.
+pub fn peaks(path: &[Step]) -> usize {
+    path.windows(2).filter(|w| w[0].1 > 0 && w[1].1 < 0).count()
+}
+
+/// The number of down steps that end on the x-axis.
+pub fn returns(path: &[Step]) -> usize {
+    path.iter()
+        .zip(heights(path))
+        .filter(|((_, dy), y)| *dy < 0 && *y == 0)
+        .count()
+}
+
+/// The greatest height reached.
+pub fn max_height(path: &[Step]) -> i64 {
+    heights(path).fold(0, i64::max)
+}
+
+/// The area between the path and the x-axis, with regions below the axis counted as negative. This is an integer for paths of unit and flat steps that start and end on the x-axis and is otherwise rounded down.
+pub fn area(path: &[Step]) -> i64 {
+    let twice: i64 = path
+        .iter()
+        .zip(heights(path))
+        .map(|((dx, dy), y)| dx * (2 * y - dy))
+        .sum();
+    Integer::div_floor(&twice, &2)
+}
+
+/// The number of paths in each member of a family of lattice paths. Ends if the values would overflow.
+///
+/// ```text
+/// LatticePaths::motzkin
+/// 1, 1, 2, 4, 9, 21, 51, 127, 323, 835, 2188, 5798, 15511, 41835...
+/// ```
+pub struct PathCounts<T> {
+    family: Box<dyn Fn(usize) -> LatticePaths>,
+    n: usize,
+    _type: PhantomData<T>,
+}
+
+impl PathCounts<Number> {
+    pub fn new<F: Fn(usize) -> LatticePaths + 'static>(family: F) -> Self {
+        Self {
+            family: Box::new(family),
+            n: 0,
+            _type: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl PathCounts<BigInt> {
+    pub fn new_big<F: Fn(usize) -> LatticePaths + 'static>(family: F) -> Self {
+        Self {
+            family: Box::new(family),
+            n: 0,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + Zero + One + CheckedAdd> Iterator for PathCounts<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = (self.family)(self.n).count()?;
+        self.n += 1;
+        Some(out)
+    }
+}
+
+type StatisticFn = Box<dyn Fn(&[Step]) -> usize>;
+
+/// The triangle whose nth row counts the paths in the nth member of a family of lattice paths by the value of a statistic. Paths are enumerated, so only the first rows are practical.
+///
+/// ```text
+/// LatticePaths::dyck by peaks, the Narayana numbers
+/// [1], [0, 1], [0, 1, 1], [0, 1, 3, 1], [0, 1, 6, 6, 1], [0, 1, 10, 20, 10, 1]...
+/// ```
+pub struct StatisticTriangle<T> {
+    family: Box<dyn Fn(usize) -> LatticePaths>,
+    statistic: StatisticFn,
+    n: usize,
+    _type: PhantomData<T>,
+}
+
+impl StatisticTriangle<Number> {
+    pub fn new<F, S>(family: F, statistic: S) -> Self
+    where
+        F: Fn(usize) -> LatticePaths + 'static,
+        S: Fn(&[Step]) -> usize + 'static,
+    {
+        Self {
+            family: Box::new(family),
+            statistic: Box::new(statistic),
+            n: 0,
+            _type: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl StatisticTriangle<BigInt> {
+    pub fn new_big<F, S>(family: F, statistic: S) -> Self
+    where
+        F: Fn(usize) -> LatticePaths + 'static,
+        S: Fn(&[Step]) -> usize + 'static,
+    {
+        Self {
+            family: Box::new(family),
+            statistic: Box::new(statistic),
+            n: 0,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + Zero + One + CheckedAdd> Iterator for StatisticTriangle<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = (self.family)(self.n).distribution(&self.statistic)?;
+        self.n += 1;
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalan::Catalan;
+    use crate::narayana_triangle::NarayanaTriangle;
+    use crate::pascal::PascalsTriangle;
+    use crate::riordan::RiordanArray;
+    use itertools::Itertools;
+    use std::iter::{once, repeat};
+
+    #[test]
+    fn enumeration() {
+        let expected: Vec<Vec<Step>> = ["UUUDDD", "UUDUDD", "UUDDUD", "UDUUDD", "UDUDUD"]
+            .iter()
+            .map(|s| {
+                s.chars()
+                    .map(|c| if c == 'U' { (1, 1) } else { (1, -1) })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(LatticePaths::dyck(3).paths().collect_vec(), expected);
+        assert_eq!(LatticePaths::dyck(0).paths().collect_vec(), vec![vec![]]);
+        assert_eq!(LatticePaths::ballot(3, 2).paths().count(), 0);
+
+        let families = [
+            LatticePaths::dyck(6),
+            LatticePaths::motzkin(8),
+            LatticePaths::schroder(4),
+            LatticePaths::delannoy(3, 4),
+            LatticePaths::ballot(9, 3),
+            LatticePaths::new(&[(0, 1), (1, -1), (2, 3)], (5, 2), true),
+        ];
+        for family in families {
+            let paths = family.paths().collect_vec();
+            assert_eq!(Some(paths.len()), family.count::<usize>());
+            assert!(paths.iter().all_unique());
+            for path in paths {
+                let end = path
+                    .iter()
+                    .fold((0, 0), |(x, y), (dx, dy)| (x + dx, y + dy));
+                assert_eq!(end, family.end());
+                assert!(!family.nonnegative || heights(&path).all(|y| y >= 0));
+            }
+        }
+    }
+
+    #[test]
+    fn counting() {
+        assert!(
+            PathCounts::new_big(LatticePaths::dyck)
+                .take(40)
+                .eq(Catalan::new_big().take(40))
+        );
+        // Ballot paths of length n that end anywhere are counted by the central binomial coefficients
+        for (n, row) in PascalsTriangle::new().take(20).enumerate() {
+            let total: i64 = (0..=n)
+                .map(|h| LatticePaths::ballot(n, h).count::<i64>().unwrap())
+                .sum();
+            assert_eq!(total, row[n / 2]);
+        }
+        for m in 0..6 {
+            for n in 0..6 {
+                assert_eq!(
+                    LatticePaths::delannoy(m, n).count::<i64>(),
+                    LatticePaths::delannoy(n, m).count::<i64>()
+                );
+            }
+        }
+        // Without the restriction up and down steps give binomial coefficients
+        let free = LatticePaths::new(&[(1, 1), (1, -1)], (10, 0), false);
+        assert_eq!(free.count::<i64>(), Some(252));
+        assert_eq!(PathCounts::new(LatticePaths::dyck).count(), 36);
+    }
+
+    #[test]
+    fn statistics() {
+        let path = [(1, 1), (1, 1), (1, -1), (1, 0), (1, -1), (1, 1), (1, -1)];
+        assert_eq!(heights(&path).collect_vec(), vec![1, 2, 1, 1, 0, 1, 0]);
+        assert_eq!(peaks(&path), 2);
+        assert_eq!(returns(&path), 2);
+        assert_eq!(max_height(&path), 2);
+        assert_eq!(area(&path), 6);
+        assert_eq!(area(&[(1, -1), (1, 1)]), -1);
+        assert_eq!(area(&[(2, 0), (0, 3), (1, 0)]), 3);
+
+        // Dyck paths by peaks give the Narayana triangle
+        assert!(
+            StatisticTriangle::new_big(LatticePaths::dyck, peaks)
+                .skip(1)
+                .take(9)
+                .map(|row| row[1..].to_vec())
+                .eq(NarayanaTriangle::new_big().take(9))
+        );
+        // Dyck paths by returns give the Riordan array (1, x C(x))
+        let returns_array =
+            RiordanArray::new(once(1).chain(repeat(0)), once(0).chain(Catalan::new()));
+        for (n, row) in StatisticTriangle::new(LatticePaths::dyck, returns)
+            .take(9)
+            .enumerate()
+        {
+            assert_eq!(row, returns_array.row(n).unwrap());
+        }
+        // Motzkin paths with k flat steps number C(n, k) times the Catalan number of (n - k)/2
+        let catalan = Catalan::new().take(10).collect_vec();
+        for (n, row) in StatisticTriangle::new(LatticePaths::motzkin, |p| {
+            p.iter().filter(|s| s.1 == 0).count()
+        })
+        .take(12)
+        .enumerate()
+        {
+            for (k, count) in row.into_iter().enumerate() {
+                let pascal = PascalsTriangle::new().nth(n).unwrap()[k];
+                let expected = if (n - k) % 2 == 0 {
+                    pascal * catalan[(n - k) / 2]
+                } else {
+                    0
+                };
+                assert_eq!(count, expected);
+            }
+        }
+    }
+}
+
+crate::check_sequences!(
+    PathCounts::new_big(LatticePaths::motzkin), [1, 1, 2, 4, 9, 21, 51, 127, 323, 835, 2188, 5798, 15511, 41835];
+    PathCounts::new_big(LatticePaths::schroder), [1, 2, 6, 22, 90, 394, 1806, 8558, 41586, 206098];
+    PathCounts::new_big(|n| LatticePaths::delannoy(n, n)), [1, 3, 13, 63, 321, 1683, 8989, 48639, 265729];
+    PathCounts::new_big(|n| LatticePaths::ballot(2 * n + 1, 1)), [1, 2, 5, 14, 42, 132, 429, 1430];
+    StatisticTriangle::new_big(LatticePaths::dyck, |p| area(p) as usize).map(|row| row.iter().enumerate().map(|(k, x)| x * k).sum::<BigInt>()), [0, 1, 6, 29, 130, 562, 2380];
+);
@@ -31,6 +31,7 @@ pub mod compositions;
 pub mod compositions_weak;
 pub mod copeland_erdos;
 pub mod curzon;
+pub mod database;
 pub mod de_bruijn;
 pub mod derangement;
 pub mod digital_product;
@@ -58,6 +59,7 @@ pub mod golden_rectangle;
 pub mod golomb;
 pub mod goodstein;
 pub mod gray;
+pub mod guess;
 pub mod harmonic;
 pub mod hofstadter_g;
 pub mod hofstadter_h;
@@ -68,6 +70,7 @@ pub mod hypotenuse;
 pub mod jordan_polya;
 pub mod juggler;
 pub mod kempner;
+pub mod lattice_paths;
 pub mod leonardo;
 pub mod liouville;
 pub mod look_and_say;
@@ -90,6 +93,7 @@ pub mod partition;
 pub mod pascal;
 pub mod pell;
 pub mod period_doubling;
+pub mod permutation;
 pub mod perrin_padovan;
 pub mod phythagorean;
 pub mod pi;
@@ -101,13 +105,17 @@ pub mod prime_gaps;
 pub mod prime_line_cover;
 pub mod pseudoprime;
 pub mod rado_pairs;
+pub mod ranking;
 pub mod recaman;
 pub mod repint;
+pub mod restricted_partitions;
+pub mod riordan;
 pub mod rowland;
 pub mod rueppel;
 pub mod ruler;
 pub mod safe_primes;
 pub mod semiprime;
+pub mod set_partitions;
 pub mod sierpinsky_triangle;
 pub mod smooth;
 pub mod sorted_pairs;
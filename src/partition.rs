@@ -1,5 +1,5 @@
 use itertools::Itertools;
-use num::{BigInt, CheckedAdd, CheckedSub, Integer, One};
+use num::{BigInt, CheckedAdd, CheckedSub, Integer, One, Signed, Zero};
 
 use crate::Number;
 
@@ -62,6 +62,128 @@ impl<T: Clone + Integer + CheckedAdd + CheckedSub> Iterator for Partition<T> {
     }
 }
 
+// Lehmer's bound on the error after the first terms terms of Rademacher's series, valid for n > 1
+fn rademacher_remainder_bound(n: u64, terms: u64) -> f64 {
+    use std::f64::consts::PI;
+    let n = n as f64;
+    let terms = terms as f64;
+    44.0 * PI * PI / (225.0 * 3.0_f64.sqrt()) / terms.sqrt()
+        + PI * 2.0_f64.sqrt() / 75.0
+            * (terms / (n - 1.0)).sqrt()
+            * (PI / terms * (2.0 * n / 3.0).sqrt()).sinh()
+}
+
+/// The number of partitions of n, p(n), computed directly from the Hardy-Ramanujan-Rademacher formula.
+/// Enough terms are taken for Lehmer's bound on the remainder to be less than 1/4. The sum is computed in fixed point and only rounded once it lies within 1/4 + 2^-(guard-8) of an integer, where guard is the number of bits kept after the point, otherwise it is recomputed with twice as many guard bits.
+/// The result is therefore exact whenever the accumulated arithmetic error is below 2^-(guard-8), an allowance of 256 units in the last place. That error is not bounded in advance, so a larger one is only caught when it moves the sum away from every integer.
+/// This is much faster than Partition for large n.
+#[cfg(feature = "big_int")]
+pub fn partition_number(n: u64) -> BigInt {
+    if n < 2 {
+        return BigInt::one();
+    }
+    certified_partition_number(n, 64 + 2 * (n.ilog2() as u64 + 1))
+}
+
+// Round the sum of the Rademacher series once it is close enough to an integer, doubling guard until it is
+#[cfg(feature = "big_int")]
+fn certified_partition_number(n: u64, mut guard: u64) -> BigInt {
+    use crate::utils::fixed_point::round;
+
+    let mut terms = 1;
+    while rademacher_remainder_bound(n, terms) >= 0.25 {
+        terms += 1;
+    }
+    loop {
+        let sum = rademacher_sum(n, terms, guard);
+        let nearest = round(&sum, guard);
+        let distance = (&sum - (&nearest << guard)).abs();
+        if distance <= (BigInt::one() << (guard - 2)) + (BigInt::one() << 8) {
+            return nearest;
+        }
+        guard *= 2;
+    }
+}
+
+// The first terms terms of the Rademacher series for p(n) in fixed point with guard bits after the point
+#[cfg(feature = "big_int")]
+fn rademacher_sum(n: u64, terms: u64, guard: u64) -> BigInt {
+    use crate::utils::fixed_point::{cos, div, exp, from_ratio, mul, pi, rescale, sqrt};
+
+    // Term k is roughly exp(mu/k) so it needs about that many bits before the point for the guard bits after it to be accurate
+    let mu_f64 = std::f64::consts::PI * (2.0 / 3.0 * (n as f64 - 1.0 / 24.0)).sqrt();
+    let precision = |k: u64| (mu_f64 / k as f64 * std::f64::consts::LOG2_E).ceil() as u64 + guard;
+    let max_bits = precision(1) + 16;
+
+    let pi_max = pi(max_bits);
+    // lambda^2 = n - 1/24, c = pi sqrt(2/3), mu = c lambda
+    let lambda2_max = from_ratio(24 * n as i64 - 1, 24, max_bits);
+    let lambda_max = sqrt(&lambda2_max, max_bits);
+    let c_max = mul(
+        &pi_max,
+        &sqrt(&from_ratio(2, 3, max_bits), max_bits),
+        max_bits,
+    );
+    let mu_max = mul(&c_max, &lambda_max, max_bits);
+    let lambda3_max = mul(&lambda2_max, &lambda_max, max_bits);
+    let pi_sqrt6_max = mul(
+        &pi_max,
+        &sqrt(&from_ratio(6, 1, max_bits), max_bits),
+        max_bits,
+    );
+
+    let mut sum = BigInt::zero();
+    for k in 1..=terms {
+        let bits = precision(k);
+        let at = |x: &BigInt| rescale(x, max_bits, bits);
+        let pi = at(&pi_max);
+
+        // Selberg's formula gives A_k(n) = sqrt(k/3) s with
+        // s = sum of (-1)^l cos(pi (6l + 1) / 6k) over 0 <= l < 2k where (3l^2 + l)/2 = -n mod k
+        let mut s = BigInt::zero();
+        let (k128, n128) = (k as u128, n as u128);
+        for l in 0..2 * k128 {
+            if ((3 * l * l + l) / 2 + n128) % k128 == 0 {
+                // Reduce the angle to [0, pi/2] exactly
+                let den = 6 * k128;
+                let mut num = (6 * l + 1) % (2 * den);
+                if num > den {
+                    num = 2 * den - num;
+                }
+                let negate = 2 * num > den;
+                if negate {
+                    num = den - num;
+                }
+                let x = &pi * BigInt::from(num) / BigInt::from(den);
+                let term = cos(&x, bits);
+                if negate ^ (l % 2 == 1) {
+                    s -= term;
+                } else {
+                    s += term;
+                }
+            }
+        }
+        if s.is_zero() {
+            continue;
+        }
+
+        // sqrt(k/3) s sqrt(k) / (pi sqrt(2)) d/dn (sinh(mu/k) / lambda)
+        // = s / (pi sqrt(6)) (c cosh(mu/k) / (2 lambda^2) - k sinh(mu/k) / (2 lambda^3))
+        let e = exp(&(at(&mu_max) / k), bits);
+        let e_inv = div(&(BigInt::one() << bits), &e, bits);
+        let cosh = (&e + &e_inv) >> 1;
+        let sinh = (&e - &e_inv) >> 1;
+        let bracket = div(
+            &mul(&at(&c_max), &cosh, bits),
+            &(at(&lambda2_max) << 1),
+            bits,
+        ) - div(&sinh, &(at(&lambda3_max) << 1), bits) * k;
+        let term = div(&mul(&s, &bracket, bits), &at(&pi_sqrt6_max), bits);
+        sum += rescale(&term, bits, guard);
+    }
+    sum
+}
+
 // https://github.com/quadrupleslap/integer-partitions/blob/master/src/lib.rs
 /// The partitions of a non-negative integer.
 ///
@@ -192,3 +314,60 @@ crate::sample_sequences!(
     PartitionsN::new(4).map(|x| format!("{x:?}"));
     Partition::new();
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "big_int")]
+    #[test]
+    fn partition_number_matches_recurrence() {
+        for (n, p) in Partition::new_big().take(1500).enumerate() {
+            assert_eq!(partition_number(n as u64), p, "p({n})");
+        }
+    }
+
+    #[cfg(feature = "big_int")]
+    #[test]
+    fn partition_number_large() {
+        assert_eq!(
+            partition_number(1000).to_string(),
+            "24061467864032622473692149727991"
+        );
+        let p = partition_number(100_000).to_string();
+        assert_eq!(p.len(), 347);
+        assert!(p.starts_with("274935105697756965126775163209"));
+        assert!(p.ends_with("80158600569421098519"));
+    }
+
+    // 11 guard bits are the fewest for which the allowance of 1/4 + 2^-(guard-8) is below 1/2
+    #[cfg(feature = "big_int")]
+    #[test]
+    fn partition_number_low_precision() {
+        for (n, p) in Partition::new_big().take(300).enumerate().skip(2) {
+            assert_eq!(certified_partition_number(n as u64, 11), p, "p({n})");
+        }
+    }
+
+    // Euler's pentagonal number theorem gives p(n) as an alternating sum of p(n - g) over the generalized pentagonal numbers g
+    #[cfg(feature = "big_int")]
+    #[test]
+    fn partition_number_pentagonal() {
+        for n in [5000, 12345] {
+            let mut sum = BigInt::zero();
+            for (i, g) in crate::figurate::GeneralizedPentagonal::<i64>::new()
+                .skip(1)
+                .take_while(|&g| g <= n)
+                .enumerate()
+            {
+                let p = partition_number((n - g) as u64);
+                if i % 4 < 2 {
+                    sum += p;
+                } else {
+                    sum -= p;
+                }
+            }
+            assert_eq!(partition_number(n as u64), sum);
+        }
+    }
+}
@@ -0,0 +1,557 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+use num::{BigInt, CheckedAdd, CheckedMul, Integer, One, Zero};
+
+use crate::Number;
+
+/// A permutation of 0..n in one-line notation, where element i is sent to images[i].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Permutation {
+    images: Vec<usize>,
+}
+
+impl Permutation {
+    /// Panics if images is not a permutation of 0..n.
+    pub fn new(images: Vec<usize>) -> Self {
+        let mut seen = vec![false; images.len()];
+        for &x in &images {
+            assert!(
+                x < images.len() && !seen[x],
+                "images must be a permutation of 0..n"
+            );
+            seen[x] = true;
+        }
+        Self { images }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Self {
+            images: (0..n).collect(),
+        }
+    }
+
+    /// The permutation of 0..n with the given disjoint cycles, elements not in any cycle are fixed.
+    /// Panics if the cycles are not disjoint or contain an element outside of 0..n.
+    pub fn from_cycles(n: usize, cycles: &[Vec<usize>]) -> Self {
+        let mut images: Vec<usize> = (0..n).collect();
+        let mut seen = vec![false; n];
+        for cycle in cycles {
+            for (&a, &b) in cycle.iter().circular_tuple_windows() {
+                assert!(a < n && !seen[a], "cycles must be disjoint and within 0..n");
+                seen[a] = true;
+                images[a] = b;
+            }
+        }
+        Self { images }
+    }
+
+    /// The number of elements permuted.
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    pub fn images(&self) -> &[usize] {
+        &self.images
+    }
+
+    /// The image of i.
+    pub fn apply(&self, i: usize) -> usize {
+        self.images[i]
+    }
+
+    /// The permutation that applies other and then self.
+    /// Panics if the permutations have different lengths.
+    pub fn compose(&self, other: &Self) -> Self {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "permutations must be the same length"
+        );
+        Self {
+            images: other.images.iter().map(|&i| self.images[i]).collect(),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut images = vec![0; self.len()];
+        for (i, &x) in self.images.iter().enumerate() {
+            images[x] = i;
+        }
+        Self { images }
+    }
+
+    /// The disjoint cycles, including fixed points, each starting from its smallest element and ordered by that element.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut out = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut x = start;
+            while !seen[x] {
+                seen[x] = true;
+                cycle.push(x);
+                x = self.images[x];
+            }
+            out.push(cycle);
+        }
+        out
+    }
+
+    /// The lengths of the cycles in decreasing order, a partition of n.
+    pub fn cycle_type(&self) -> Vec<usize> {
+        self.cycles()
+            .iter()
+            .map(|c| c.len())
+            .sorted()
+            .rev()
+            .collect()
+    }
+
+    /// 1 for even permutations and -1 for odd permutations.
+    pub fn sign(&self) -> i8 {
+        if (self.len() - self.cycles().len()).is_even() {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// The smallest positive power that gives the identity, the least common multiple of the cycle lengths.
+    pub fn order(&self) -> BigInt {
+        self.cycles()
+            .iter()
+            .fold(BigInt::one(), |acc, c| acc.lcm(&BigInt::from(c.len())))
+    }
+
+    pub fn fixed_points(&self) -> Vec<usize> {
+        (0..self.len()).filter(|&i| self.images[i] == i).collect()
+    }
+
+    /// The positions i where images[i] > images[i + 1].
+    pub fn descents(&self) -> Vec<usize> {
+        (0..self.len().saturating_sub(1))
+            .filter(|&i| self.images[i] > self.images[i + 1])
+            .collect()
+    }
+
+    /// The number of pairs i < j where images[i] > images[j].
+    pub fn inversions(&self) -> usize {
+        self.images
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| a > b)
+            .count()
+    }
+
+    /// The sum of the descent positions, counting positions from one.
+    pub fn major_index(&self) -> usize {
+        self.descents().iter().map(|i| i + 1).sum()
+    }
+
+    /// True if some subsequence of the images is in the same relative order as the pattern, which is itself a permutation of 0..k. For example 231 is written [1, 2, 0].
+    pub fn contains_pattern(&self, pattern: &[usize]) -> bool {
+        (0..self.len()).combinations(pattern.len()).any(|idxs| {
+            idxs.iter()
+                .enumerate()
+                .tuple_combinations()
+                .all(|((a, &i), (b, &j))| {
+                    (self.images[i] < self.images[j]) == (pattern[a] < pattern[b])
+                })
+        })
+    }
+
+    pub fn avoids(&self, pattern: &[usize]) -> bool {
+        !self.contains_pattern(pattern)
+    }
+}
+
+impl Display for Permutation {
+    /// Cycle notation without fixed points, so the identity is ().
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cycles = self
+            .cycles()
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .collect_vec();
+        if cycles.is_empty() {
+            return write!(f, "()");
+        }
+        for c in cycles {
+            write!(f, "({})", c.iter().join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// All permutations of 0..n by Heap's algorithm, where each is reached from the one before by a single transposition.
+///
+/// ```text
+/// n = 3
+/// [0, 1, 2], [1, 0, 2], [2, 0, 1], [0, 2, 1], [1, 2, 0], [2, 1, 0]
+/// ```
+pub struct HeapPermutations {
+    images: Vec<usize>,
+    counters: Vec<usize>,
+    i: usize,
+    started: bool,
+}
+
+impl HeapPermutations {
+    pub fn new(n: usize) -> Self {
+        Self {
+            images: (0..n).collect(),
+            counters: vec![0; n],
+            i: 1,
+            started: false,
+        }
+    }
+}
+
+impl Iterator for HeapPermutations {
+    type Item = Permutation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(Permutation::new(self.images.clone()));
+        }
+        while self.i < self.images.len() {
+            if self.counters[self.i] < self.i {
+                if self.i.is_even() {
+                    self.images.swap(0, self.i);
+                } else {
+                    self.images.swap(self.counters[self.i], self.i);
+                }
+                self.counters[self.i] += 1;
+                self.i = 1;
+                return Some(Permutation::new(self.images.clone()));
+            }
+            self.counters[self.i] = 0;
+            self.i += 1;
+        }
+        None
+    }
+}
+
+/// All permutations of 0..n by the Steinhaus-Johnson-Trotter algorithm with Even's speedup, where each is reached from the one before by swapping two adjacent elements.
+///
+/// ```text
+/// n = 3
+/// [0, 1, 2], [0, 2, 1], [2, 0, 1], [2, 1, 0], [1, 2, 0], [1, 0, 2]
+/// ```
+pub struct SteinhausJohnsonTrotter {
+    images: Vec<usize>,
+    // true if the element at that position is moving left
+    left: Vec<bool>,
+    done: bool,
+}
+
+impl SteinhausJohnsonTrotter {
+    pub fn new(n: usize) -> Self {
+        Self {
+            images: (0..n).collect(),
+            left: vec![true; n],
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SteinhausJohnsonTrotter {
+    type Item = Permutation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let out = Permutation::new(self.images.clone());
+
+        // The largest element that points at a smaller neighbor
+        let n = self.images.len();
+        let mobile = (0..n)
+            .filter(|&i| {
+                let j = if self.left[i] {
+                    i.checked_sub(1)
+                } else {
+                    Some(i + 1).filter(|&j| j < n)
+                };
+                j.is_some_and(|j| self.images[j] < self.images[i])
+            })
+            .max_by_key(|&i| self.images[i]);
+        match mobile {
+            Some(i) => {
+                let x = self.images[i];
+                let j = if self.left[i] { i - 1 } else { i + 1 };
+                self.images.swap(i, j);
+                self.left.swap(i, j);
+                for k in 0..n {
+                    if self.images[k] > x {
+                        self.left[k] = !self.left[k];
+                    }
+                }
+            }
+            None => self.done = true,
+        }
+        Some(out)
+    }
+}
+
+/// The number of permutations of each length from zero that avoid a pattern, found by checking every permutation so only the first few terms are practical.
+///
+/// ```text
+/// pattern = [1, 2, 0]
+/// 1, 1, 2, 5, 14, 42, 132, 429...
+/// ```
+pub struct PatternAvoiding {
+    pattern: Vec<usize>,
+    n: usize,
+}
+
+impl PatternAvoiding {
+    /// Panics if pattern is not a permutation of 0..k.
+    pub fn new(pattern: &[usize]) -> Self {
+        Permutation::new(pattern.to_vec());
+        Self {
+            pattern: pattern.to_vec(),
+            n: 0,
+        }
+    }
+}
+
+impl Iterator for PatternAvoiding {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = HeapPermutations::new(self.n)
+            .filter(|p| p.avoids(&self.pattern))
+            .count();
+        self.n += 1;
+        Some(out)
+    }
+}
+
+/// The number of permutations of 0..n with each value of a statistic, found by checking every permutation.
+pub fn statistic_distribution<F: Fn(&Permutation) -> usize>(n: usize, statistic: F) -> Vec<usize> {
+    let mut out = Vec::new();
+    for p in HeapPermutations::new(n) {
+        let s = statistic(&p);
+        if s >= out.len() {
+            out.resize(s + 1, 0);
+        }
+        out[s] += 1;
+    }
+    out
+}
+
+/// The Eulerian numbers by rows, the number of permutations of n elements with k descents.
+///
+/// ```text
+/// [1], [1], [1, 1], [1, 4, 1], [1, 11, 11, 1], [1, 26, 66, 26, 1]...
+/// ```
+pub struct EulerianTriangle<T> {
+    n: u32,
+    row: Vec<T>,
+}
+
+impl EulerianTriangle<Number> {
+    pub fn new() -> Self {
+        Self { n: 0, row: vec![1] }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl EulerianTriangle<BigInt> {
+    pub fn new_big() -> Self {
+        Self {
+            n: 0,
+            row: vec![BigInt::one()],
+        }
+    }
+}
+
+impl<T: From<u32> + Zero + CheckedAdd + CheckedMul + Clone> Iterator for EulerianTriangle<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.row.clone();
+        self.n += 1;
+        let n = self.n;
+        // A(n, k) = (k + 1) A(n - 1, k) + (n - k) A(n - 1, k - 1)
+        let mut next_row = Vec::with_capacity(n as usize);
+        for k in 0..n {
+            let mut value = T::zero();
+            if let Some(a) = self.row.get(k as usize) {
+                value = T::from(k + 1).checked_mul(a)?;
+            }
+            if let Some(a) = k.checked_sub(1).and_then(|j| self.row.get(j as usize)) {
+                value = value.checked_add(&T::from(n - k).checked_mul(a)?)?;
+            }
+            next_row.push(value);
+        }
+        self.row = next_row;
+        Some(out)
+    }
+}
+
+/// The Mahonian numbers by rows, the number of permutations of n elements with k inversions, which is also the number with major index k.
+///
+/// ```text
+/// [1], [1], [1, 1], [1, 2, 2, 1], [1, 3, 5, 6, 5, 3, 1]...
+/// ```
+pub struct MahonianTriangle<T> {
+    n: usize,
+    row: Vec<T>,
+}
+
+impl MahonianTriangle<Number> {
+    pub fn new() -> Self {
+        Self { n: 0, row: vec![1] }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl MahonianTriangle<BigInt> {
+    pub fn new_big() -> Self {
+        Self {
+            n: 0,
+            row: vec![BigInt::one()],
+        }
+    }
+}
+
+impl<T: Zero + CheckedAdd + Clone> Iterator for MahonianTriangle<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.row.clone();
+        self.n += 1;
+        // The new element adds between 0 and n - 1 inversions, so the row is convolved with n ones
+        let mut next_row = vec![T::zero(); self.row.len() + self.n - 1];
+        for (i, a) in self.row.iter().enumerate() {
+            for x in next_row[i..i + self.n].iter_mut() {
+                *x = x.checked_add(a)?;
+            }
+        }
+        self.row = next_row;
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalan::Catalan;
+    use crate::derangement::Derangements;
+    use std::collections::HashSet;
+
+    #[test]
+    fn group_operations() {
+        let p = Permutation::new(vec![2, 0, 1, 4, 3, 5]);
+        assert_eq!(p.cycles(), vec![vec![0, 2, 1], vec![3, 4], vec![5]]);
+        assert_eq!(p.cycle_type(), vec![3, 2, 1]);
+        assert_eq!(p.to_string(), "(0 2 1)(3 4)");
+        assert_eq!(p.sign(), -1);
+        assert_eq!(p.order(), BigInt::from(6));
+        assert_eq!(p.fixed_points(), vec![5]);
+        assert_eq!(Permutation::from_cycles(6, &p.cycles()), p);
+        assert_eq!(p.compose(&p.inverse()), Permutation::identity(6));
+        assert_eq!(Permutation::identity(3).to_string(), "()");
+        // Composition applies the right permutation first
+        let q = Permutation::new(vec![1, 0, 2, 3, 4, 5]);
+        assert_eq!(p.compose(&q).images(), &[0, 2, 1, 4, 3, 5]);
+
+        let mut power = Permutation::identity(6);
+        for _ in 0..6 {
+            power = power.compose(&p);
+        }
+        assert_eq!(power, Permutation::identity(6));
+    }
+
+    #[test]
+    fn sign_is_a_homomorphism() {
+        let all: Vec<Permutation> = HeapPermutations::new(4).collect();
+        for p in &all {
+            for q in &all {
+                assert_eq!(p.compose(q).sign(), p.sign() * q.sign());
+            }
+            assert_eq!(p.sign(), if p.inversions().is_even() { 1 } else { -1 });
+        }
+    }
+
+    #[test]
+    fn enumerators() {
+        for n in 0..8 {
+            let heap: Vec<Permutation> = HeapPermutations::new(n).collect();
+            let sjt: Vec<Permutation> = SteinhausJohnsonTrotter::new(n).collect();
+            let factorial = (1..=n).product::<usize>();
+            assert_eq!(heap.iter().collect::<HashSet<_>>().len(), factorial);
+            assert_eq!(sjt.iter().collect::<HashSet<_>>().len(), factorial);
+            for pair in heap.windows(2) {
+                let differ = (0..n)
+                    .filter(|&i| pair[0].apply(i) != pair[1].apply(i))
+                    .count();
+                assert_eq!(differ, 2);
+            }
+            for pair in sjt.windows(2) {
+                let differ = (0..n)
+                    .filter(|&i| pair[0].apply(i) != pair[1].apply(i))
+                    .collect_vec();
+                assert!(differ.len() == 2 && differ[1] == differ[0] + 1);
+            }
+        }
+        // Derangements have no fixed points
+        for (n, d) in Derangements::new().take(8).enumerate() {
+            let count = HeapPermutations::new(n)
+                .filter(|p| p.fixed_points().is_empty())
+                .count();
+            assert_eq!(count as i64, d);
+        }
+    }
+
+    #[test]
+    fn pattern_avoidance() {
+        // Every pattern of length 3 is avoided by a Catalan number of permutations
+        let catalan: Vec<usize> = Catalan::new().take(8).map(|c| c as usize).collect();
+        for pattern in HeapPermutations::new(3) {
+            let counts: Vec<usize> = PatternAvoiding::new(pattern.images()).take(8).collect();
+            assert_eq!(counts, catalan);
+        }
+        // 1342-avoiding permutations, A022558
+        let counts: Vec<usize> = PatternAvoiding::new(&[0, 2, 3, 1]).take(8).collect();
+        assert_eq!(counts, vec![1, 1, 2, 6, 23, 103, 512, 2740]);
+    }
+
+    #[test]
+    fn statistics() {
+        for (n, row) in EulerianTriangle::new().take(8).enumerate().skip(1) {
+            let descents = statistic_distribution(n, |p| p.descents().len());
+            assert_eq!(descents.iter().map(|&x| x as i64).collect_vec(), row);
+        }
+        for (n, row) in MahonianTriangle::new().take(8).enumerate() {
+            let inversions = statistic_distribution(n, Permutation::inversions);
+            let major = statistic_distribution(n, Permutation::major_index);
+            assert_eq!(inversions.iter().map(|&x| x as i64).collect_vec(), row);
+            assert_eq!(inversions, major);
+        }
+    }
+}
+
+crate::check_sequences!(
+    EulerianTriangle::new_big().flatten(), [1, 1, 1, 1, 1, 4, 1, 1, 11, 11, 1, 1, 26, 66, 26, 1, 1, 57, 302, 302, 57, 1];
+    EulerianTriangle::new().flatten(), [1, 1, 1, 1, 1, 4, 1, 1, 11, 11, 1, 1, 26, 66, 26, 1, 1, 57, 302, 302, 57, 1];
+    MahonianTriangle::new_big().flatten(), [1, 1, 1, 1, 1, 2, 2, 1, 1, 3, 5, 6, 5, 3, 1, 1, 4, 9, 15, 20, 22, 20, 15, 9, 4, 1];
+    MahonianTriangle::new().flatten(), [1, 1, 1, 1, 1, 2, 2, 1, 1, 3, 5, 6, 5, 3, 1, 1, 4, 9, 15, 20, 22, 20, 15, 9, 4, 1];
+    PatternAvoiding::new(&[1, 2, 0]), [1, 1, 2, 5, 14, 42, 132];
+);
+
+crate::print_sequences!(
+    HeapPermutations::new(4), 24, "{:?}", "\n";
+    SteinhausJohnsonTrotter::new(4), 24, "{:?}", "\n";
+);
@@ -0,0 +1,715 @@
+//! Ranking and unranking of combinatorial objects. Each family converts between its objects and their indices in the order used by the matching enumerator, which allows uniform sampling and splitting an enumeration into independent ranges.
+use num::{BigInt, One, Signed, ToPrimitive, Zero};
+
+use crate::factoradic::factoradic;
+
+/// The binomial coefficient C(n, k), which is zero when k > n.
+fn binomial(n: usize, k: usize) -> BigInt {
+    if k > n {
+        return BigInt::zero();
+    }
+    let k = k.min(n - k);
+    let mut out = BigInt::one();
+    for i in 0..k {
+        out = out * (n - i) / (i + 1);
+    }
+    out
+}
+
+/// A finite family of objects in a fixed order, indexed from zero.
+pub trait Ranking {
+    type Object;
+
+    /// The number of objects in the family.
+    fn count(&self) -> BigInt;
+
+    /// The index of an object, or None if it is not a member of the family.
+    fn rank(&self, object: &Self::Object) -> Option<BigInt>;
+
+    /// The object with a given index, or None if the index is out of range.
+    fn unrank(&self, index: &BigInt) -> Option<Self::Object>;
+
+    /// The object following the given one, or None if it is the last.
+    /// By default this ranks and unranks, families with a direct method should override it.
+    fn successor(&self, object: &Self::Object) -> Option<Self::Object> {
+        self.unrank(&(self.rank(object)? + 1))
+    }
+
+    /// All objects from the given index onward, in order.
+    fn iter_from(&self, index: &BigInt) -> impl Iterator<Item = Self::Object> {
+        std::iter::successors(self.unrank(index), |object| self.successor(object))
+    }
+
+    /// Split the indices into at most parts contiguous ranges start..end of nearly equal size, so that each can be enumerated independently with iter_from.
+    /// Panics if parts is zero.
+    fn split_ranges(&self, parts: usize) -> Vec<(BigInt, BigInt)> {
+        assert!(parts > 0, "parts must be positive");
+        let count = self.count();
+        let mut out = Vec::new();
+        for i in 0..parts {
+            let start = &count * i / parts;
+            let end = &count * (i + 1) / parts;
+            if start < end {
+                out.push((start, end));
+            }
+        }
+        out
+    }
+
+    /// A uniformly random object, or None if the family is empty.
+    #[cfg(feature = "big_int")]
+    fn sample<R: crate::rngs::Rng + ?Sized>(&self, rng: &mut R) -> Option<Self::Object> {
+        let count = self.count();
+        if !count.is_positive() {
+            return None;
+        }
+        self.unrank(&crate::rngs::distributions::bounded_big(rng, &count))
+    }
+}
+
+/// The combinations of k elements from 0..n as increasing vectors in lexicographic order, matching core::Combinations on (0..n) for k > 0. Ranks are given by the combinatorial number system.
+///
+/// ```text
+/// n = 4, k = 2
+/// 0: [0, 1], 1: [0, 2], 2: [0, 3], 3: [1, 2], 4: [1, 3], 5: [2, 3]
+/// ```
+pub struct RankedCombinations {
+    n: usize,
+    k: usize,
+}
+
+impl RankedCombinations {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self { n, k }
+    }
+
+    fn contains(&self, object: &[usize]) -> bool {
+        object.len() == self.k
+            && object.iter().all(|&c| c < self.n)
+            && object.windows(2).all(|w| w[0] < w[1])
+    }
+}
+
+impl Ranking for RankedCombinations {
+    type Object = Vec<usize>;
+
+    fn count(&self) -> BigInt {
+        binomial(self.n, self.k)
+    }
+
+    fn rank(&self, object: &Vec<usize>) -> Option<BigInt> {
+        if !self.contains(object) {
+            return None;
+        }
+        // With d_i = n - 1 - c_i the sum of C(d_i, k - i) counts the combinations that follow this one
+        let after: BigInt = object
+            .iter()
+            .enumerate()
+            .map(|(i, c)| binomial(self.n - 1 - c, self.k - i))
+            .sum();
+        Some(self.count() - 1 - after)
+    }
+
+    fn unrank(&self, index: &BigInt) -> Option<Vec<usize>> {
+        let count = self.count();
+        if index.is_negative() || index >= &count {
+            return None;
+        }
+        let mut m = count - 1 - index;
+        let mut out = Vec::with_capacity(self.k);
+        let mut d = self.n;
+        for j in (1..=self.k).rev() {
+            // Greedily take the largest d with C(d, j) <= m
+            d -= 1;
+            let mut b = binomial(d, j);
+            while b > m {
+                b = b * (d - j) / d;
+                d -= 1;
+            }
+            m -= b;
+            out.push(self.n - 1 - d);
+        }
+        Some(out)
+    }
+
+    fn successor(&self, object: &Vec<usize>) -> Option<Vec<usize>> {
+        if !self.contains(object) {
+            return None;
+        }
+        let mut out = object.clone();
+        let i = (0..self.k).rev().find(|&i| out[i] < self.n - self.k + i)?;
+        out[i] += 1;
+        for j in i + 1..self.k {
+            out[j] = out[j - 1] + 1;
+        }
+        Some(out)
+    }
+}
+
+/// The permutations of 0..n in lexicographic order. The rank of a permutation is its Lehmer code read as a factoradic number.
+///
+/// ```text
+/// n = 3
+/// 0: [0, 1, 2], 1: [0, 2, 1], 2: [1, 0, 2], 3: [1, 2, 0], 4: [2, 0, 1], 5: [2, 1, 0]
+/// ```
+pub struct RankedPermutations {
+    n: usize,
+}
+
+impl RankedPermutations {
+    pub fn new(n: usize) -> Self {
+        Self { n }
+    }
+
+    fn contains(&self, object: &[usize]) -> bool {
+        let mut seen = vec![false; self.n];
+        object.len() == self.n
+            && object
+                .iter()
+                .all(|&p| p < self.n && !std::mem::replace(&mut seen[p], true))
+    }
+}
+
+impl Ranking for RankedPermutations {
+    type Object = Vec<usize>;
+
+    fn count(&self) -> BigInt {
+        (1..=self.n).map(BigInt::from).product()
+    }
+
+    fn rank(&self, object: &Vec<usize>) -> Option<BigInt> {
+        if object.len() != self.n {
+            return None;
+        }
+        let mut seen = vec![false; self.n];
+        let mut out = BigInt::zero();
+        for (i, &p) in object.iter().enumerate() {
+            if p >= self.n || seen[p] {
+                return None;
+            }
+            seen[p] = true;
+            // Lehmer code digit: the number of unused values below p
+            let digit = seen[..p].iter().filter(|s| !**s).count();
+            out = out * (self.n - i) + digit;
+        }
+        Some(out)
+    }
+
+    fn unrank(&self, index: &BigInt) -> Option<Vec<usize>> {
+        if self.n == 0 {
+            return index.is_zero().then(Vec::new);
+        }
+        if index.is_negative() {
+            return None;
+        }
+        let digits = factoradic(index.clone(), self.n)?;
+        let mut unused: Vec<usize> = (0..self.n).collect();
+        Some(
+            digits
+                .iter()
+                .map(|d| unused.remove(d.to_usize().unwrap()))
+                .collect(),
+        )
+    }
+
+    fn successor(&self, object: &Vec<usize>) -> Option<Vec<usize>> {
+        if !self.contains(object) {
+            return None;
+        }
+        let mut out = object.clone();
+        let i = (1..self.n).rev().find(|&i| out[i - 1] < out[i])? - 1;
+        let j = (i + 1..self.n).rev().find(|&j| out[j] > out[i]).unwrap();
+        out.swap(i, j);
+        out[i + 1..].reverse();
+        Some(out)
+    }
+}
+
+// The next composition in lexicographic order among those with the same number of parts, all at least min
+fn next_composition(object: &[usize], min: usize) -> Option<Vec<usize>> {
+    let k = object.len();
+    let mut out = object.to_vec();
+    // Move one unit from the rightmost part that can spare it onto the part before, and everything after that onto the last part
+    let last = (1..k).rev().find(|&i| out[i] > min)?;
+    let z = out[last];
+    out[last - 1] += 1;
+    out[last] = min;
+    out[k - 1] += z - 1 - min;
+    Some(out)
+}
+
+/// The compositions of n with exactly k parts in lexicographic order, matching compositions::CompositionsNK. A composition corresponds to the combination of its k - 1 partial sums.
+///
+/// ```text
+/// n = 5, k = 3
+/// 0: [1, 1, 3], 1: [1, 2, 2], 2: [1, 3, 1], 3: [2, 1, 2], 4: [2, 2, 1], 5: [3, 1, 1]
+/// ```
+pub struct RankedCompositionsNK {
+    n: usize,
+    k: usize,
+}
+
+impl RankedCompositionsNK {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self { n, k }
+    }
+
+    fn contains(&self, object: &[usize]) -> bool {
+        object.len() == self.k && !object.contains(&0) && object.iter().sum::<usize>() == self.n
+    }
+
+    // The partial sums, less one, as a combination from 0..n-1
+    fn cuts(&self) -> Option<RankedCombinations> {
+        if self.k == 0 || self.k > self.n {
+            None
+        } else {
+            Some(RankedCombinations::new(self.n - 1, self.k - 1))
+        }
+    }
+}
+
+impl Ranking for RankedCompositionsNK {
+    type Object = Vec<usize>;
+
+    fn count(&self) -> BigInt {
+        match self.cuts() {
+            Some(cuts) => cuts.count(),
+            None => BigInt::from((self.n == 0 && self.k == 0) as u8),
+        }
+    }
+
+    fn rank(&self, object: &Vec<usize>) -> Option<BigInt> {
+        if !self.contains(object) {
+            return None;
+        }
+        let Some(cuts) = self.cuts() else {
+            return Some(BigInt::zero());
+        };
+        let sums: Vec<usize> = object[..self.k - 1]
+            .iter()
+            .scan(0, |s, c| {
+                *s += c;
+                Some(*s - 1)
+            })
+            .collect();
+        cuts.rank(&sums)
+    }
+
+    fn unrank(&self, index: &BigInt) -> Option<Vec<usize>> {
+        let Some(cuts) = self.cuts() else {
+            return (index.is_zero() && self.n == 0 && self.k == 0).then(Vec::new);
+        };
+        let sums = cuts.unrank(index)?;
+        let mut out = Vec::with_capacity(self.k);
+        let mut prev = 0;
+        for s in sums
+            .into_iter()
+            .map(|s| s + 1)
+            .chain(std::iter::once(self.n))
+        {
+            out.push(s - prev);
+            prev = s;
+        }
+        Some(out)
+    }
+
+    fn successor(&self, object: &Vec<usize>) -> Option<Vec<usize>> {
+        if !self.contains(object) {
+            return None;
+        }
+        next_composition(object, 1)
+    }
+}
+
+/// All compositions of n ordered by decreasing number of parts and then lexicographically, matching compositions::CompositionsN.
+///
+/// ```text
+/// n = 3
+/// 0: [1, 1, 1], 1: [1, 2], 2: [2, 1], 3: [3]
+/// ```
+pub struct RankedCompositionsN {
+    n: usize,
+}
+
+impl RankedCompositionsN {
+    pub fn new(n: usize) -> Self {
+        Self { n }
+    }
+
+    // The number of compositions with more than k parts
+    fn offset(&self, k: usize) -> BigInt {
+        (k + 1..=self.n)
+            .map(|j| RankedCompositionsNK::new(self.n, j).count())
+            .sum()
+    }
+}
+
+impl Ranking for RankedCompositionsN {
+    type Object = Vec<usize>;
+
+    fn count(&self) -> BigInt {
+        if self.n == 0 {
+            BigInt::one()
+        } else {
+            BigInt::one() << (self.n - 1)
+        }
+    }
+
+    fn rank(&self, object: &Vec<usize>) -> Option<BigInt> {
+        let k = object.len();
+        Some(self.offset(k) + RankedCompositionsNK::new(self.n, k).rank(object)?)
+    }
+
+    fn unrank(&self, index: &BigInt) -> Option<Vec<usize>> {
+        if index.is_negative() {
+            return None;
+        }
+        let mut index = index.clone();
+        for k in (0..=self.n).rev() {
+            let family = RankedCompositionsNK::new(self.n, k);
+            let count = family.count();
+            if index < count {
+                return family.unrank(&index);
+            }
+            index -= count;
+        }
+        None
+    }
+
+    fn successor(&self, object: &Vec<usize>) -> Option<Vec<usize>> {
+        if object.contains(&0) || object.iter().sum::<usize>() != self.n {
+            return None;
+        }
+        next_composition(object, 1).or_else(|| {
+            let k = object.len().checked_sub(1).filter(|&k| k > 0)?;
+            let mut out = vec![1; k];
+            out[k - 1] = self.n - k + 1;
+            Some(out)
+        })
+    }
+}
+
+/// The weak compositions of n with exactly k parts in lexicographic order, matching compositions_weak::WeakCompositionsNK. Adding one to every part gives a composition of n + k.
+///
+/// ```text
+/// n = 2, k = 2
+/// 0: [0, 2], 1: [1, 1], 2: [2, 0]
+/// ```
+pub struct RankedWeakCompositionsNK {
+    n: usize,
+    k: usize,
+    shifted: RankedCompositionsNK,
+}
+
+impl RankedWeakCompositionsNK {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self {
+            n,
+            k,
+            shifted: RankedCompositionsNK::new(n + k, k),
+        }
+    }
+}
+
+impl Ranking for RankedWeakCompositionsNK {
+    type Object = Vec<usize>;
+
+    fn count(&self) -> BigInt {
+        self.shifted.count()
+    }
+
+    fn rank(&self, object: &Vec<usize>) -> Option<BigInt> {
+        self.shifted.rank(&object.iter().map(|c| c + 1).collect())
+    }
+
+    fn unrank(&self, index: &BigInt) -> Option<Vec<usize>> {
+        Some(
+            self.shifted
+                .unrank(index)?
+                .into_iter()
+                .map(|c| c - 1)
+                .collect(),
+        )
+    }
+
+    fn successor(&self, object: &Vec<usize>) -> Option<Vec<usize>> {
+        if object.len() != self.k || object.iter().sum::<usize>() != self.n {
+            return None;
+        }
+        next_composition(object, 0)
+    }
+}
+
+/// The partitions of the set 0..n as restricted growth strings in lexicographic order. Element i is in block a_i, where a_0 = 0 and each a_i is at most one more than every value before it. The count is the Bell number.
+///
+/// ```text
+/// n = 3
+/// 0: [0, 0, 0], 1: [0, 0, 1], 2: [0, 1, 0], 3: [0, 1, 1], 4: [0, 1, 2]
+/// ```
+pub struct RankedSetPartitions {
+    n: usize,
+    // completions[i][b] is the number of ways to fill i more positions when b blocks are in use
+    completions: Vec<Vec<BigInt>>,
+}
+
+impl RankedSetPartitions {
+    pub fn new(n: usize) -> Self {
+        let mut completions = vec![vec![BigInt::one(); n + 2]];
+        for i in 1..n {
+            let prev: &Vec<BigInt> = &completions[i - 1];
+            let row = (0..=n)
+                .map(|b| &prev[b] * b + &prev[b + 1])
+                .chain(std::iter::once(BigInt::zero()))
+                .collect();
+            completions.push(row);
+        }
+        Self { n, completions }
+    }
+
+    // A restricted growth string of length n, where each value opens at most one new block
+    fn contains(&self, object: &[usize]) -> bool {
+        let mut blocks = 0;
+        object.len() == self.n
+            && object.iter().all(|&a| {
+                let allowed = a <= blocks;
+                blocks = blocks.max(a + 1);
+                allowed
+            })
+    }
+}
+
+impl Ranking for RankedSetPartitions {
+    type Object = Vec<usize>;
+
+    fn count(&self) -> BigInt {
+        if self.n == 0 {
+            BigInt::one()
+        } else {
+            self.completions[self.n - 1][1].clone()
+        }
+    }
+
+    fn rank(&self, object: &Vec<usize>) -> Option<BigInt> {
+        if object.len() != self.n || object.first().is_some_and(|&a| a != 0) {
+            return None;
+        }
+        let mut out = BigInt::zero();
+        let mut blocks = 1;
+        for (i, &a) in object.iter().enumerate().skip(1) {
+            if a > blocks {
+                return None;
+            }
+            // Each smaller value keeps the number of blocks the same
+            out += &self.completions[self.n - 1 - i][blocks] * a;
+            blocks = blocks.max(a + 1);
+        }
+        Some(out)
+    }
+
+    fn unrank(&self, index: &BigInt) -> Option<Vec<usize>> {
+        if index.is_negative() || index >= &self.count() {
+            return None;
+        }
+        let mut index = index.clone();
+        let mut out = vec![0; self.n];
+        let mut blocks = 1;
+        for (i, a) in out.iter_mut().enumerate().skip(1) {
+            let rest = &self.completions[self.n - 1 - i][blocks];
+            let old = rest * blocks;
+            if index < old {
+                *a = (&index / rest).to_usize().unwrap();
+                index %= rest;
+            } else {
+                *a = blocks;
+                index -= old;
+                blocks += 1;
+            }
+        }
+        Some(out)
+    }
+
+    fn successor(&self, object: &Vec<usize>) -> Option<Vec<usize>> {
+        if !self.contains(object) {
+            return None;
+        }
+        // limit[i] is the largest value allowed at position i
+        let limits: Vec<usize> = object
+            .iter()
+            .scan(0, |max, &a| {
+                let limit = *max + 1;
+                *max = (*max).max(a);
+                Some(limit)
+            })
+            .collect();
+        let i = (1..self.n).rev().find(|&i| object[i] < limits[i])?;
+        let mut out = object.clone();
+        out[i] += 1;
+        out[i + 1..].fill(0);
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compositions::{CompositionsN, CompositionsNK};
+    use crate::compositions_weak::WeakCompositionsNK;
+    use crate::core::combinations::Combinations;
+    use crate::rngs::SplitMix64;
+    use rayon::prelude::*;
+
+    // The ranking must agree with the enumeration at every index and in every direction
+    fn check<R: Ranking<Object = Vec<usize>>>(family: &R, expected: Vec<Vec<usize>>) {
+        assert_eq!(family.count(), BigInt::from(expected.len()));
+        for (i, object) in expected.iter().enumerate() {
+            let i = BigInt::from(i);
+            assert_eq!(family.rank(object), Some(i.clone()), "rank of {object:?}");
+            assert_eq!(family.unrank(&i).as_ref(), Some(object));
+        }
+        assert_eq!(
+            family.iter_from(&BigInt::zero()).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(family.unrank(&family.count()), None);
+        assert_eq!(family.unrank(&BigInt::from(-1)), None);
+    }
+
+    #[test]
+    fn matches_enumerators() {
+        for n in 1..7 {
+            for k in 1..=n {
+                check(
+                    &RankedCombinations::new(n, k),
+                    Combinations::new((0..n).collect(), k).collect(),
+                );
+                check(
+                    &RankedCompositionsNK::new(n, k),
+                    CompositionsNK::new(n, k).collect(),
+                );
+                check(
+                    &RankedWeakCompositionsNK::new(n, k),
+                    WeakCompositionsNK::new(n, k).collect(),
+                );
+            }
+            check(
+                &RankedCompositionsN::new(n),
+                CompositionsN::new(n).collect(),
+            );
+        }
+        check(
+            &RankedPermutations::new(3),
+            vec![
+                vec![0, 1, 2],
+                vec![0, 2, 1],
+                vec![1, 0, 2],
+                vec![1, 2, 0],
+                vec![2, 0, 1],
+                vec![2, 1, 0],
+            ],
+        );
+        check(
+            &RankedSetPartitions::new(3),
+            vec![
+                vec![0, 0, 0],
+                vec![0, 0, 1],
+                vec![0, 1, 0],
+                vec![0, 1, 1],
+                vec![0, 1, 2],
+            ],
+        );
+    }
+
+    #[test]
+    fn consistency() {
+        for n in 0..8 {
+            let perms = RankedPermutations::new(n);
+            let all: Vec<Vec<usize>> = perms.iter_from(&BigInt::zero()).collect();
+            assert_eq!(BigInt::from(all.len()), perms.count());
+            for (i, p) in all.iter().enumerate() {
+                assert_eq!(perms.rank(p), Some(BigInt::from(i)));
+            }
+            let partitions = RankedSetPartitions::new(n);
+            let all: Vec<Vec<usize>> = partitions.iter_from(&BigInt::zero()).collect();
+            assert_eq!(BigInt::from(all.len()), partitions.count());
+            for (i, p) in all.iter().enumerate() {
+                assert_eq!(partitions.unrank(&BigInt::from(i)).as_ref(), Some(p));
+            }
+        }
+        // Bell numbers
+        assert_eq!(RankedSetPartitions::new(10).count(), BigInt::from(115975));
+        assert_eq!(RankedWeakCompositionsNK::new(0, 3).count(), BigInt::one());
+        assert_eq!(RankedCombinations::new(2, 3).count(), BigInt::zero());
+        assert_eq!(RankedCombinations::new(5, 2).rank(&vec![3, 1]), None);
+        assert_eq!(RankedPermutations::new(3).rank(&vec![0, 0, 1]), None);
+        assert_eq!(RankedSetPartitions::new(3).rank(&vec![0, 2, 1]), None);
+    }
+
+    #[test]
+    fn successor_of_non_members() {
+        let combinations = RankedCombinations::new(5, 2);
+        assert_eq!(combinations.successor(&vec![0]), None);
+        assert_eq!(combinations.successor(&vec![3, 1]), None);
+        assert_eq!(combinations.successor(&vec![1, 7]), None);
+        let perms = RankedPermutations::new(3);
+        assert_eq!(perms.successor(&vec![0]), None);
+        assert_eq!(perms.successor(&vec![0, 0, 1]), None);
+        assert_eq!(perms.successor(&vec![0, 1, 2, 3]), None);
+        let compositions = RankedCompositionsNK::new(5, 3);
+        assert_eq!(compositions.successor(&vec![5]), None);
+        assert_eq!(compositions.successor(&vec![0, 2, 3]), None);
+        assert_eq!(compositions.successor(&vec![1, 1, 1]), None);
+        let compositions = RankedCompositionsN::new(3);
+        assert_eq!(compositions.successor(&vec![]), None);
+        assert_eq!(compositions.successor(&vec![2, 2]), None);
+        assert_eq!(compositions.successor(&vec![0, 3]), None);
+        let partitions = RankedSetPartitions::new(3);
+        assert_eq!(partitions.successor(&vec![0]), None);
+        assert_eq!(partitions.successor(&vec![1, 0, 0]), None);
+        assert_eq!(partitions.successor(&vec![0, 2, 1]), None);
+        // Members still advance
+        assert_eq!(perms.successor(&vec![0, 2, 1]), Some(vec![1, 0, 2]));
+        assert_eq!(partitions.successor(&vec![0, 1, 0]), Some(vec![0, 1, 1]));
+    }
+
+    #[test]
+    fn large_indices() {
+        let family = RankedCombinations::new(100, 50);
+        let last = family.count() - 1;
+        assert_eq!(family.unrank(&last), Some((50..100).collect()));
+        let perms = RankedPermutations::new(30);
+        let index: BigInt = "123456789012345678901234567890".parse().unwrap();
+        let p = perms.unrank(&index).unwrap();
+        assert_eq!(perms.rank(&p), Some(index.clone()));
+        assert_eq!(perms.rank(&perms.successor(&p).unwrap()), Some(index + 1));
+        let partitions = RankedSetPartitions::new(40);
+        let last = partitions.count() - 1;
+        assert_eq!(partitions.unrank(&last), Some((0..40).collect()));
+    }
+
+    #[test]
+    fn parallel_ranges() {
+        let family = RankedSetPartitions::new(7);
+        let sequential: Vec<Vec<usize>> = family.iter_from(&BigInt::zero()).collect();
+        let parallel: Vec<Vec<usize>> = family
+            .split_ranges(5)
+            .into_par_iter()
+            .flat_map_iter(|(start, end)| {
+                let len = (&end - &start).to_usize().unwrap();
+                family.iter_from(&start).take(len).collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn sampling() {
+        let family = RankedPermutations::new(3);
+        let mut rng = SplitMix64::new(17);
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            let p = family.sample(&mut rng).unwrap();
+            counts[family.rank(&p).unwrap().to_usize().unwrap()] += 1;
+        }
+        assert!(counts.iter().all(|&c| (900..1100).contains(&c)));
+    }
+}
@@ -0,0 +1,695 @@
+use crate::partition::PartitionsN;
+use num::{BigInt, CheckedAdd, One, Zero};
+
+/// A restriction on the parts of a partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionRestriction {
+    /// Every partition
+    Unrestricted,
+    /// No part appears more than once
+    Distinct,
+    /// Every part is odd
+    Odd,
+    /// There are at most k parts
+    AtMostParts(usize),
+    /// No part is greater than m
+    BoundedParts(usize),
+    /// Every part is in the given set
+    PartsFrom(Vec<usize>),
+}
+
+impl PartitionRestriction {
+    /// The parts allowed in partitions of n, in increasing order.
+    fn allowed_parts(&self, n: usize) -> Vec<usize> {
+        match self {
+            Self::Odd => (1..=n).step_by(2).collect(),
+            Self::BoundedParts(m) => (1..=n.min(*m)).collect(),
+            Self::PartsFrom(set) => {
+                let mut parts: Vec<usize> =
+                    set.iter().copied().filter(|p| *p > 0 && *p <= n).collect();
+                parts.sort();
+                parts.dedup();
+                parts
+            }
+            _ => (1..=n).collect(),
+        }
+    }
+
+    /// The number of times a part may be used.
+    fn multiplicity(&self) -> usize {
+        if *self == Self::Distinct {
+            1
+        } else {
+            usize::MAX
+        }
+    }
+
+    /// The counting sequence only needs allowed parts and multiplicities. By conjugation partitions into at most k parts are counted as partitions into parts no greater than k.
+    fn counting_form(&self) -> Self {
+        match self {
+            Self::AtMostParts(k) => Self::BoundedParts(*k),
+            other => other.clone(),
+        }
+    }
+}
+
+/// A factor of a generating function for partitions, either 1/(1 - x^p) or 1 + x^p.
+#[derive(Debug, Clone, Copy)]
+enum Factor {
+    Unlimited(usize),
+    Once(usize),
+}
+
+/// Multiply the series in values, which is 1 in the constant term, by each factor. Returns the number of coefficients that did not overflow.
+fn apply_factors<T: Clone + CheckedAdd>(values: &mut [T], factors: &[Factor]) -> usize {
+    let mut valid = values.len();
+    for factor in factors {
+        match *factor {
+            Factor::Unlimited(p) => {
+                for i in p..valid {
+                    match values[i].checked_add(&values[i - p]) {
+                        Some(v) => values[i] = v,
+                        None => {
+                            valid = i;
+                            break;
+                        }
+                    }
+                }
+            }
+            Factor::Once(p) => {
+                for i in (p..valid).rev() {
+                    match values[i].checked_add(&values[i - p]) {
+                        Some(v) => values[i] = v,
+                        None => valid = i,
+                    }
+                }
+            }
+        }
+    }
+    valid
+}
+
+/// Shared state for counting sequences given by products of factors. Coefficients are recomputed with twice the length whenever more are needed.
+struct FactorCounts<T> {
+    values: Vec<T>,
+    valid: usize,
+    ctr: usize,
+    factors: fn(usize, &PartitionRestriction) -> Vec<Factor>,
+    restriction: PartitionRestriction,
+}
+
+impl<T: Clone + Zero + One + CheckedAdd> FactorCounts<T> {
+    fn new(
+        factors: fn(usize, &PartitionRestriction) -> Vec<Factor>,
+        restriction: PartitionRestriction,
+    ) -> Self {
+        Self {
+            values: Vec::new(),
+            valid: 0,
+            ctr: 0,
+            factors,
+            restriction,
+        }
+    }
+
+    fn next(&mut self) -> Option<T> {
+        if self.ctr >= self.valid {
+            // Once overflow has occured no more values can be computed
+            if self.valid < self.values.len() {
+                return None;
+            }
+            let len = (2 * self.values.len()).max(32);
+            let mut values = vec![T::zero(); len];
+            values[0] = T::one();
+            let factors: Vec<Factor> = (1..len)
+                .flat_map(|p| (self.factors)(p, &self.restriction))
+                .collect();
+            self.valid = apply_factors(&mut values, &factors);
+            self.values = values;
+            if self.ctr >= self.valid {
+                return None;
+            }
+        }
+        let out = self.values[self.ctr].clone();
+        self.ctr += 1;
+        Some(out)
+    }
+}
+
+fn restricted_factors(p: usize, restriction: &PartitionRestriction) -> Vec<Factor> {
+    if !restriction.allowed_parts(p).contains(&p) {
+        vec![]
+    } else if restriction.multiplicity() == 1 {
+        vec![Factor::Once(p)]
+    } else {
+        vec![Factor::Unlimited(p)]
+    }
+}
+
+/// The number of restricted partitions of each non-negative integer.
+///
+/// ```text
+/// Distinct parts (equal to Odd parts)
+/// 1, 1, 1, 2, 2, 3, 4, 5, 6, 8, 10, 12, 15, 18, 22, 27, 32, 38, 46...
+///
+/// At most 3 parts (equal to parts no greater than 3)
+/// 1, 1, 2, 3, 4, 5, 7, 8, 10, 12, 14, 16, 19, 21, 24, 27, 30, 33...
+///
+/// Parts from [1, 2, 5, 10]
+/// 1, 1, 2, 2, 3, 4, 5, 6, 7, 8, 11, 12, 15, 16, 19, 22, 25, 28...
+/// ```
+pub struct RestrictedPartition<T> {
+    counts: FactorCounts<T>,
+}
+
+impl RestrictedPartition<crate::Number> {
+    pub fn new(restriction: PartitionRestriction) -> Self {
+        Self {
+            counts: FactorCounts::new(restricted_factors, restriction.counting_form()),
+        }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl RestrictedPartition<BigInt> {
+    pub fn new_big(restriction: PartitionRestriction) -> Self {
+        Self {
+            counts: FactorCounts::new(restricted_factors, restriction.counting_form()),
+        }
+    }
+}
+
+impl<T: Clone + Zero + One + CheckedAdd> Iterator for RestrictedPartition<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.counts.next()
+    }
+}
+
+/// The number of plane partitions of each non-negative integer. The generating function is MacMahon's product of 1/(1 - x^k)^k.
+///
+/// ```text
+/// 1, 1, 3, 6, 13, 24, 48, 86, 160, 282, 500, 859, 1479, 2485, 4167...
+/// ```
+pub struct PlanePartition<T> {
+    counts: FactorCounts<T>,
+}
+
+fn plane_factors(p: usize, _: &PartitionRestriction) -> Vec<Factor> {
+    vec![Factor::Unlimited(p); p]
+}
+
+impl PlanePartition<crate::Number> {
+    pub fn new() -> Self {
+        Self {
+            counts: FactorCounts::new(plane_factors, PartitionRestriction::Unrestricted),
+        }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl PlanePartition<BigInt> {
+    pub fn new_big() -> Self {
+        Self {
+            counts: FactorCounts::new(plane_factors, PartitionRestriction::Unrestricted),
+        }
+    }
+}
+
+impl<T: Clone + Zero + One + CheckedAdd> Iterator for PlanePartition<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.counts.next()
+    }
+}
+
+/// The number of overpartitions of each non-negative integer. An overpartition is a partition where the first occurrence of each part may be overlined.
+///
+/// ```text
+/// 1, 2, 4, 8, 14, 24, 40, 64, 100, 154, 232, 344, 504, 728, 1040...
+/// ```
+pub struct Overpartition<T> {
+    counts: FactorCounts<T>,
+}
+
+fn over_factors(p: usize, _: &PartitionRestriction) -> Vec<Factor> {
+    vec![Factor::Unlimited(p), Factor::Once(p)]
+}
+
+impl Overpartition<crate::Number> {
+    pub fn new() -> Self {
+        Self {
+            counts: FactorCounts::new(over_factors, PartitionRestriction::Unrestricted),
+        }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl Overpartition<BigInt> {
+    pub fn new_big() -> Self {
+        Self {
+            counts: FactorCounts::new(over_factors, PartitionRestriction::Unrestricted),
+        }
+    }
+}
+
+impl<T: Clone + Zero + One + CheckedAdd> Iterator for Overpartition<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.counts.next()
+    }
+}
+
+/// The restricted partitions of a non-negative integer with parts in increasing order. They are produced in lexicographic order, the same order as PartitionsN, and each can be ranked and unranked directly.
+///
+/// ```text
+/// n = 8, Distinct
+/// [1, 2, 5], [1, 3, 4], [1, 7], [2, 6], [3, 5], [8]
+///
+/// n = 6, AtMostParts(2)
+/// [1, 5], [2, 4], [3, 3], [6]
+/// ```
+pub struct RestrictedPartitionsN {
+    n: usize,
+    parts: Vec<usize>,
+    multiplicity: usize,
+    max_len: Option<usize>,
+    /// The number of ways to complete a partition, indexed by remaining sum, position in parts, and remaining length
+    table: Vec<u128>,
+    index: u128,
+}
+
+impl RestrictedPartitionsN {
+    /// Only usize output is available.
+    /// Panics if the number of partitions does not fit in a u128.
+    pub fn new(n: usize, restriction: PartitionRestriction) -> Self {
+        let parts = restriction.allowed_parts(n);
+        let multiplicity = restriction.multiplicity();
+        let max_len = match restriction {
+            PartitionRestriction::AtMostParts(k) => Some(k.min(n)),
+            _ => None,
+        };
+        let mut rp = Self {
+            n,
+            parts,
+            multiplicity,
+            max_len,
+            table: Vec::new(),
+            index: 0,
+        };
+        rp.fill_table();
+        rp
+    }
+
+    fn lengths(&self) -> usize {
+        self.max_len.map_or(1, |k| k + 1)
+    }
+
+    fn table_index(&self, r: usize, i: usize, l: usize) -> usize {
+        (r * (self.parts.len() + 1) + i) * self.lengths() + l
+    }
+
+    /// The remaining length is ignored when the number of parts is not limited.
+    fn ways(&self, r: usize, i: usize, l: usize) -> u128 {
+        self.table[self.table_index(r, i, l)]
+    }
+
+    /// The multiplicities that may be used for the part at position i, from most to fewest, which is lexicographic order.
+    fn choices(&self, r: usize, i: usize, l: usize) -> impl Iterator<Item = usize> {
+        let mut most = (r / self.parts[i]).min(self.multiplicity);
+        if self.max_len.is_some() {
+            most = most.min(l);
+        }
+        (0..=most).rev()
+    }
+
+    fn next_length(&self, l: usize, j: usize) -> usize {
+        if self.max_len.is_some() { l - j } else { 0 }
+    }
+
+    fn fill_table(&mut self) {
+        let size = (self.n + 1) * (self.parts.len() + 1) * self.lengths();
+        self.table = vec![0; size];
+        for i in (0..=self.parts.len()).rev() {
+            for r in 0..=self.n {
+                for l in 0..self.lengths() {
+                    let w = if r == 0 {
+                        1
+                    } else if i == self.parts.len() {
+                        0
+                    } else {
+                        self.choices(r, i, l)
+                            .map(|j| {
+                                self.ways(r - j * self.parts[i], i + 1, self.next_length(l, j))
+                            })
+                            .try_fold(0_u128, |acc, w| acc.checked_add(w))
+                            .expect("too many partitions to count")
+                    };
+                    let p = self.table_index(r, i, l);
+                    self.table[p] = w;
+                }
+            }
+        }
+    }
+
+    /// The number of partitions.
+    pub fn total(&self) -> u128 {
+        self.ways(self.n, 0, self.lengths() - 1)
+    }
+
+    /// The position of a partition in lexicographic order. The parts may be given in any order. Returns None if it is not a valid partition.
+    pub fn rank(&self, partition: &[usize]) -> Option<u128> {
+        let mut sorted = partition.to_vec();
+        sorted.sort();
+        if sorted.iter().sum::<usize>() != self.n {
+            return None;
+        }
+        let (mut r, mut l) = (self.n, self.lengths() - 1);
+        let mut rank = 0;
+        let mut pos = 0;
+        for i in 0..self.parts.len() {
+            let p = self.parts[i];
+            let mut used = 0;
+            while pos < sorted.len() && sorted[pos] == p {
+                used += 1;
+                pos += 1;
+            }
+            let mut valid = false;
+            for j in self.choices(r, i, l) {
+                if j == used {
+                    valid = true;
+                    break;
+                }
+                rank += self.ways(r - j * p, i + 1, self.next_length(l, j));
+            }
+            if !valid {
+                return None;
+            }
+            r -= used * p;
+            l = self.next_length(l, used);
+        }
+        // Any part left over was not allowed
+        (pos == sorted.len()).then_some(rank)
+    }
+
+    /// The partition at the given position in lexicographic order. Returns None if the position is not less than the total.
+    pub fn unrank(&self, mut index: u128) -> Option<Vec<usize>> {
+        if index >= self.total() {
+            return None;
+        }
+        let (mut r, mut l) = (self.n, self.lengths() - 1);
+        let mut partition = Vec::new();
+        for i in 0..self.parts.len() {
+            if r == 0 {
+                break;
+            }
+            for j in self.choices(r, i, l) {
+                let w = self.ways(r - j * self.parts[i], i + 1, self.next_length(l, j));
+                if index < w {
+                    partition.extend(std::iter::repeat_n(self.parts[i], j));
+                    r -= j * self.parts[i];
+                    l = self.next_length(l, j);
+                    break;
+                }
+                index -= w;
+            }
+        }
+        Some(partition)
+    }
+
+    /// Jump so that the next partition is the one at the given position.
+    pub fn seek(&mut self, index: u128) {
+        self.index = index;
+    }
+}
+
+impl Iterator for RestrictedPartitionsN {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.unrank(self.index)?;
+        self.index += 1;
+        Some(out)
+    }
+}
+
+/// The conjugate of a partition, found by exchanging the rows and columns of its Ferrers diagram. The parts may be given in any order and the result is in increasing order.
+pub fn conjugate(partition: &[usize]) -> Vec<usize> {
+    let largest = partition.iter().copied().max().unwrap_or(0);
+    let mut out: Vec<usize> = (1..=largest)
+        .map(|i| partition.iter().filter(|p| **p >= i).count())
+        .collect();
+    out.reverse();
+    out
+}
+
+/// The Ferrers diagram of a partition with one row of dots for each part, largest first.
+///
+/// ```text
+/// [1, 2, 4]
+/// ● ● ● ●
+/// ● ●
+/// ●
+/// ```
+pub fn ferrers_diagram(partition: &[usize]) -> String {
+    let mut sorted = partition.to_vec();
+    sorted.sort_by(|a, b| b.cmp(a));
+    sorted
+        .iter()
+        .map(|p| vec!["●"; *p].join(" "))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The plane partitions of a non-negative integer. Each is a list of rows where both the rows and the columns are weakly decreasing.
+///
+/// ```text
+/// n = 3
+/// [[1], [1], [1]], [[1, 1], [1]], [[2], [1]], [[1, 1, 1]], [[2, 1]], [[3]]
+/// ```
+pub struct PlanePartitionsN {
+    partitions: std::vec::IntoIter<Vec<Vec<usize>>>,
+}
+
+impl PlanePartitionsN {
+    /// Only usize output is available. All of the plane partitions are found when this is created.
+    pub fn new(n: usize) -> Self {
+        let mut out = Vec::new();
+        Self::extend(n, None, &mut Vec::new(), &mut out);
+        Self {
+            partitions: out.into_iter(),
+        }
+    }
+
+    /// Add every row that fits below the previous one and continue with what remains.
+    fn extend(
+        remaining: usize,
+        above: Option<&[usize]>,
+        rows: &mut Vec<Vec<usize>>,
+        out: &mut Vec<Vec<Vec<usize>>>,
+    ) {
+        if remaining == 0 {
+            out.push(rows.clone());
+            return;
+        }
+        for size in 1..=remaining {
+            for mut row in PartitionsN::new(size) {
+                row.reverse();
+                let fits = match above {
+                    None => true,
+                    Some(prev) => {
+                        row.len() <= prev.len() && row.iter().zip(prev).all(|(a, b)| a <= b)
+                    }
+                };
+                if fits {
+                    rows.push(row.clone());
+                    Self::extend(remaining - size, Some(&row), rows, out);
+                    rows.pop();
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for PlanePartitionsN {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.partitions.next()
+    }
+}
+
+/// The overpartitions of a non-negative integer, with parts in increasing order. Each part is paired with whether it is overlined, which is only possible for the first occurrence of each value.
+///
+/// ```text
+/// n = 2
+/// [(1, false), (1, false)], [(1, true), (1, false)], [(2, false)], [(2, true)]
+/// ```
+pub struct OverpartitionsN {
+    partitions: PartitionsN,
+    current: Option<Vec<usize>>,
+    /// Indices of the first occurrence of each distinct part in current
+    firsts: Vec<usize>,
+    mask: u64,
+}
+
+impl OverpartitionsN {
+    /// Only usize output is available.
+    pub fn new(n: usize) -> Self {
+        Self {
+            partitions: PartitionsN::new(n),
+            current: None,
+            firsts: Vec::new(),
+            mask: 0,
+        }
+    }
+}
+
+impl Iterator for OverpartitionsN {
+    type Item = Vec<(usize, bool)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Each partition gives one overpartition for every choice of overlined parts
+        if self.current.is_none() || self.mask >> self.firsts.len() != 0 {
+            let p = self.partitions.next()?;
+            self.firsts = (0..p.len())
+                .filter(|i| *i == 0 || p[*i] != p[*i - 1])
+                .collect();
+            self.current = Some(p);
+            self.mask = 0;
+        }
+        let mut out: Vec<(usize, bool)> =
+            self.current.as_ref()?.iter().map(|p| (*p, false)).collect();
+        for (bit, i) in self.firsts.iter().enumerate() {
+            out[*i].1 = (self.mask >> bit) & 1 == 1;
+        }
+        self.mask += 1;
+        Some(out)
+    }
+}
+
+crate::check_sequences!(
+    RestrictedPartition::new(PartitionRestriction::Distinct), [1, 1, 1, 2, 2, 3, 4, 5, 6, 8, 10, 12, 15, 18, 22, 27, 32, 38, 46, 54, 64];
+    RestrictedPartition::new(PartitionRestriction::Odd), [1, 1, 1, 2, 2, 3, 4, 5, 6, 8, 10, 12, 15, 18, 22, 27, 32, 38, 46, 54, 64];
+    RestrictedPartition::new(PartitionRestriction::AtMostParts(3)), [1, 1, 2, 3, 4, 5, 7, 8, 10, 12, 14, 16, 19, 21, 24, 27, 30, 33, 37, 40, 44];
+    RestrictedPartition::new(PartitionRestriction::BoundedParts(3)), [1, 1, 2, 3, 4, 5, 7, 8, 10, 12, 14, 16, 19, 21, 24, 27, 30, 33, 37, 40, 44];
+    RestrictedPartition::new(PartitionRestriction::PartsFrom(vec![1, 2, 5, 10])), [1, 1, 2, 2, 3, 4, 5, 6, 7, 8, 11, 12, 15, 16, 19, 22, 25, 28, 31, 34, 40];
+    RestrictedPartition::new_big(PartitionRestriction::Unrestricted), [1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42, 56, 77, 101, 135, 176, 231];
+    PlanePartition::new(), [1, 1, 3, 6, 13, 24, 48, 86, 160, 282, 500, 859, 1479, 2485, 4167, 6879, 11297, 18334, 29601, 47330, 75278];
+    Overpartition::new(), [1, 2, 4, 8, 14, 24, 40, 64, 100, 154, 232, 344, 504, 728, 1040, 1472, 2062, 2864, 3948, 5400, 7336];
+);
+
+crate::sample_sequences!(
+    RestrictedPartitionsN::new(8, PartitionRestriction::Distinct).map(|x| format!("{x:?}"));
+    RestrictedPartitionsN::new(6, PartitionRestriction::AtMostParts(2)).map(|x| format!("{x:?}"));
+    PlanePartitionsN::new(3).map(|x| format!("{x:?}"));
+    OverpartitionsN::new(2).map(|x| format!("{x:?}"));
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partition::Partition;
+    use itertools::Itertools;
+
+    #[test]
+    fn enumerators_agree_with_counts() {
+        let restrictions = [
+            PartitionRestriction::Unrestricted,
+            PartitionRestriction::Distinct,
+            PartitionRestriction::Odd,
+            PartitionRestriction::AtMostParts(3),
+            PartitionRestriction::BoundedParts(3),
+            PartitionRestriction::PartsFrom(vec![10, 5, 2, 1]),
+        ];
+        for restriction in restrictions {
+            let counts: Vec<i64> = RestrictedPartition::new(restriction.clone())
+                .take(16)
+                .collect();
+            for n in 0..16 {
+                let partitions = RestrictedPartitionsN::new(n, restriction.clone()).collect_vec();
+                assert_eq!(partitions.len() as i64, counts[n]);
+                assert!(partitions.windows(2).all(|w| w[0] < w[1]));
+                assert!(partitions.iter().all(|p| p.iter().sum::<usize>() == n));
+            }
+        }
+        assert!(
+            RestrictedPartition::new(PartitionRestriction::Unrestricted)
+                .take(50)
+                .eq(Partition::new().take(50))
+        );
+        // Overflow ends the sequence
+        assert!(RestrictedPartition::new(PartitionRestriction::Unrestricted).count() < 500);
+    }
+
+    #[test]
+    fn unrestricted_matches_partitions_n() {
+        for n in 0..12 {
+            assert!(
+                RestrictedPartitionsN::new(n, PartitionRestriction::Unrestricted)
+                    .eq(PartitionsN::new(n))
+            );
+        }
+    }
+
+    #[test]
+    fn rank_and_unrank() {
+        let rp = RestrictedPartitionsN::new(100, PartitionRestriction::Unrestricted);
+        assert_eq!(rp.total(), 190569292);
+        assert_eq!(rp.unrank(0), Some(vec![1; 100]));
+        assert_eq!(rp.unrank(190569291), Some(vec![100]));
+        assert_eq!(rp.unrank(190569292), None);
+        for i in [0, 1, 12345, 98765432, 190569291] {
+            let p = rp.unrank(i).unwrap();
+            assert_eq!(rp.rank(&p), Some(i));
+        }
+        assert_eq!(rp.rank(&[50, 49]), None);
+
+        let mut rp = RestrictedPartitionsN::new(30, PartitionRestriction::Distinct);
+        let all = rp.by_ref().collect_vec();
+        for (i, p) in all.iter().enumerate() {
+            assert_eq!(rp.rank(p), Some(i as u128));
+        }
+        assert_eq!(rp.rank(&[15, 15]), None);
+        rp.seek(100);
+        assert_eq!(rp.next().as_ref(), all.get(100));
+
+        let rp = RestrictedPartitionsN::new(10, PartitionRestriction::AtMostParts(2));
+        assert_eq!(rp.rank(&[1, 1, 8]), None);
+        assert_eq!(rp.rank(&[4, 6]), Some(3));
+    }
+
+    #[test]
+    fn conjugation() {
+        assert_eq!(conjugate(&[1, 2, 4]), vec![1, 1, 2, 3]);
+        assert_eq!(conjugate(&[]), Vec::<usize>::new());
+        for p in PartitionsN::new(10) {
+            assert_eq!(conjugate(&conjugate(&p)), p);
+        }
+        // Partitions into at most k parts are conjugate to partitions with parts no greater than k
+        let mut a = RestrictedPartitionsN::new(12, PartitionRestriction::AtMostParts(4))
+            .map(|p| conjugate(&p))
+            .collect_vec();
+        a.sort();
+        let b = RestrictedPartitionsN::new(12, PartitionRestriction::BoundedParts(4)).collect_vec();
+        assert_eq!(a, b);
+        assert_eq!(ferrers_diagram(&[1, 2, 4]), "● ● ● ●\n● ●\n●");
+    }
+
+    #[test]
+    fn plane_and_over() {
+        let plane: Vec<i64> = PlanePartition::new().take(9).collect();
+        let over: Vec<i64> = Overpartition::new().take(9).collect();
+        for n in 0..9 {
+            let planes = PlanePartitionsN::new(n).collect_vec();
+            assert_eq!(planes.len() as i64, plane[n]);
+            assert!(planes.iter().all_unique());
+            let overs = OverpartitionsN::new(n).collect_vec();
+            assert_eq!(overs.len() as i64, over[n]);
+            assert!(overs.iter().all_unique());
+        }
+    }
+}
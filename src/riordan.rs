@@ -0,0 +1,322 @@
+use std::rc::Rc;
+
+use num::{CheckedAdd, CheckedMul, CheckedSub, Integer};
+
+use crate::core::triangle::Triangle;
+
+/// A Riordan array, the lower triangular matrix whose kth column has generating function d(x) h(x)^k where d(0) is not zero, h(0) = 0 and h'(0) is not zero.
+/// Rows are computed as they are needed and shared between clones. Products and inverses of Riordan arrays are again Riordan arrays, and for any of them d and h can be recovered from the first two columns.
+/// Every sequence ends early rather than overflowing or producing a value that is not an integer.
+///
+/// ```text
+/// d(x) = 1/(1 - x), h(x) = x/(1 - x) gives Pascal's triangle
+/// [1], [1, 1], [1, 2, 1], [1, 3, 3, 1], [1, 4, 6, 4, 1]...
+/// ```
+#[derive(Clone)]
+pub struct RiordanArray<T> {
+    triangle: Rc<Triangle<T>>,
+}
+
+impl<T: Clone + Integer + CheckedAdd + CheckedMul + CheckedSub + 'static> RiordanArray<T> {
+    /// The Riordan array of the power series with the given coefficients.
+    /// Panics if d(0) is zero, h(0) is not zero or h'(0) is zero.
+    pub fn new<D, H>(mut d: D, mut h: H) -> Self
+    where
+        D: Iterator<Item = T> + 'static,
+        H: Iterator<Item = T> + 'static,
+    {
+        let d0 = d
+            .next()
+            .filter(|d0| !d0.is_zero())
+            .expect("d(0) must not be zero");
+        assert!(h.next().is_some_and(|h0| h0.is_zero()), "h(0) must be zero");
+        let h1 = h
+            .next()
+            .filter(|h1| !h1.is_zero())
+            .expect("h'(0) must not be zero");
+        Self::build(std::iter::once(d0).chain(d), std::iter::once(h1).chain(h))
+    }
+
+    // The rows for d and for h without its constant term, which are not checked
+    fn build<D, H>(mut d: D, mut h: H) -> Self
+    where
+        D: Iterator<Item = T> + 'static,
+        H: Iterator<Item = T> + 'static,
+    {
+        // h_coeffs[i - 1] is the coefficient of x^i in h
+        let mut h_coeffs: Vec<T> = Vec::new();
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        let rows_iter = std::iter::from_fn(move || {
+            let n = rows.len();
+            let mut row = vec![d.next()?];
+            if n > 0 {
+                h_coeffs.push(h.next()?);
+            }
+            // T(n, k) = sum of h_i T(n - i, k - 1) for i from 1
+            for k in 1..=n {
+                let mut value = T::zero();
+                for (i, hi) in h_coeffs.iter().enumerate().take(n - k + 1) {
+                    value = value.checked_add(&hi.checked_mul(&rows[n - i - 1][k - 1])?)?;
+                }
+                row.push(value);
+            }
+            rows.push(row.clone());
+            Some(row)
+        });
+        Self::from_rows(rows_iter)
+    }
+
+    /// Treat a triangle given by its rows as a Riordan array without checking it, d and h are then taken from its first two columns. Sequences that would divide by zero end there.
+    pub fn from_rows<I>(rows: I) -> Self
+    where
+        I: Iterator<Item = Vec<T>> + 'static,
+    {
+        Self {
+            triangle: Rc::new(Triangle::new(rows)),
+        }
+    }
+
+    /// True if the first n rows of the triangle form a Riordan array, that is they agree with the Riordan array made from the first two columns.
+    pub fn is_riordan<I>(rows: I, n: usize) -> bool
+    where
+        I: Iterator<Item = Vec<T>> + 'static,
+    {
+        let given: Vec<Vec<T>> = rows.take(n).collect();
+        if given.len() < n
+            || given
+                .iter()
+                .enumerate()
+                .any(|(i, row)| row.len() != i + 1 || row[i].is_zero())
+        {
+            return false;
+        }
+        let candidate = Self::from_rows(given.clone().into_iter());
+        let rebuilt = Self::build(candidate.d(), candidate.h().skip(1));
+        rebuilt.rows().take(n).eq(given)
+    }
+
+    /// Row n, or None if it cannot be computed.
+    pub fn row(&self, n: usize) -> Option<Vec<T>> {
+        self.triangle.row(n)
+    }
+
+    /// The entry T(n, k), or None if it does not exist.
+    pub fn get(&self, n: usize, k: usize) -> Option<T> {
+        self.triangle.get(n, k)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Vec<T>> + 'static {
+        let triangle = self.triangle.clone();
+        (0..).map_while(move |n| triangle.row(n))
+    }
+
+    /// A Triangle with the same rows, for its views and sums.
+    pub fn triangle(&self) -> Triangle<T> {
+        Triangle::new(self.rows())
+    }
+
+    /// The coefficients of d(x), which is the generating function of the first column.
+    pub fn d(&self) -> impl Iterator<Item = T> + 'static {
+        let triangle = self.triangle.clone();
+        (0..).map_while(move |n| triangle.get(n, 0))
+    }
+
+    /// The coefficients of h(x), which is the generating function of the second column divided by d(x).
+    pub fn h(&self) -> impl Iterator<Item = T> + 'static {
+        let triangle = self.triangle.clone();
+        let mut h: Vec<T> = vec![T::zero()];
+        std::iter::once(T::zero()).chain(std::iter::from_fn(move || {
+            let n = h.len();
+            let d0 = triangle.get(0, 0).filter(|d0| !d0.is_zero())?;
+            let mut value = triangle.get(n, 1)?;
+            for (i, hi) in h.iter().enumerate().skip(1) {
+                value = value.checked_sub(&hi.checked_mul(&triangle.get(n - i, 0)?)?)?;
+            }
+            let (q, r) = value.div_rem(&d0);
+            if !r.is_zero() {
+                return None;
+            }
+            h.push(q.clone());
+            Some(q)
+        }))
+    }
+
+    /// The product in the Riordan group, which is the matrix product. For (d1, h1) (d2, h2) this is (d1 d2(h1), h2(h1)).
+    pub fn product(&self, other: &Self) -> Self {
+        let (a, b) = (self.triangle.clone(), other.triangle.clone());
+        let rows = (0..).map_while(move |n| {
+            let a_row = a.row(n)?;
+            (0..=n)
+                .map(|k| {
+                    (k..=n).try_fold(T::zero(), |acc, j| {
+                        acc.checked_add(&a_row.get(j)?.checked_mul(&b.get(j, k)?)?)
+                    })
+                })
+                .collect::<Option<Vec<T>>>()
+        });
+        Self::from_rows(rows)
+    }
+
+    /// The inverse in the Riordan group, which is the matrix inverse. For (d, h) this is (1/d(g), g) where g is the compositional inverse of h.
+    pub fn inverse(&self) -> Self {
+        Self::from_rows(self.triangle().inverse().into_rows())
+    }
+
+    // The coefficients c_j with T(n + 1, k) = sum of c_j T(n, j) for every n, solving for each c_n in turn using the diagonal. Column 1 gives the A-sequence and column 0 the Z-sequence.
+    fn characterization(&self, k: usize) -> impl Iterator<Item = T> + 'static {
+        let triangle = self.triangle.clone();
+        let mut c: Vec<T> = Vec::new();
+        std::iter::from_fn(move || {
+            let n = c.len();
+            let row = triangle.row(n).filter(|row| !row[n].is_zero())?;
+            let mut value = triangle.get(n + 1, k)?;
+            for (j, cj) in c.iter().enumerate() {
+                value = value.checked_sub(&cj.checked_mul(&row[j])?)?;
+            }
+            let (q, r) = value.div_rem(&row[n]);
+            if !r.is_zero() {
+                return None;
+            }
+            c.push(q.clone());
+            Some(q)
+        })
+    }
+
+    /// The A-sequence, the unique sequence with T(n + 1, k + 1) = sum of a_j T(n, k + j). Equivalently h(x) = x A(h(x)).
+    pub fn a_sequence(&self) -> impl Iterator<Item = T> + 'static {
+        self.characterization(1)
+    }
+
+    /// The Z-sequence, the unique sequence with T(n + 1, 0) = sum of z_j T(n, j). Equivalently d(x) = d(0) / (1 - x Z(h(x))).
+    pub fn z_sequence(&self) -> impl Iterator<Item = T> + 'static {
+        self.characterization(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalan::Catalan;
+    use crate::pascal::PascalsTriangle;
+    use crate::stirling::StirlingSecond;
+    use itertools::Itertools;
+    use num::BigInt;
+    use std::iter::{once, repeat};
+
+    fn pascal() -> RiordanArray<i64> {
+        RiordanArray::new(repeat(1), once(0).chain(repeat(1)))
+    }
+
+    #[test]
+    fn construction() {
+        assert!(pascal().rows().take(12).eq(PascalsTriangle::new().take(12)));
+        assert_eq!(pascal().d().take(5).collect_vec(), vec![1, 1, 1, 1, 1]);
+        assert_eq!(pascal().h().take(5).collect_vec(), vec![0, 1, 1, 1, 1]);
+        // The Catalan triangle (C(x), x C(x)) has the Catalan numbers as its row sums shifted by one
+        let catalan = RiordanArray::new(Catalan::new(), once(0).chain(Catalan::new()));
+        assert!(
+            catalan
+                .triangle()
+                .row_sums()
+                .take(10)
+                .eq(Catalan::new().skip(1).take(10))
+        );
+    }
+
+    #[test]
+    fn group_operations() {
+        // Pascal's triangle squared is (1/(1 - 2x), x/(1 - 2x)) with entries 2^(n-k) C(n, k)
+        let square = pascal().product(&pascal());
+        for n in 0..10 {
+            for k in 0..=n {
+                let expected = (1 << (n - k)) * pascal().get(n, k).unwrap();
+                assert_eq!(square.get(n, k), Some(expected));
+            }
+        }
+        assert_eq!(square.h().take(5).collect_vec(), vec![0, 1, 2, 4, 8]);
+        // The inverse of Pascal's triangle is (1/(1 + x), x/(1 + x))
+        let inverse = pascal().inverse();
+        assert_eq!(inverse.d().take(5).collect_vec(), vec![1, -1, 1, -1, 1]);
+        assert_eq!(inverse.h().take(5).collect_vec(), vec![0, 1, -1, 1, -1]);
+        let identity = pascal().product(&inverse);
+        for (n, row) in identity.rows().take(10).enumerate() {
+            assert_eq!(row, (0..=n).map(|k| (k == n) as i64).collect_vec());
+        }
+    }
+
+    #[test]
+    fn characterizations() {
+        assert_eq!(
+            pascal().a_sequence().take(5).collect_vec(),
+            vec![1, 1, 0, 0, 0]
+        );
+        assert_eq!(
+            pascal().z_sequence().take(5).collect_vec(),
+            vec![1, 0, 0, 0, 0]
+        );
+        // The Catalan triangle has A-sequence 1, 1, 1...
+        let catalan = RiordanArray::new(Catalan::new(), once(0).chain(Catalan::new()));
+        assert_eq!(catalan.a_sequence().take(6).collect_vec(), vec![1; 6]);
+        // Check the defining relations on a less regular array
+        let array: RiordanArray<BigInt> = RiordanArray::new(
+            [1, 2, 0, 3, 1, 4, 1, 5, 9].into_iter().map(BigInt::from),
+            [0, 1, 3, 1, 2, 7, 0, 1, 8].into_iter().map(BigInt::from),
+        );
+        let a = array.a_sequence().take(7).collect_vec();
+        let z = array.z_sequence().take(7).collect_vec();
+        for n in 0..7 {
+            let row = array.row(n).unwrap();
+            let z_sum: BigInt = (0..=n).map(|j| &z[j] * &row[j]).sum();
+            assert_eq!(array.get(n + 1, 0).unwrap(), z_sum);
+            for k in 0..=n {
+                let a_sum: BigInt = (0..=n - k).map(|j| &a[j] * &row[k + j]).sum();
+                assert_eq!(array.get(n + 1, k + 1).unwrap(), a_sum);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "d(0)")]
+    fn zero_d0() {
+        RiordanArray::new(once(0).chain(repeat(1)), once(0).chain(repeat(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "h'(0)")]
+    fn zero_h1() {
+        RiordanArray::new(repeat(1), [0, 0, 1].into_iter().chain(repeat(1)));
+    }
+
+    #[test]
+    fn zero_divisors() {
+        // A zero corner leaves h with only its constant term
+        let corner = RiordanArray::from_rows([vec![0], vec![1, 1], vec![1, 2, 1]].into_iter());
+        assert_eq!(corner.h().collect_vec(), vec![0]);
+        // A zero on the diagonal ends the A- and Z-sequences there
+        let diagonal = RiordanArray::from_rows(
+            [vec![1], vec![1, 0], vec![1, 1, 0], vec![1, 2, 1, 0]].into_iter(),
+        );
+        assert_eq!(diagonal.a_sequence().collect_vec(), vec![0]);
+        assert_eq!(diagonal.z_sequence().collect_vec(), vec![1]);
+    }
+
+    #[test]
+    fn identification() {
+        assert!(RiordanArray::is_riordan(PascalsTriangle::new_big(), 15));
+        assert!(RiordanArray::is_riordan(
+            pascal().product(&pascal()).rows(),
+            15
+        ));
+        // The Stirling numbers are exponential rather than ordinary Riordan arrays
+        assert!(!RiordanArray::is_riordan(StirlingSecond::new_big(), 15));
+        assert!(!RiordanArray::is_riordan(
+            PascalsTriangle::new().map(|row| row.iter().map(|x| x * x).collect()),
+            10
+        ));
+    }
+}
+
+crate::check_sequences!(
+    RiordanArray::new(std::iter::repeat(1), std::iter::once(0).chain(std::iter::repeat(1))).rows().flatten(), [1, 1, 1, 1, 2, 1, 1, 3, 3, 1, 1, 4, 6, 4, 1];
+    RiordanArray::new(std::iter::repeat(1), std::iter::once(0).chain(std::iter::repeat(1))).inverse().rows().flatten(), [1, -1, 1, 1, -2, 1, -1, 3, -3, 1, 1, -4, 6, -4, 1];
+);
@@ -1,8 +1,11 @@
 use crate::{
     Number,
     core::{Primes, traits::Increment},
+    rngs::Rng,
     utils::{divisibility::prime_factorization, miller_rabin::is_prime},
 };
+#[cfg(feature = "big_int")]
+use num::{BigInt, Integer, One, Signed};
 use std::collections::VecDeque;
 
 /// The Blum integers. Natural numbers of the form p*q where p and q are primes congruent to 3 modulo 4 and p is not equal to q. They are relevant to the Blum-Blum-Shub PRNG.
@@ -151,9 +154,177 @@ impl Iterator for BlumBlumShub {
     }
 }
 
+/// The Blum-Blum-Shub PRNG. Each state is the square of the previous one modulo M = p*q and the lowest log2(log2(M)) bits of each state after the seed are output, least significant first.
+///
+/// ```text
+/// seed = 1234, p = 23, q = 14159
+/// 0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 1, 1, 0, 1, 1, 1...
+/// ```
+#[cfg(feature = "big_int")]
+#[derive(Clone)]
+pub struct BbsRng {
+    state: BigInt,
+    seed: BigInt,
+    modulus: BigInt,
+    totient: BigInt,
+    bits_per_state: u64,
+    buffer: VecDeque<u8>,
+}
+
+#[cfg(feature = "big_int")]
+impl BbsRng {
+    /// Create a generator from two primes. The primes are not checked for primality.
+    /// Panics if p or q is not congruent to 3 modulo 4, if p equals q, or if the seed is not coprime to p*q.
+    pub fn new<G>(seed: G, p: G, q: G) -> Self
+    where
+        BigInt: From<G>,
+    {
+        let seed = BigInt::from(seed);
+        let p = BigInt::from(p);
+        let q = BigInt::from(q);
+        // The lowest two bits of a positive number congruent to 3 modulo 4 are both set
+        assert!(
+            p.is_positive() && q.is_positive() && p.bit(0) && p.bit(1) && q.bit(0) && q.bit(1),
+            "p and q must be congruent to 3 modulo 4"
+        );
+        assert!(p != q, "p and q must be distinct");
+        let modulus = &p * &q;
+        let seed = seed.mod_floor(&modulus);
+        assert!(
+            seed.gcd(&modulus).is_one() && !seed.is_one(),
+            "seed must be coprime to p*q and greater than 1"
+        );
+        let totient = (&p - 1) * (&q - 1);
+        // Using more than log2(log2(M)) bits of each state is not known to be secure
+        let bits_per_state = (modulus.bits() - 1).max(2).ilog2() as u64;
+        Self {
+            state: seed.clone(),
+            seed,
+            modulus,
+            totient,
+            bits_per_state,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Create a generator from the first two primes from BlumBlumShubPrimes that are at least the given bound. These give a long period for most seeds.
+    /// Panics if the seed is not coprime to the modulus or if the primes would overflow.
+    pub fn from_bbs_primes(seed: Number, bound: Number) -> Self {
+        let mut primes = BlumBlumShubPrimes::new().skip_while(|p| *p < bound);
+        let p = primes.next().expect("primes overflowed");
+        let q = primes.next().expect("primes overflowed");
+        Self::new(BigInt::from(seed), BigInt::from(p), BigInt::from(q))
+    }
+
+    /// The modulus M = p*q.
+    pub fn modulus(&self) -> &BigInt {
+        &self.modulus
+    }
+
+    /// The number of bits taken from each state.
+    pub fn bits_per_state(&self) -> u64 {
+        self.bits_per_state
+    }
+
+    /// The state after n squarings of the seed, computed directly by Euler's theorem as seed^(2^n mod phi(M)) mod M.
+    pub fn state_at(&self, n: u64) -> BigInt {
+        let e = BigInt::from(2).modpow(&BigInt::from(n), &self.totient);
+        self.seed.modpow(&e, &self.modulus)
+    }
+
+    /// Jump so that the next bits come from the state after n squarings of the seed. The first bits come from n = 1.
+    pub fn seek(&mut self, n: u64) {
+        self.state = self.state_at(n.saturating_sub(1));
+        self.buffer.clear();
+    }
+
+    /// The next byte, made from eight bits with the first bit as the most significant.
+    pub fn next_byte(&mut self) -> u8 {
+        (0..8).fold(0, |acc, _| (acc << 1) | self.next().unwrap())
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl Iterator for BbsRng {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            self.state = (&self.state * &self.state) % &self.modulus;
+            for i in 0..self.bits_per_state {
+                self.buffer.push_back(self.state.bit(i) as u8);
+            }
+        }
+        self.buffer.pop_front()
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl Rng for BbsRng {
+    /// Thirty-two bits with the first bit as the most significant.
+    fn next_u32(&mut self) -> u32 {
+        (0..32).fold(0, |acc, _| (acc << 1) | self.next().unwrap() as u32)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for b in dest.iter_mut() {
+            *b = self.next_byte();
+        }
+    }
+}
+
 crate::check_sequences!(
     Blum::new(), [21, 33, 57, 69, 77, 93, 129, 133, 141, 161, 177, 201, 209, 213, 217, 237, 249, 253, 301, 309, 321, 329, 341, 381, 393, 413, 417, 437, 453, 469, 473, 489, 497, 501, 517, 537, 553, 573, 581, 589, 597, 633, 649, 669, 681, 713, 717, 721, 737, 749, 753, 781, 789];
     BlumBlumShubPrimes::new(), [23, 47, 167, 359, 719, 1439, 2039, 2879, 4079, 4127, 4919, 5639, 5807, 5927, 6047, 7247, 7559, 7607, 7727, 9839, 10799, 11279, 13799, 13967, 14159, 15287, 15647, 20327, 21599, 21767, 23399, 24407, 24527, 25799, 28319, 28607, 29399];
     BlumBlumShubMaximum::new(), [1081, 3841, 7849, 8257, 16537, 16873, 33097, 46897, 59953, 66217, 93817, 94921, 95833, 113137, 120073, 129697, 133561, 136321, 139081, 166681, 173857, 174961, 177721, 226297, 231193, 240313, 248377, 258121, 259417, 265033, 278569, 317377, 321241, 325657];
+    BbsRng::new(1234, 23, 14159), [0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 1, 1, 0, 1, 1, 1];
     BlumBlumShub::new(1234, 325657), [1234, 220128, 203069, 49822, 74030, 284904, 281966, 227804, 242495, 266192, 102519, 217000, 300428, 168663, 91648];
 );
+
+#[cfg(test)]
+#[cfg(feature = "big_int")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn states_and_bits() {
+        // The same states as BlumBlumShub, with four bits taken from each after the seed
+        let rng = BbsRng::new(1234, 23, 14159);
+        assert_eq!(rng.modulus(), &BigInt::from(325657));
+        assert_eq!(rng.bits_per_state(), 4);
+        let bits: Vec<u8> = rng.clone().take(40).collect();
+        let states: Vec<u64> = BlumBlumShub::new(1234, 325657).skip(1).take(10).collect();
+        for (i, s) in states.iter().enumerate() {
+            for b in 0..4 {
+                assert_eq!(bits[4 * i + b] as u64, (s >> b) & 1);
+            }
+        }
+        for (n, s) in BlumBlumShub::new(1234, 325657).take(50).enumerate() {
+            assert_eq!(rng.state_at(n as u64), BigInt::from(s));
+        }
+    }
+
+    #[test]
+    fn seek() {
+        let mut rng = BbsRng::from_bbs_primes(987654321, 1_000_000);
+        let bits: Vec<u8> = rng.clone().take(1000).collect();
+        let k = rng.bits_per_state() as usize;
+        rng.seek(31);
+        assert!(
+            rng.by_ref()
+                .take(200)
+                .eq(bits[30 * k..30 * k + 200].iter().copied())
+        );
+        rng.seek(1);
+        assert_eq!(
+            rng.next_byte(),
+            bits[..8].iter().fold(0, |acc, b| (acc << 1) | b)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_bad_primes() {
+        BbsRng::new(1234, 13, 23);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::rngs::{Advance, Rng};
+
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// The ChaCha stream cipher used as a generator, with a 64-bit block counter and a 64-bit stream number as in Bernstein's original design. Each block of the keystream is sixteen 32-bit words.
+///
+/// ```text
+/// ChaCha20, key = [0; 32], stream = 0
+/// 2917185654, 2419978656, 3848953152, 683509331, 3438365936, 1851091299, 3587096406...
+/// ```
+#[derive(Clone)]
+pub struct ChaCha {
+    key: [u32; 8],
+    counter: u64,
+    stream: u64,
+    rounds: u32,
+    block: [u32; 16],
+    index: usize,
+}
+
+impl ChaCha {
+    /// Create a generator with the given number of rounds.
+    /// Panics if rounds is zero or odd.
+    pub fn new(key: [u8; 32], stream: u64, rounds: u32) -> Self {
+        assert!(
+            rounds > 0 && rounds.is_multiple_of(2),
+            "rounds must be positive and even"
+        );
+        let key =
+            std::array::from_fn(|i| u32::from_le_bytes(key[4 * i..4 * i + 4].try_into().unwrap()));
+        Self {
+            key,
+            counter: 0,
+            stream,
+            rounds,
+            block: [0; 16],
+            index: 16,
+        }
+    }
+
+    /// ChaCha with 8 rounds, the fastest variant without a known practical attack.
+    pub fn new_chacha8(key: [u8; 32], stream: u64) -> Self {
+        Self::new(key, stream, 8)
+    }
+
+    /// ChaCha with 12 rounds.
+    pub fn new_chacha12(key: [u8; 32], stream: u64) -> Self {
+        Self::new(key, stream, 12)
+    }
+
+    /// ChaCha with 20 rounds, as standardized for encryption.
+    pub fn new_chacha20(key: [u8; 32], stream: u64) -> Self {
+        Self::new(key, stream, 20)
+    }
+
+    /// Move to the start of the given block of the keystream.
+    pub fn seek(&mut self, block: u64) {
+        self.counter = block;
+        self.index = 16;
+    }
+
+    /// The block of the keystream that the next word comes from.
+    pub fn block_position(&self) -> u64 {
+        if self.index == 16 {
+            self.counter
+        } else {
+            self.counter.wrapping_sub(1)
+        }
+    }
+
+    fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        x[a] = x[a].wrapping_add(x[b]);
+        x[d] = (x[d] ^ x[a]).rotate_left(16);
+        x[c] = x[c].wrapping_add(x[d]);
+        x[b] = (x[b] ^ x[c]).rotate_left(12);
+        x[a] = x[a].wrapping_add(x[b]);
+        x[d] = (x[d] ^ x[a]).rotate_left(8);
+        x[c] = x[c].wrapping_add(x[d]);
+        x[b] = (x[b] ^ x[c]).rotate_left(7);
+    }
+
+    fn refill(&mut self) {
+        let mut input = [0; 16];
+        input[..4].copy_from_slice(&CONSTANTS);
+        input[4..12].copy_from_slice(&self.key);
+        input[12] = self.counter as u32;
+        input[13] = (self.counter >> 32) as u32;
+        input[14] = self.stream as u32;
+        input[15] = (self.stream >> 32) as u32;
+        let mut x = input;
+        for _ in 0..self.rounds / 2 {
+            // Column round
+            Self::quarter_round(&mut x, 0, 4, 8, 12);
+            Self::quarter_round(&mut x, 1, 5, 9, 13);
+            Self::quarter_round(&mut x, 2, 6, 10, 14);
+            Self::quarter_round(&mut x, 3, 7, 11, 15);
+            // Diagonal round
+            Self::quarter_round(&mut x, 0, 5, 10, 15);
+            Self::quarter_round(&mut x, 1, 6, 11, 12);
+            Self::quarter_round(&mut x, 2, 7, 8, 13);
+            Self::quarter_round(&mut x, 3, 4, 9, 14);
+        }
+        for (out, (a, b)) in self.block.iter_mut().zip(x.iter().zip(input.iter())) {
+            *out = a.wrapping_add(*b);
+        }
+        self.counter = self.counter.wrapping_add(1);
+        self.index = 0;
+    }
+}
+
+impl Iterator for ChaCha {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == 16 {
+            self.refill();
+        }
+        let out = self.block[self.index];
+        self.index += 1;
+        Some(out)
+    }
+}
+
+impl Advance for ChaCha {
+    /// A step is one word of the keystream. Whole blocks are skipped by moving the counter.
+    fn advance(&mut self, n: u64) {
+        let position = self.block_position() as u128 * 16 + (self.index % 16) as u128 + n as u128;
+        self.seek((position / 16) as u64);
+        let offset = (position % 16) as usize;
+        if offset > 0 {
+            self.refill();
+            self.index = offset;
+        }
+    }
+}
+
+impl Rng for ChaCha {
+    /// Words of the keystream, so fill_bytes gives the keystream bytes in order.
+    fn next_u32(&mut self) -> u32 {
+        self.next().unwrap()
+    }
+}
+
+crate::check_sequences!(
+    ChaCha::new_chacha20([0; 32], 0), [2917185654_u32, 2419978656, 3848953152, 683509331];
+);
+
+crate::sample_sequences!(
+    ChaCha::new_chacha8([0; 32], 0);
+    ChaCha::new_chacha20([0; 32], 0);
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keystream(mut rng: ChaCha, len: usize) -> Vec<u8> {
+        let mut bytes = vec![0; len];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn zero_key_vectors() {
+        // First bytes of the keystream for an all zero key and nonce
+        assert_eq!(
+            keystream(ChaCha::new_chacha8([0; 32], 0), 8),
+            [0x3e, 0x00, 0xef, 0x2f, 0x89, 0x5f, 0x40, 0xd6]
+        );
+        assert_eq!(
+            keystream(ChaCha::new_chacha12([0; 32], 0), 8),
+            [0x9b, 0xf4, 0x9a, 0x6a, 0x07, 0x55, 0xf9, 0x53]
+        );
+        assert_eq!(
+            keystream(ChaCha::new_chacha20([0; 32], 0), 8),
+            [0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90]
+        );
+    }
+
+    #[test]
+    fn rfc_8439_block() {
+        // Section 2.3.2, the RFC's 32-bit counter and 96-bit nonce overlap the 64-bit counter and stream
+        let key = std::array::from_fn(|i| i as u8);
+        let mut rng = ChaCha::new_chacha20(key, 0x4a000000);
+        rng.seek((0x09000000 << 32) | 1);
+        assert_eq!(
+            keystream(rng, 16),
+            [
+                0x10, 0xf1, 0xe7, 0xe4, 0xd1, 0x3b, 0x59, 0x15, 0x50, 0x0f, 0xdd, 0x1f, 0xa3, 0x20,
+                0x71, 0xc4
+            ]
+        );
+    }
+
+    #[test]
+    fn seek_and_advance() {
+        let mut rng = ChaCha::new_chacha12([7; 32], 3);
+        let words: Vec<u32> = rng.clone().take(100).collect();
+        rng.advance(37);
+        assert_eq!(rng.next(), Some(words[37]));
+        assert_eq!(rng.block_position(), 2);
+        rng.advance(10);
+        assert_eq!(rng.next(), Some(words[48]));
+        rng.seek(5);
+        assert_eq!(rng.next(), Some(words[80]));
+        assert_ne!(ChaCha::new_chacha12([7; 32], 4).next(), Some(words[0]));
+    }
+}
@@ -0,0 +1,606 @@
+use crate::{rngs::Rng, utils::special_functions::ln_gamma};
+#[cfg(feature = "big_int")]
+use num::{BigInt, Integer, One, Signed, Zero, rational::Ratio};
+use std::sync::OnceLock;
+
+/// A probability distribution that can be sampled using any generator.
+pub trait Distribution {
+    type Output;
+
+    /// A single random value from the distribution.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Output;
+
+    /// An endless iterator of random values from the distribution.
+    fn samples<'a, R: Rng + ?Sized>(&'a self, rng: &'a mut R) -> Samples<'a, Self, R>
+    where
+        Self: Sized,
+    {
+        Samples { dist: self, rng }
+    }
+}
+
+/// Iterator returned by Distribution::samples.
+pub struct Samples<'a, D, R: ?Sized> {
+    dist: &'a D,
+    rng: &'a mut R,
+}
+
+impl<D: Distribution, R: Rng + ?Sized> Iterator for Samples<'_, D, R> {
+    type Item = D::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.dist.sample(self.rng))
+    }
+}
+
+/// Uniformly random f64 in the interval (0, 1], safe to take the logarithm of.
+fn open_unit<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    1.0 - rng.next_f64()
+}
+
+const ZIGGURAT_LAYERS: usize = 128;
+/// The start of the tail of the ziggurat and the area of each layer, for 128 layers.
+const ZIGGURAT_R: f64 = 3.442619855899;
+const ZIGGURAT_V: f64 = 9.91256303526217e-3;
+
+/// The right edges of the layers of the ziggurat, from the base (which includes the tail) to the peak, and the value of the density at each.
+fn ziggurat_tables() -> &'static ([f64; ZIGGURAT_LAYERS + 1], [f64; ZIGGURAT_LAYERS + 1]) {
+    static TABLES: OnceLock<([f64; ZIGGURAT_LAYERS + 1], [f64; ZIGGURAT_LAYERS + 1])> =
+        OnceLock::new();
+    TABLES.get_or_init(|| {
+        let f = |x: f64| (-0.5 * x * x).exp();
+        let mut x = [0.0; ZIGGURAT_LAYERS + 1];
+        x[0] = ZIGGURAT_V / f(ZIGGURAT_R);
+        x[1] = ZIGGURAT_R;
+        for i in 2..ZIGGURAT_LAYERS {
+            x[i] = (-2.0 * (ZIGGURAT_V / x[i - 1] + f(x[i - 1])).ln()).sqrt();
+        }
+        x[ZIGGURAT_LAYERS] = 0.0;
+        let y = x.map(f);
+        (x, y)
+    })
+}
+
+/// A standard normal variate by Marsaglia and Tsang's Ziggurat method.
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let (x, y) = ziggurat_tables();
+    loop {
+        let bits = rng.next_u64();
+        let i = (bits & 0x7f) as usize;
+        // The upper 53 bits give a signed value in (-1, 1)
+        let u = 2.0 * ((bits >> 11) as f64 * (1.0 / (1_u64 << 53) as f64)) - 1.0;
+        let z = u * x[i];
+        if z.abs() < x[i + 1] {
+            return z;
+        }
+        if i == 0 {
+            // Sample from the tail beyond R
+            loop {
+                let a = -open_unit(rng).ln() / ZIGGURAT_R;
+                let b = -open_unit(rng).ln();
+                if 2.0 * b > a * a {
+                    return (ZIGGURAT_R + a).copysign(u);
+                }
+            }
+        }
+        if y[i] + rng.next_f64() * (y[i + 1] - y[i]) < (-0.5 * z * z).exp() {
+            return z;
+        }
+    }
+}
+
+/// The normal distribution.
+#[derive(Debug, Clone, Copy)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    /// Panics if std_dev is negative or not finite.
+    pub fn new(mean: f64, std_dev: f64) -> Self {
+        assert!(
+            std_dev >= 0.0 && std_dev.is_finite(),
+            "std_dev must be non-negative and finite"
+        );
+        Self { mean, std_dev }
+    }
+}
+
+impl Distribution for Normal {
+    type Output = f64;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.mean + self.std_dev * standard_normal(rng)
+    }
+}
+
+/// The exponential distribution, sampled by inversion.
+#[derive(Debug, Clone, Copy)]
+pub struct Exponential {
+    rate: f64,
+}
+
+impl Exponential {
+    /// Panics if rate is not positive.
+    pub fn new(rate: f64) -> Self {
+        assert!(rate > 0.0, "rate must be positive");
+        Self { rate }
+    }
+}
+
+impl Distribution for Exponential {
+    type Output = f64;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        -open_unit(rng).ln() / self.rate
+    }
+}
+
+/// The Poisson distribution. Small means use Knuth's multiplication method and larger means use Hörmann's transformed rejection (PTRS).
+#[derive(Debug, Clone, Copy)]
+pub struct Poisson {
+    lambda: f64,
+}
+
+impl Poisson {
+    /// Panics if lambda is negative or not finite.
+    pub fn new(lambda: f64) -> Self {
+        assert!(
+            lambda >= 0.0 && lambda.is_finite(),
+            "lambda must be non-negative and finite"
+        );
+        Self { lambda }
+    }
+}
+
+impl Distribution for Poisson {
+    type Output = u64;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let lambda = self.lambda;
+        if lambda < 10.0 {
+            let limit = (-lambda).exp();
+            let mut k = 0;
+            let mut p = rng.next_f64();
+            while p > limit {
+                k += 1;
+                p *= rng.next_f64();
+            }
+            return k;
+        }
+        let slam = lambda.sqrt();
+        let loglam = lambda.ln();
+        let b = 0.931 + 2.53 * slam;
+        let a = -0.059 + 0.02483 * b;
+        let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let vr = 0.9277 - 3.6224 / (b - 2.0);
+        loop {
+            let u = rng.next_f64() - 0.5;
+            let v = open_unit(rng);
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+            if us >= 0.07 && v <= vr {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+            if v.ln() + inv_alpha.ln() - (a / (us * us) + b).ln()
+                <= -lambda + k * loglam - ln_gamma(k + 1.0)
+            {
+                return k as u64;
+            }
+        }
+    }
+}
+
+/// The binomial distribution, the number of successes in n trials. Sampled by inversion searching outward from the mode, which takes time proportional to the standard deviation.
+#[derive(Debug, Clone, Copy)]
+pub struct Binomial {
+    n: u64,
+    p: f64,
+}
+
+impl Binomial {
+    /// Panics if p is not in the interval [0, 1].
+    pub fn new(n: u64, p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "p must be in the interval [0, 1]");
+        Self { n, p }
+    }
+
+    fn ln_pmf(n: f64, p: f64, k: f64) -> f64 {
+        ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0)
+            + k * p.ln()
+            + (n - k) * (1.0 - p).ln()
+    }
+
+    fn finish(&self, k: f64, flip: bool) -> u64 {
+        if flip { self.n - k as u64 } else { k as u64 }
+    }
+}
+
+impl Distribution for Binomial {
+    type Output = u64;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        // Work with the smaller probability and reflect the result
+        let flip = self.p > 0.5;
+        let p = if flip { 1.0 - self.p } else { self.p };
+        if p == 0.0 {
+            return if flip { self.n } else { 0 };
+        }
+        let n = self.n as f64;
+        let ratio = p / (1.0 - p);
+        let mode = ((n + 1.0) * p).floor().min(n);
+        'outer: loop {
+            let mut u = rng.next_f64();
+            let pmf_mode = Self::ln_pmf(n, p, mode).exp();
+            // Alternate steps below and above the mode, updating the probabilities by their ratios
+            let (mut lo, mut hi) = (mode, mode);
+            let (mut p_lo, mut p_hi) = (pmf_mode, pmf_mode);
+            u -= pmf_mode;
+            if u < 0.0 {
+                break 'outer self.finish(mode, flip);
+            }
+            while lo > 0.0 || hi < n {
+                if lo > 0.0 {
+                    p_lo *= lo / ((n - lo + 1.0) * ratio);
+                    lo -= 1.0;
+                    u -= p_lo;
+                    if u < 0.0 {
+                        break 'outer self.finish(lo, flip);
+                    }
+                }
+                if hi < n {
+                    p_hi *= (n - hi) * ratio / (hi + 1.0);
+                    hi += 1.0;
+                    u -= p_hi;
+                    if u < 0.0 {
+                        break 'outer self.finish(hi, flip);
+                    }
+                }
+            }
+            // Rounding left a tiny amount of probability unassigned, so try again
+        }
+    }
+}
+
+/// The geometric distribution, the number of failures before the first success. Sampled by inversion.
+#[derive(Debug, Clone, Copy)]
+pub struct Geometric {
+    p: f64,
+}
+
+impl Geometric {
+    /// Panics if p is not in the interval (0, 1].
+    pub fn new(p: f64) -> Self {
+        assert!(p > 0.0 && p <= 1.0, "p must be in the interval (0, 1]");
+        Self { p }
+    }
+}
+
+impl Distribution for Geometric {
+    type Output = u64;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        if self.p == 1.0 {
+            return 0;
+        }
+        (open_unit(rng).ln() / (-self.p).ln_1p()).floor() as u64
+    }
+}
+
+/// The gamma distribution with a shape and a scale, sampled by the method of Marsaglia and Tsang.
+#[derive(Debug, Clone, Copy)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+}
+
+impl Gamma {
+    /// Panics if shape or scale is not positive.
+    pub fn new(shape: f64, scale: f64) -> Self {
+        assert!(shape > 0.0, "shape must be positive");
+        assert!(scale > 0.0, "scale must be positive");
+        Self { shape, scale }
+    }
+}
+
+impl Distribution for Gamma {
+    type Output = f64;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        // A shape below one is boosted by one and corrected with a uniform power
+        let (shape, boost) = if self.shape < 1.0 {
+            (self.shape + 1.0, open_unit(rng).powf(1.0 / self.shape))
+        } else {
+            (self.shape, 1.0)
+        };
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let x = standard_normal(rng);
+            let v = 1.0 + c * x;
+            if v <= 0.0 {
+                continue;
+            }
+            let v = v * v * v;
+            let u = open_unit(rng);
+            if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+                return d * v * boost * self.scale;
+            }
+        }
+    }
+}
+
+/// A discrete distribution over the indices of a list of weights, sampled in constant time with Vose's alias method.
+#[derive(Debug, Clone)]
+pub struct AliasTable {
+    probability: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl AliasTable {
+    /// Panics if there are no weights, if any weight is negative or not finite, or if all weights are zero.
+    pub fn new(weights: &[f64]) -> Self {
+        assert!(!weights.is_empty(), "weights must not be empty");
+        assert!(
+            weights.iter().all(|w| *w >= 0.0 && w.is_finite()),
+            "weights must be non-negative and finite"
+        );
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0.0, "weights must not all be zero");
+        let n = weights.len();
+        let mut scaled: Vec<f64> = weights.iter().map(|w| w * n as f64 / total).collect();
+        let mut probability = vec![1.0; n];
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|i| scaled[*i] < 1.0);
+        while let (Some(s), Some(l)) = (small.pop(), large.pop()) {
+            probability[s] = scaled[s];
+            alias[s] = l;
+            scaled[l] -= 1.0 - scaled[s];
+            if scaled[l] < 1.0 {
+                small.push(l);
+            } else {
+                large.push(l);
+            }
+        }
+        // Whatever remains is only away from one by rounding
+        Self { probability, alias }
+    }
+}
+
+impl Distribution for AliasTable {
+    type Output = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let i = rng.bounded_u64(self.probability.len() as u64) as usize;
+        if rng.next_f64() < self.probability[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+/// A uniformly random BigInt in the range 0..bound, by rejection from random bits.
+#[cfg(feature = "big_int")]
+pub(crate) fn bounded_big<R: Rng + ?Sized>(rng: &mut R, bound: &BigInt) -> BigInt {
+    let bits = bound.bits();
+    let words = bits.div_ceil(32) as usize;
+    let excess = words as u64 * 32 - bits;
+    loop {
+        let mut digits: Vec<u32> = (0..words).map(|_| rng.next_u32()).collect();
+        if let Some(top) = digits.last_mut() {
+            *top >>= excess;
+        }
+        let x = BigInt::from_slice(num::bigint::Sign::Plus, &digits);
+        if &x < bound {
+            return x;
+        }
+    }
+}
+
+/// A discrete distribution over the indices of a list of rational probabilities, such as those from BinomialDistribution. Sampling is exact, using only integer arithmetic on random bits.
+#[cfg(feature = "big_int")]
+#[derive(Debug, Clone)]
+pub struct ExactDiscrete {
+    cumulative: Vec<BigInt>,
+}
+
+#[cfg(feature = "big_int")]
+impl ExactDiscrete {
+    /// The probabilities are normalized by their sum, so they may be any non-negative weights.
+    /// Panics if there are no probabilities, if any is negative, or if all are zero.
+    pub fn new(probabilities: &[Ratio<BigInt>]) -> Self {
+        assert!(!probabilities.is_empty(), "probabilities must not be empty");
+        assert!(
+            probabilities.iter().all(|p| !p.is_negative()),
+            "probabilities must be non-negative"
+        );
+        let denominator = probabilities
+            .iter()
+            .fold(BigInt::one(), |acc, p| acc.lcm(p.denom()));
+        let mut total = BigInt::zero();
+        let cumulative: Vec<BigInt> = probabilities
+            .iter()
+            .map(|p| {
+                total += p.numer() * (&denominator / p.denom());
+                total.clone()
+            })
+            .collect();
+        assert!(total.is_positive(), "probabilities must not all be zero");
+        Self { cumulative }
+    }
+}
+
+#[cfg(feature = "big_int")]
+impl Distribution for ExactDiscrete {
+    type Output = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let x = bounded_big(rng, self.cumulative.last().unwrap());
+        self.cumulative.partition_point(|c| *c <= x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        binomial_distribution::BinomialDistribution,
+        rngs::{Mt19937, Xoshiro256, statistical_tests::ks_uniform},
+        utils::special_functions::{chi_square_sf, gamma_p, normal_cdf},
+    };
+    use num::ToPrimitive;
+
+    fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let var = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var)
+    }
+
+    /// Pearson's chi-squared test of observed counts against expected probabilities.
+    fn chi_square_p_value(counts: &[u64], probabilities: &[f64]) -> f64 {
+        let n: u64 = counts.iter().sum();
+        let stat: f64 = counts
+            .iter()
+            .zip(probabilities)
+            .map(|(c, p)| {
+                let e = p * n as f64;
+                (*c as f64 - e).powi(2) / e
+            })
+            .sum();
+        chi_square_sf(stat, (counts.len() - 1) as f64)
+    }
+
+    #[test]
+    fn normal() {
+        let mut rng = Mt19937::new(5489);
+        let values: Vec<f64> = Normal::new(0.0, 1.0)
+            .samples(&mut rng)
+            .take(20000)
+            .collect();
+        let uniform: Vec<f64> = values.iter().map(|x| normal_cdf(*x)).collect();
+        assert!(ks_uniform(&uniform).passed(0.001));
+        let values: Vec<f64> = Normal::new(5.0, 2.0)
+            .samples(&mut rng)
+            .take(20000)
+            .collect();
+        let (mean, var) = mean_and_variance(&values);
+        assert!((mean - 5.0).abs() < 0.1);
+        assert!((var - 4.0).abs() < 0.2);
+        // The tail beyond the base of the ziggurat is reached
+        assert!(values.iter().any(|x| (x - 5.0).abs() > 2.0 * ZIGGURAT_R));
+    }
+
+    #[test]
+    fn exponential_and_gamma() {
+        let mut rng = Xoshiro256::new_star_star([1, 2, 3, 4]);
+        let values: Vec<f64> = Exponential::new(2.0)
+            .samples(&mut rng)
+            .take(20000)
+            .collect();
+        let uniform: Vec<f64> = values.iter().map(|x| 1.0 - (-2.0 * x).exp()).collect();
+        assert!(ks_uniform(&uniform).passed(0.001));
+        for shape in [0.5, 1.0, 3.5] {
+            let values: Vec<f64> = Gamma::new(shape, 1.0)
+                .samples(&mut rng)
+                .take(20000)
+                .collect();
+            let uniform: Vec<f64> = values.iter().map(|x| gamma_p(shape, *x)).collect();
+            assert!(ks_uniform(&uniform).passed(0.001));
+        }
+    }
+
+    #[test]
+    fn discrete_moments() {
+        let mut rng = Mt19937::new(1);
+        for (lambda, n) in [(3.0, 20000), (50.0, 20000), (1000.0, 5000)] {
+            let values: Vec<f64> = Poisson::new(lambda)
+                .samples(&mut rng)
+                .take(n)
+                .map(|x| x as f64)
+                .collect();
+            let (mean, var) = mean_and_variance(&values);
+            assert!((mean - lambda).abs() < 4.0 * (lambda / n as f64).sqrt());
+            assert!((var / lambda - 1.0).abs() < 0.1);
+        }
+        for (trials, p) in [(10, 0.3), (1000, 0.9), (100000, 0.01)] {
+            let values: Vec<f64> = Binomial::new(trials, p)
+                .samples(&mut rng)
+                .take(20000)
+                .map(|x| x as f64)
+                .collect();
+            let (mean, var) = mean_and_variance(&values);
+            let expected_var = trials as f64 * p * (1.0 - p);
+            assert!((mean - trials as f64 * p).abs() < 4.0 * (expected_var / 20000.0).sqrt());
+            assert!((var / expected_var - 1.0).abs() < 0.1);
+        }
+        let values: Vec<f64> = Geometric::new(0.2)
+            .samples(&mut rng)
+            .take(20000)
+            .map(|x| x as f64)
+            .collect();
+        let (mean, var) = mean_and_variance(&values);
+        assert!((mean - 4.0).abs() < 0.15);
+        assert!((var - 20.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn binomial_matches_pmf() {
+        let mut rng = Mt19937::new(2);
+        let pmf: Vec<f64> = BinomialDistribution::new_big(15, Ratio::new(2, 3))
+            .map(|r| r.to_f64().unwrap())
+            .collect();
+        let mut counts = [0; 16];
+        for x in Binomial::new(15, 2.0 / 3.0).samples(&mut rng).take(50000) {
+            counts[x as usize] += 1;
+        }
+        // Merge the rarest outcomes so every expected count is large enough
+        let merged_counts = [vec![counts[..7].iter().sum()], counts[7..].to_vec()].concat();
+        let merged_pmf = [vec![pmf[..7].iter().sum()], pmf[7..].to_vec()].concat();
+        assert!(chi_square_p_value(&merged_counts, &merged_pmf) > 0.001);
+    }
+
+    #[test]
+    fn alias_and_exact() {
+        let mut rng = Mt19937::new(3);
+        let weights = [1.0, 0.0, 2.0, 5.0, 0.5];
+        let table = AliasTable::new(&weights);
+        let mut counts = [0; 5];
+        for i in table.samples(&mut rng).take(50000) {
+            counts[i] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        let probs: Vec<f64> = [1.0, 2.0, 5.0, 0.5].iter().map(|w| w / 8.5).collect();
+        let nonzero = [counts[0], counts[2], counts[3], counts[4]];
+        assert!(chi_square_p_value(&nonzero, &probs) > 0.001);
+
+        // Exact sampling from the rational binomial probabilities
+        let pmf: Vec<Ratio<BigInt>> = BinomialDistribution::new_big(6, Ratio::new(1, 3)).collect();
+        let exact = ExactDiscrete::new(&pmf);
+        let mut counts = [0; 7];
+        for i in exact.samples(&mut rng).take(50000) {
+            counts[i] += 1;
+        }
+        let probs: Vec<f64> = [64.0, 192.0, 240.0, 160.0, 60.0, 12.0, 1.0]
+            .iter()
+            .map(|w| w / 729.0)
+            .collect();
+        // The last outcome is merged into the one before
+        let merged_counts = [&counts[..5], &[counts[5] + counts[6]]].concat();
+        let merged_probs = [&probs[..5], &[probs[5] + probs[6]]].concat();
+        assert!(chi_square_p_value(&merged_counts, &merged_probs) > 0.001);
+
+        // An outcome with probability zero never occurs
+        let exact = ExactDiscrete::new(&[
+            Ratio::new(BigInt::from(0), BigInt::from(1)),
+            Ratio::new(BigInt::from(1), BigInt::from(7)),
+        ]);
+        assert!(exact.samples(&mut rng).take(1000).all(|i| i == 1));
+    }
+}
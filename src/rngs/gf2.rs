@@ -0,0 +1,231 @@
+//! Linear algebra over GF(2) used to jump ahead in generators whose state transition is linear.
+//!
+//! Polynomials are packed into words with the coefficient of x^i at bit i % 64 of word i / 64.
+
+/// Apply a linear map on at most 128 bits to the state n times. The map is squared repeatedly as a matrix stored by columns, so this takes O(log n) steps.
+pub(crate) fn advance_linear<F: FnMut(u128) -> u128>(
+    mut step: F,
+    mut state: u128,
+    mut n: u64,
+) -> u128 {
+    let mut columns: Vec<u128> = (0..128).map(|j| step(1 << j)).collect();
+    while n > 0 {
+        if n & 1 == 1 {
+            state = apply_columns(&columns, state);
+        }
+        n >>= 1;
+        if n > 0 {
+            columns = columns
+                .iter()
+                .map(|c| apply_columns(&columns, *c))
+                .collect();
+        }
+    }
+    state
+}
+
+fn apply_columns(columns: &[u128], v: u128) -> u128 {
+    let mut out = 0;
+    let mut v = v;
+    while v != 0 {
+        out ^= columns[v.trailing_zeros() as usize];
+        v &= v - 1;
+    }
+    out
+}
+
+/// Degree of a packed polynomial, or None for the zero polynomial.
+pub(crate) fn degree(p: &[u64]) -> Option<usize> {
+    p.iter()
+        .rposition(|w| *w != 0)
+        .map(|i| 64 * i + 63 - p[i].leading_zeros() as usize)
+}
+
+pub(crate) fn coefficient(p: &[u64], i: usize) -> bool {
+    p.get(i / 64).is_some_and(|w| (w >> (i % 64)) & 1 == 1)
+}
+
+/// 64 bits of a packed bit array beginning at the given bit offset, with zeros past the end.
+fn word_at(p: &[u64], offset: usize) -> u64 {
+    let (w, s) = (offset / 64, offset % 64);
+    let lo = p.get(w).copied().unwrap_or(0) >> s;
+    if s == 0 {
+        lo
+    } else {
+        lo | (p.get(w + 1).copied().unwrap_or(0) << (64 - s))
+    }
+}
+
+/// XOR b shifted left by the given number of bits into a.
+fn xor_shifted(a: &mut [u64], b: &[u64], shift: usize) {
+    let (w, s) = (shift / 64, shift % 64);
+    for (i, x) in b.iter().enumerate() {
+        if *x == 0 {
+            continue;
+        }
+        if let Some(t) = a.get_mut(i + w) {
+            *t ^= x << s;
+        }
+        if s != 0
+            && let Some(t) = a.get_mut(i + w + 1)
+        {
+            *t ^= x >> (64 - s);
+        }
+    }
+}
+
+/// The Berlekamp-Massey algorithm. Returns the linear complexity of the bits, which is the length of the shortest linear recurrence s_i = c_1 s_(i-1) + ... + c_L s_(i-L) that produces them, along with its connection polynomial 1 + c_1 x + ... + c_L x^L. The linear complexity of each prefix is passed to profile as it is found.
+pub(crate) fn berlekamp_massey<F: FnMut(usize)>(bits: &[u8], mut profile: F) -> (usize, Vec<u64>) {
+    let n = bits.len();
+    let words = n / 64 + 2;
+    // The sequence in reverse so each discrepancy is an inner product with a contiguous range
+    let mut reversed = vec![0_u64; words];
+    for (t, b) in bits.iter().rev().enumerate() {
+        reversed[t / 64] |= (*b as u64 & 1) << (t % 64);
+    }
+    let mut c = vec![0_u64; words];
+    let mut b = vec![0_u64; words];
+    c[0] = 1;
+    b[0] = 1;
+    let mut l = 0;
+    let mut m = 1;
+    for i in 0..n {
+        let offset = n - 1 - i;
+        let d = (0..=l / 64)
+            .fold(0, |acc, w| {
+                acc ^ (c[w] & word_at(&reversed, offset + 64 * w))
+            })
+            .count_ones()
+            & 1;
+        if d == 0 {
+            m += 1;
+        } else if 2 * l <= i {
+            let t = c.clone();
+            xor_shifted(&mut c, &b, m);
+            l = i + 1 - l;
+            b = t;
+            m = 1;
+        } else {
+            xor_shifted(&mut c, &b, m);
+            m += 1;
+        }
+        profile(l);
+    }
+    c.truncate(l / 64 + 1);
+    (l, c)
+}
+
+/// The minimal polynomial of a sequence of bits, which is the reciprocal x^L + c_1 x^(L-1) + ... + c_L of its connection polynomial.
+pub(crate) fn minimal_polynomial(bits: &[u8]) -> Vec<u64> {
+    let (l, c) = berlekamp_massey(bits, |_| {});
+    let mut out = vec![0_u64; l / 64 + 1];
+    for i in 0..=l {
+        if coefficient(&c, i) {
+            out[(l - i) / 64] |= 1 << ((l - i) % 64);
+        }
+    }
+    out
+}
+
+/// Reduce a packed polynomial modulo another in place, using copies of the modulus shifted by every amount up to 63 bits.
+fn reduce(p: &mut Vec<u64>, shifted: &[Vec<u64>], deg: usize) {
+    if let Some(top) = degree(p) {
+        for k in (deg..=top).rev() {
+            if coefficient(p, k) {
+                let s = k - deg;
+                let (w, r) = (s / 64, s % 64);
+                for (i, x) in shifted[r].iter().enumerate() {
+                    if let Some(t) = p.get_mut(i + w) {
+                        *t ^= x;
+                    }
+                }
+            }
+        }
+    }
+    p.truncate(deg / 64 + 1);
+}
+
+/// x^n modulo a polynomial of positive degree.
+pub(crate) fn x_pow_mod(n: u64, modulus: &[u64]) -> Vec<u64> {
+    let deg = degree(modulus).expect("modulus must be nonzero");
+    assert!(deg > 0, "modulus must have positive degree");
+    let mut shifted = vec![modulus.to_vec()];
+    for s in 1..64 {
+        let mut v = vec![0; modulus.len() + 1];
+        xor_shifted(&mut v, modulus, s);
+        shifted.push(v);
+    }
+    let mut out = vec![0_u64; deg / 64 + 1];
+    out[0] = 1;
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        // Squaring over GF(2) spreads the bits of each coefficient apart
+        let mut sq = vec![0_u64; 2 * out.len() + 1];
+        for (i, w) in out.iter().enumerate() {
+            sq[2 * i] = spread(*w as u32);
+            sq[2 * i + 1] = spread((w >> 32) as u32);
+        }
+        if (n >> bit) & 1 == 1 {
+            let mut times_x = vec![0_u64; sq.len() + 1];
+            xor_shifted(&mut times_x, &sq, 1);
+            sq = times_x;
+        }
+        reduce(&mut sq, &shifted, deg);
+        out = sq;
+    }
+    out
+}
+
+/// Insert a zero bit above each bit of a word.
+fn spread(x: u32) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 16)) & 0x0000ffff0000ffff;
+    x = (x | (x << 8)) & 0x00ff00ff00ff00ff;
+    x = (x | (x << 4)) & 0x0f0f0f0f0f0f0f0f;
+    x = (x | (x << 2)) & 0x3333333333333333;
+    x = (x | (x << 1)) & 0x5555555555555555;
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_polynomial_of_lfsr() {
+        // s_i = s_(i-1) + s_(i-4), characteristic polynomial x^4 + x^3 + 1
+        let mut s = vec![1, 0, 0, 0];
+        for i in 4..30 {
+            s.push(s[i - 1] ^ s[i - 4]);
+        }
+        assert_eq!(minimal_polynomial(&s), vec![0b11001]);
+        assert_eq!(minimal_polynomial(&[0, 0, 0, 0]), vec![1]);
+    }
+
+    #[test]
+    fn powers_of_x() {
+        // x^4 + x + 1 is primitive so x has order 15
+        let p = [0b10011];
+        assert_eq!(x_pow_mod(15, &p), vec![1]);
+        assert_eq!(x_pow_mod(4, &p), vec![0b0011]);
+        assert_eq!(x_pow_mod(5, &p), vec![0b0110]);
+        assert_eq!(x_pow_mod(0, &p), vec![1]);
+        // A modulus spanning several words
+        let mut big = vec![0_u64; 3];
+        big[2] = 1 << 3; // x^131
+        big[0] = 1;
+        assert_eq!(x_pow_mod(131, &big), vec![1, 0, 0]);
+        assert_eq!(x_pow_mod(262, &big), vec![1, 0, 0]);
+        assert_eq!(x_pow_mod(130, &big), vec![0, 0, 1 << 2]);
+    }
+
+    #[test]
+    fn linear_advance() {
+        let step = |s: u128| s.rotate_left(3) ^ (s >> 5);
+        let mut s = 0x123456789abcdef_u128;
+        let jumped = advance_linear(step, s, 1000);
+        for _ in 0..1000 {
+            s = step(s);
+        }
+        assert_eq!(jumped, s);
+    }
+}
@@ -0,0 +1,553 @@
+use crate::{Number, utils::divisibility::prime_divisors};
+use num::Integer;
+use std::{
+    fmt::{Debug, Display},
+    ops::{Add, Div, Mul, Rem},
+};
+
+/// The distinct prime factors of 2^n - 1 for n up to 64, indexed by n.
+const MERSENNE_FACTORS: [&[u64]; 65] = [
+    &[],
+    &[],
+    &[3],
+    &[7],
+    &[3, 5],
+    &[31],
+    &[3, 7],
+    &[127],
+    &[3, 5, 17],
+    &[7, 73],
+    &[3, 11, 31],
+    &[23, 89],
+    &[3, 5, 7, 13],
+    &[8191],
+    &[3, 43, 127],
+    &[7, 31, 151],
+    &[3, 5, 17, 257],
+    &[131071],
+    &[3, 7, 19, 73],
+    &[524287],
+    &[3, 5, 11, 31, 41],
+    &[7, 127, 337],
+    &[3, 23, 89, 683],
+    &[47, 178481],
+    &[3, 5, 7, 13, 17, 241],
+    &[31, 601, 1801],
+    &[3, 2731, 8191],
+    &[7, 73, 262657],
+    &[3, 5, 29, 43, 113, 127],
+    &[233, 1103, 2089],
+    &[3, 7, 11, 31, 151, 331],
+    &[2147483647],
+    &[3, 5, 17, 257, 65537],
+    &[7, 23, 89, 599479],
+    &[3, 43691, 131071],
+    &[31, 71, 127, 122921],
+    &[3, 5, 7, 13, 19, 37, 73, 109],
+    &[223, 616318177],
+    &[3, 174763, 524287],
+    &[7, 79, 8191, 121369],
+    &[3, 5, 11, 17, 31, 41, 61681],
+    &[13367, 164511353],
+    &[3, 7, 43, 127, 337, 5419],
+    &[431, 9719, 2099863],
+    &[3, 5, 23, 89, 397, 683, 2113],
+    &[7, 31, 73, 151, 631, 23311],
+    &[3, 47, 178481, 2796203],
+    &[2351, 4513, 13264529],
+    &[3, 5, 7, 13, 17, 97, 241, 257, 673],
+    &[127, 4432676798593],
+    &[3, 11, 31, 251, 601, 1801, 4051],
+    &[7, 103, 2143, 11119, 131071],
+    &[3, 5, 53, 157, 1613, 2731, 8191],
+    &[6361, 69431, 20394401],
+    &[3, 7, 19, 73, 87211, 262657],
+    &[23, 31, 89, 881, 3191, 201961],
+    &[3, 5, 17, 29, 43, 113, 127, 15790321],
+    &[7, 32377, 524287, 1212847],
+    &[3, 59, 233, 1103, 2089, 3033169],
+    &[179951, 3203431780337],
+    &[3, 5, 7, 11, 13, 31, 41, 61, 151, 331, 1321],
+    &[2305843009213693951],
+    &[3, 715827883, 2147483647],
+    &[7, 73, 127, 337, 92737, 649657],
+    &[3, 5, 17, 257, 641, 65537, 6700417],
+];
+
+/// A polynomial over GF(2) of degree at most 127. The coefficient of x^i is bit i of the underlying integer.
+///
+/// ```text
+/// 0b10011 = x^4 + x + 1
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Gf2Polynomial {
+    coef: u128,
+}
+
+impl Gf2Polynomial {
+    pub const ZERO: Self = Self { coef: 0 };
+    pub const ONE: Self = Self { coef: 1 };
+    pub const X: Self = Self { coef: 2 };
+
+    pub fn new(coef: u128) -> Self {
+        Self { coef }
+    }
+
+    /// The polynomial with a term for each exponent given. Repeated exponents cancel.
+    /// Panics if any exponent is greater than 127.
+    pub fn from_exponents(exponents: &[u32]) -> Self {
+        let mut coef = 0;
+        for e in exponents {
+            assert!(*e < 128, "exponents must be at most 127");
+            coef ^= 1 << e;
+        }
+        Self { coef }
+    }
+
+    /// The coefficients as the bits of an integer.
+    pub fn coef(&self) -> u128 {
+        self.coef
+    }
+
+    /// The coefficient of x^i.
+    pub fn coefficient(&self, i: u32) -> bool {
+        i < 128 && (self.coef >> i) & 1 == 1
+    }
+
+    /// The degree of the polynomial. None if the polynomial is zero.
+    pub fn degree(&self) -> Option<u32> {
+        if self.coef == 0 {
+            None
+        } else {
+            Some(127 - self.coef.leading_zeros())
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coef == 0
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.coef == 1
+    }
+
+    /// The formal derivative. Over GF(2) only the terms with odd exponents survive.
+    pub fn derivative(&self) -> Self {
+        Self {
+            coef: (self.coef >> 1) & 0x5555_5555_5555_5555_5555_5555_5555_5555,
+        }
+    }
+
+    /// The reciprocal polynomial x^n f(1/x) where n is the degree of f. Its roots are the inverses of the roots of f, so it is primitive exactly when f is.
+    pub fn reciprocal(&self) -> Self {
+        match self.degree() {
+            Some(d) => Self {
+                coef: self.coef.reverse_bits() >> (127 - d),
+            },
+            None => *self,
+        }
+    }
+
+    /// The product, or None if its degree would be greater than 127.
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        match (self.degree(), rhs.degree()) {
+            (Some(a), Some(b)) if a + b > 127 => None,
+            _ => {
+                let mut coef = 0;
+                let mut r = rhs.coef;
+                while r != 0 {
+                    coef ^= self.coef << r.trailing_zeros();
+                    r &= r - 1;
+                }
+                Some(Self { coef })
+            }
+        }
+    }
+
+    /// Quotient and remainder.
+    /// Panics if the divisor is zero.
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let d = rhs.degree().expect("division by zero");
+        let mut q = 0;
+        let mut r = self.coef;
+        while let Some(n) = Self::new(r).degree()
+            && n >= d
+        {
+            q ^= 1 << (n - d);
+            r ^= rhs.coef << (n - d);
+        }
+        (Self::new(q), Self::new(r))
+    }
+
+    pub fn gcd(&self, rhs: &Self) -> Self {
+        let (mut a, mut b) = (*self, *rhs);
+        while !b.is_zero() {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+
+    pub fn lcm(&self, rhs: &Self) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::ZERO;
+        }
+        (*self / self.gcd(rhs)) * *rhs
+    }
+
+    /// The product modulo another polynomial.
+    /// Panics if the modulus is zero.
+    pub fn mul_mod(&self, rhs: &Self, modulus: &Self) -> Self {
+        let d = modulus.degree().expect("modulus must be nonzero");
+        let a = *self % *modulus;
+        let b = *rhs % *modulus;
+        let mut acc = 0_u128;
+        for i in (0..128 - b.coef.leading_zeros()).rev() {
+            // acc has degree less than d so multiplying by x cannot overflow
+            acc <<= 1;
+            if (acc >> d) & 1 == 1 {
+                acc ^= modulus.coef;
+            }
+            if (b.coef >> i) & 1 == 1 {
+                acc ^= a.coef;
+            }
+        }
+        Self::new(acc)
+    }
+
+    /// Raise to a power modulo another polynomial.
+    /// Panics if the modulus is zero.
+    pub fn pow_mod(&self, mut exp: u128, modulus: &Self) -> Self {
+        let mut base = *self % *modulus;
+        let mut out = Self::ONE % *modulus;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out = out.mul_mod(&base, modulus);
+            }
+            base = base.mul_mod(&base, modulus);
+            exp >>= 1;
+        }
+        out
+    }
+
+    /// x^(2^k) modulo the polynomial, by squaring k times.
+    fn x_pow_pow2_mod(&self, k: u32) -> Self {
+        let mut p = Self::X % *self;
+        for _ in 0..k {
+            p = p.mul_mod(&p, self);
+        }
+        p
+    }
+
+    /// Rabin's test. A polynomial of degree n is irreducible exactly when x^(2^n) = x modulo it and x^(2^(n/q)) - x shares no factor with it for each prime q dividing n.
+    pub fn is_irreducible(&self) -> bool {
+        let n = match self.degree() {
+            Some(0) | None => return false,
+            Some(n) => n,
+        };
+        if self.x_pow_pow2_mod(n) != Self::X % *self {
+            return false;
+        }
+        prime_divisors(n as Number).into_iter().all(|q| {
+            (self.x_pow_pow2_mod(n / q as u32) + Self::X)
+                .gcd(self)
+                .is_one()
+        })
+    }
+
+    /// A polynomial of degree n is primitive when it is irreducible and x has order 2^n - 1 modulo it. These are exactly the characteristic polynomials of maximal-length LFSRs.
+    /// Panics if the degree is greater than 64.
+    pub fn is_primitive(&self) -> bool {
+        let n = match self.degree() {
+            Some(0) | None => return false,
+            Some(n) => n,
+        };
+        assert!(n <= 64, "degree must be at most 64");
+        if !self.coefficient(0) || !self.is_irreducible() {
+            return false;
+        }
+        let m = (1_u128 << n) - 1;
+        MERSENNE_FACTORS[n as usize]
+            .iter()
+            .all(|p| !Self::X.pow_mod(m / *p as u128, self).is_one())
+    }
+
+    /// Square-free factorization. Each factor is square-free and is given with its multiplicity.
+    fn squarefree_factors(&self) -> Vec<(Self, u32)> {
+        let mut out = Vec::new();
+        let mut c = self.gcd(&self.derivative());
+        let mut w = *self / c;
+        let mut i = 1;
+        while !w.is_one() {
+            let y = w.gcd(&c);
+            let z = w / y;
+            if !z.is_one() {
+                out.push((z, i));
+            }
+            i += 1;
+            w = y;
+            c = c / y;
+        }
+        if !c.is_one() {
+            // What remains is a square, and over GF(2) its square root is found by halving every exponent
+            let mut root = 0;
+            for j in 0..64 {
+                root |= ((c.coef >> (2 * j)) & 1) << j;
+            }
+            for (f, m) in Self::new(root).squarefree_factors() {
+                out.push((f, 2 * m));
+            }
+        }
+        out
+    }
+
+    /// Distinct degree factorization of a square-free polynomial. Each factor is the product of all of the irreducible factors of one degree.
+    fn distinct_degree_factors(&self) -> Vec<(Self, u32)> {
+        let mut out = Vec::new();
+        let mut f = *self;
+        let mut xp = Self::X % f;
+        let mut d = 1;
+        while let Some(n) = f.degree()
+            && n >= 2 * d
+        {
+            xp = xp.mul_mod(&xp, &f);
+            let h = (xp + Self::X).gcd(&f);
+            if !h.is_one() {
+                out.push((h, d));
+                f = f / h;
+                xp = xp % f;
+            }
+            d += 1;
+        }
+        if let Some(n) = f.degree()
+            && n > 0
+        {
+            out.push((f, n));
+        }
+        out
+    }
+
+    /// The multiplicative order of x modulo the polynomial, which is the period of any LFSR with this characteristic polynomial whose state is in general position. Returns None if x is not invertible modulo the polynomial, if the polynomial is constant, or if it has an irreducible factor of degree greater than 64.
+    pub fn order(&self) -> Option<u128> {
+        if self.degree()? == 0 || !self.coefficient(0) {
+            return None;
+        }
+        // The order modulo p^e is the order modulo p times the least power of 2 that is at least e
+        let factors = self.squarefree_factors();
+        let max_multiplicity = factors.iter().map(|(_, m)| *m).max().unwrap_or(1);
+        let radical = factors.iter().fold(Self::ONE, |acc, (f, _)| acc.lcm(f));
+        let mut order = 1_u128;
+        for (h, d) in radical.distinct_degree_factors() {
+            if d > 64 {
+                return None;
+            }
+            // The order modulo a product of irreducibles of degree d divides 2^d - 1
+            let mut n = (1_u128 << d) - 1;
+            for p in MERSENNE_FACTORS[d as usize] {
+                let p = *p as u128;
+                while n.is_multiple_of(p) && Self::X.pow_mod(n / p, &h).is_one() {
+                    n /= p;
+                }
+            }
+            order = order.lcm(&n);
+        }
+        order.checked_mul(max_multiplicity.next_power_of_two() as u128)
+    }
+
+    /// The primitive polynomial of degree n with the smallest coefficients.
+    /// Panics if n is zero or greater than 64.
+    pub fn primitive(n: u32) -> Self {
+        PrimitivePolynomials::new(n).next().unwrap()
+    }
+}
+
+impl Add for Gf2Polynomial {
+    type Output = Self;
+
+    // Addition of coefficients in GF(2) is XOR
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.coef ^ rhs.coef)
+    }
+}
+
+impl Mul for Gf2Polynomial {
+    type Output = Self;
+
+    /// Panics if the degree of the product would be greater than 127.
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(&rhs)
+            .expect("product must have degree at most 127")
+    }
+}
+
+impl Div for Gf2Polynomial {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).0
+    }
+}
+
+impl Rem for Gf2Polynomial {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).1
+    }
+}
+
+impl Display for Gf2Polynomial {
+    /// Terms in descending order of degree.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let terms: Vec<String> = (0..128)
+            .rev()
+            .filter(|i| self.coefficient(*i))
+            .map(|i| match i {
+                0 => "1".to_string(),
+                1 => "x".to_string(),
+                _ => format!("x^{i}"),
+            })
+            .collect();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+impl Debug for Gf2Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The primitive polynomials over GF(2) of degree n in increasing order of their coefficients.
+///
+/// ```text
+/// n = 4
+/// x^4 + x + 1, x^4 + x^3 + 1
+///
+/// n = 5
+/// x^5 + x^2 + 1, x^5 + x^3 + 1, x^5 + x^3 + x^2 + x + 1, x^5 + x^4 + x^2 + x + 1...
+/// ```
+pub struct PrimitivePolynomials {
+    coef: u128,
+    end: u128,
+}
+
+impl PrimitivePolynomials {
+    /// Panics if n is zero or greater than 64.
+    pub fn new(n: u32) -> Self {
+        assert!(n > 0 && n <= 64, "degree must be between 1 and 64");
+        Self {
+            // Every primitive polynomial has a constant term except x + 1
+            coef: 1 << n,
+            end: 1 << (n + 1),
+        }
+    }
+}
+
+impl Iterator for PrimitivePolynomials {
+    type Item = Gf2Polynomial;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.coef < self.end {
+            let p = Gf2Polynomial::new(self.coef);
+            self.coef += 1;
+            if p.is_primitive() {
+                return Some(p);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mersenne_factors() {
+        for (n, factors) in MERSENNE_FACTORS.iter().enumerate().skip(1) {
+            let mut m = (1_u128 << n) - 1;
+            for p in factors.iter() {
+                assert!(crate::utils::miller_rabin::is_prime(*p as Number));
+                assert_eq!(m % *p as u128, 0);
+                while m % *p as u128 == 0 {
+                    m /= *p as u128;
+                }
+            }
+            assert_eq!(m, 1);
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Gf2Polynomial::from_exponents(&[4, 1, 0]);
+        let b = Gf2Polynomial::from_exponents(&[2, 0]);
+        assert_eq!(a.to_string(), "x^4 + x + 1");
+        assert_eq!(a * b, Gf2Polynomial::from_exponents(&[6, 4, 3, 2, 1, 0]));
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(q * b + r, a);
+        assert!(r.degree() < b.degree());
+        assert_eq!((a * b).gcd(&(b * b)), b);
+        assert_eq!(a.reciprocal(), Gf2Polynomial::from_exponents(&[4, 3, 0]));
+        assert_eq!(a.derivative(), Gf2Polynomial::ONE);
+        assert_eq!(Gf2Polynomial::X.pow_mod(15, &a), Gf2Polynomial::ONE);
+        assert_eq!(
+            Gf2Polynomial::new(1 << 127).checked_mul(&Gf2Polynomial::X),
+            None
+        );
+    }
+
+    #[test]
+    fn irreducible_counts() {
+        // The number of irreducible polynomials of degree n (OEIS A001037)
+        let counts: Vec<usize> = (1..=10)
+            .map(|n| {
+                (1_u128 << n..1 << (n + 1))
+                    .filter(|c| Gf2Polynomial::new(*c).is_irreducible())
+                    .count()
+            })
+            .collect();
+        assert_eq!(counts, [2, 1, 2, 3, 6, 9, 18, 30, 56, 99]);
+    }
+
+    #[test]
+    fn primitive_counts() {
+        // The number of primitive polynomials of degree n is phi(2^n - 1) / n (OEIS A011260)
+        let counts: Vec<usize> = (1..=12)
+            .map(|n| PrimitivePolynomials::new(n).count())
+            .collect();
+        assert_eq!(counts, [1, 1, 2, 2, 6, 6, 18, 16, 48, 60, 176, 144]);
+        assert_eq!(
+            Gf2Polynomial::primitive(8),
+            Gf2Polynomial::from_exponents(&[8, 4, 3, 2, 0])
+        );
+        assert_eq!(
+            Gf2Polynomial::primitive(64),
+            Gf2Polynomial::from_exponents(&[64, 4, 3, 1, 0])
+        );
+        // x^8 + x^4 + x^3 + x + 1, the AES modulus, is irreducible but not primitive
+        let aes = Gf2Polynomial::from_exponents(&[8, 4, 3, 1, 0]);
+        assert!(aes.is_irreducible());
+        assert!(!aes.is_primitive());
+        assert_eq!(aes.order(), Some(51));
+    }
+
+    #[test]
+    fn orders() {
+        let p = |e: &[u32]| Gf2Polynomial::from_exponents(e);
+        assert_eq!(p(&[4, 1, 0]).order(), Some(15));
+        assert_eq!(p(&[1, 0]).order(), Some(1));
+        // (x^2 + x + 1)^2 has order 3 * 2
+        assert_eq!(p(&[4, 2, 0]).order(), Some(6));
+        // (x + 1)^3 has order 4
+        assert_eq!(p(&[3, 2, 1, 0]).order(), Some(4));
+        // (x^2 + x + 1)(x^3 + x + 1) has order lcm(3, 7)
+        assert_eq!((p(&[2, 1, 0]) * p(&[3, 1, 0])).order(), Some(21));
+        assert_eq!(p(&[4, 1]).order(), None);
+        assert_eq!(Gf2Polynomial::ONE.order(), None);
+        assert_eq!(p(&[64, 4, 3, 1, 0]).order(), Some(u64::MAX as u128));
+    }
+}
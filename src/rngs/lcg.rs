@@ -1,4 +1,4 @@
-use crate::rngs::{SQRTUMAX, UMAX, UNumber};
+use crate::rngs::{Advance, Rng, SQRTUMAX, UMAX, UNumber, u32_from_modulus};
 use num::PrimInt;
 
 /// A linear congruential generator.
@@ -12,6 +12,7 @@ use num::PrimInt;
 /// n = 1, a = 65539, b = 0, m = 2147483648 (RANDU)
 /// 1, 65539, 393225, 1769499, 7077969, 26542323, 95552217, 334432395...
 /// ```
+#[derive(Clone)]
 pub struct Lcg<T> {
     n: T,
     a: T,
@@ -41,6 +42,33 @@ impl<T: PrimInt> Iterator for Lcg<T> {
     }
 }
 
+impl Advance for Lcg<UNumber> {
+    /// A step is one value of the iterator. The affine map x -> ax + c is composed with itself by repeated squaring.
+    fn advance(&mut self, mut n: u64) {
+        let m = self.m as u128;
+        let (mut mul, mut add) = (self.a as u128 % m, self.c as u128 % m);
+        let (mut acc_mul, mut acc_add) = (1 % m, 0);
+        while n > 0 {
+            if n & 1 == 1 {
+                acc_mul = acc_mul * mul % m;
+                acc_add = (acc_add * mul + add) % m;
+            }
+            add = (add * mul + add) % m;
+            mul = mul * mul % m;
+            n >>= 1;
+        }
+        self.n = ((acc_mul * self.n as u128 + acc_add) % m) as UNumber;
+    }
+}
+
+impl Rng for Lcg<UNumber> {
+    /// Built from the largest number of bits that are uniform below the modulus.
+    fn next_u32(&mut self) -> u32 {
+        let m = self.m;
+        u32_from_modulus(m, || self.next().unwrap())
+    }
+}
+
 crate::check_sequences!(
     // Lehmer's LCG
     Lcg::new(47594118, 23, 0, 100000001), [47594118, 94664704, 77288171, 77627916, 85442051, 65167154, 98844528, 73424122, 88754790, 41360150, 51283441, 79519132, 28940018, 65620408, 9269369, 13195485, 3496152, 80411496, 49464390, 37680959, 66662049, 33227112, 64223569];
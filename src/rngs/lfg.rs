@@ -1,4 +1,4 @@
-use crate::rngs::{HALFUMAX, SQRTUMAX, UNumber};
+use crate::rngs::{HALFUMAX, Rng, SQRTUMAX, UNumber, u32_from_modulus};
 use num::PrimInt;
 
 /// Lagged Fibonacci Generator using addition.
@@ -37,6 +37,14 @@ impl<T: PrimInt> Iterator for Lfg<T> {
     }
 }
 
+impl Rng for Lfg<UNumber> {
+    /// Built from the largest number of bits that are uniform below the modulus.
+    fn next_u32(&mut self) -> u32 {
+        let m = self.m;
+        u32_from_modulus(m, || self.next().unwrap())
+    }
+}
+
 /// Lagged Fibonacci Generator using multiplication.
 ///
 /// ```text
@@ -73,6 +81,14 @@ impl<T: PrimInt> Iterator for LfgMult<T> {
     }
 }
 
+impl Rng for LfgMult<UNumber> {
+    /// Built from the largest number of bits that are uniform below the modulus.
+    fn next_u32(&mut self) -> u32 {
+        let m = self.m;
+        u32_from_modulus(m, || self.next().unwrap())
+    }
+}
+
 crate::sample_sequences!(
     Lfg::new(123, 456, 789);
     LfgMult::new(123, 456, 789);
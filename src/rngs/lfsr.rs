@@ -1,4 +1,7 @@
-use crate::rngs::{UBITS, UMAX, UNumber};
+use crate::rngs::{
+    Advance, Gf2Polynomial, Rng, UBITS, UMAX, UNumber, connection_polynomial,
+    gf2::{advance_linear, minimal_polynomial},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum LfsrType {
@@ -17,6 +20,7 @@ enum LfsrType {
 /// state = 0b0000000001, taps = 0b1001000000
 /// 1, 800, 400, 200, 100, 50, 25, 812, 406, 203, 837, 642, 321, 896...
 /// ```
+#[derive(Clone)]
 pub struct Lfsr<T> {
     state: T,
     taps: T,
@@ -49,6 +53,113 @@ impl Lfsr<UNumber> {
     pub fn new_galois(state: UNumber, taps: UNumber) -> Self {
         Self::new(state, taps, LfsrType::Galois)
     }
+
+    /// A Fibonacci LFSR with the given characteristic polynomial, whose degree is the number of bits used. The output bits satisfy the linear recurrence given by the polynomial.
+    /// Panics if the polynomial does not have degree between 1 and 64 or does not have a constant term.
+    pub fn from_polynomial_fibonacci(state: UNumber, polynomial: Gf2Polynomial) -> Self {
+        let bits = Self::polynomial_width(&polynomial);
+        // The lowest bit of the state is always fed back so it never needs a tap
+        let taps = (polynomial.coef() as UNumber) & (UMAX << 1) & (UMAX >> (UBITS - bits));
+        Self {
+            state,
+            taps,
+            bits,
+            lfsr_type: LfsrType::Fibonacci,
+        }
+    }
+
+    /// A Galois LFSR with the given characteristic polynomial, whose degree is the number of bits used. The output bits satisfy the linear recurrence given by the polynomial.
+    /// Panics if the polynomial does not have degree between 1 and 64 or does not have a constant term.
+    pub fn from_polynomial_galois(state: UNumber, polynomial: Gf2Polynomial) -> Self {
+        let bits = Self::polynomial_width(&polynomial);
+        // Tap i is the coefficient of x^(bits - i)
+        let taps =
+            (polynomial.reciprocal().coef() as UNumber) & (UMAX >> (UBITS - bits)) & (UMAX << 1);
+        Self {
+            state,
+            taps,
+            bits,
+            lfsr_type: LfsrType::Galois,
+        }
+    }
+
+    fn polynomial_width(polynomial: &Gf2Polynomial) -> UNumber {
+        let bits = polynomial.degree().unwrap_or(0) as UNumber;
+        assert!(
+            (1..=UBITS).contains(&bits),
+            "polynomial must have degree between 1 and {UBITS}"
+        );
+        assert!(
+            polynomial.coefficient(0),
+            "polynomial must have a constant term"
+        );
+        bits
+    }
+
+    /// A Fibonacci LFSR of the given width with a primitive characteristic polynomial, so every nonzero state is reached before it repeats.
+    /// Panics if width is not between 1 and 64.
+    pub fn maximal_fibonacci(state: UNumber, width: u32) -> Self {
+        Self::from_polynomial_fibonacci(state, Gf2Polynomial::primitive(width))
+    }
+
+    /// A Galois LFSR of the given width with a primitive characteristic polynomial, so every nonzero state is reached before it repeats.
+    /// Panics if width is not between 1 and 64.
+    pub fn maximal_galois(state: UNumber, width: u32) -> Self {
+        Self::from_polynomial_galois(state, Gf2Polynomial::primitive(width))
+    }
+
+    /// The characteristic polynomial of the register. A register whose characteristic polynomial is primitive has the maximum period of 2^n - 1.
+    pub fn characteristic_polynomial(&self) -> Gf2Polynomial {
+        let k = self.bits as u32;
+        let taps = Gf2Polynomial::new(self.taps as u128 & !1);
+        let feedback = match self.lfsr_type {
+            // A tap on the lowest bit cancels the feedback that is always present there
+            LfsrType::Fibonacci => taps + Gf2Polynomial::new(1 ^ (self.taps & 1) as u128),
+            LfsrType::Galois => Gf2Polynomial::new((taps.coef().reverse_bits() >> (127 - k)) | 1),
+        };
+        feedback + Gf2Polynomial::new(1 << k)
+    }
+
+    /// The number of steps after which the sequence of states repeats, ignoring any states at the start that never recur. This is found from the minimal polynomial of the output without running through the period.
+    pub fn period(&self) -> u64 {
+        let bits: Vec<u8> = self
+            .clone()
+            .take(2 * self.bits as usize)
+            .map(|s| (s & 1) as u8)
+            .collect();
+        let m = minimal_polynomial(&bits);
+        let mut m = m[0] as u128 | (m.get(1).copied().unwrap_or(0) as u128) << 64;
+        // Factors of x only delay the start of the cycle
+        while m > 1 && m & 1 == 0 {
+            m >>= 1;
+        }
+        Gf2Polynomial::new(m).order().unwrap_or(1) as u64
+    }
+
+    /// The shortest Fibonacci LFSR whose output bits, the lowest bit of each state, begin with the given bits. It is found with the Berlekamp-Massey algorithm from any 2L bits of output, where L is the length of the register. Only the lowest bit of each value is used.
+    /// Returns None if the bits are all zero or no register of at most 64 bits produces them.
+    pub fn from_sequence(bits: &[u8]) -> Option<Self> {
+        let c = connection_polynomial(bits);
+        let l = c.len() - 1;
+        if l == 0 || l > UBITS as usize {
+            return None;
+        }
+        // The lowest bit of the state is always fed back so a tap there cancels it, which is needed when c_L is zero
+        let mut taps = (1 - c[l]) as UNumber;
+        let mut state = 0;
+        for i in 1..l {
+            taps |= (c[l - i] as UNumber) << i;
+        }
+        for (i, b) in bits[..l].iter().enumerate() {
+            state |= ((b & 1) as UNumber) << i;
+        }
+        Some(Self {
+            state,
+            taps,
+            bits: l as UNumber,
+            lfsr_type: LfsrType::Fibonacci,
+        })
+    }
 }
 
 impl Iterator for Lfsr<UNumber> {
@@ -76,7 +187,138 @@ impl Iterator for Lfsr<UNumber> {
     }
 }
 
+impl Advance for Lfsr<UNumber> {
+    /// A step is one shift of the register, so each u32 from the Rng interface takes 32 steps.
+    fn advance(&mut self, n: u64) {
+        let mut register = self.clone();
+        let step = |s: u128| {
+            register.state = s as UNumber;
+            register.next();
+            register.state as u128
+        };
+        self.state = advance_linear(step, self.state as u128, n) as UNumber;
+    }
+}
+
+impl Rng for Lfsr<UNumber> {
+    /// The bits shifted out of the register, which is the proper output of an LFSR, with the earliest bit as the most significant.
+    fn next_u32(&mut self) -> u32 {
+        let mut out = 0;
+        for _ in 0..32 {
+            out = (out << 1) | (self.next().unwrap() & 1) as u32;
+        }
+        out
+    }
+}
+
 crate::sample_sequences!(
     Lfsr::new_fibonacci(0b0000000001, 0b1001000000);
     Lfsr::new_galois(0b0000000001, 0b1001000000);
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output_bits(rng: &mut Lfsr<UNumber>, n: usize) -> Vec<u8> {
+        rng.take(n).map(|s| (s & 1) as u8).collect()
+    }
+
+    #[test]
+    fn reconstruction() {
+        // 2L bits of output are enough to predict everything that follows. The register found may be shorter if the taps do not give a primitive polynomial.
+        for (mut rng, length) in [
+            (Lfsr::new_fibonacci(1, 0b1001000000), 10),
+            (Lfsr::new_galois(1, 0b1001000000), 10),
+            (Lfsr::new_galois(0xdeadbeef, 0x80200003), 32),
+            (
+                Lfsr::new_fibonacci(0x123456789abcdef, 0xd800000000000000),
+                64,
+            ),
+        ] {
+            let observed = output_bits(&mut rng.clone(), 2 * length);
+            let mut rebuilt = Lfsr::from_sequence(&observed).unwrap();
+            assert!(rebuilt.bits <= length as UNumber);
+            assert_eq!(output_bits(&mut rebuilt, 2000), output_bits(&mut rng, 2000));
+        }
+    }
+
+    #[test]
+    fn reconstruction_edge_cases() {
+        assert!(Lfsr::from_sequence(&[0, 0, 0, 0]).is_none());
+        // A sequence that is not purely periodic needs a tap on the lowest bit
+        let bits = [1, 0, 0, 0, 0, 0];
+        let mut rebuilt = Lfsr::from_sequence(&bits).unwrap();
+        assert_eq!(output_bits(&mut rebuilt, 6), bits);
+        let bits = [1, 1, 0, 1, 0, 0, 1, 1, 0];
+        let mut rebuilt = Lfsr::from_sequence(&bits).unwrap();
+        assert_eq!(output_bits(&mut rebuilt, 9), bits);
+    }
+
+    #[test]
+    fn maximal_length() {
+        for width in 1..=12 {
+            for mut rng in [
+                Lfsr::maximal_fibonacci(1, width),
+                Lfsr::maximal_galois(1, width),
+            ] {
+                let period = (1 << width) - 1;
+                assert_eq!(rng.period(), period);
+                let mut seen = std::collections::HashSet::new();
+                for _ in 0..period {
+                    assert!(seen.insert(rng.next().unwrap()));
+                }
+                assert_eq!(rng.next(), Some(1));
+            }
+        }
+        for width in [32, 63, 64] {
+            let rng = Lfsr::maximal_galois(0xabcdef, width);
+            assert!(rng.characteristic_polynomial().is_primitive());
+            assert_eq!(rng.period(), u64::MAX >> (64 - width));
+        }
+    }
+
+    #[test]
+    fn characteristic_polynomials() {
+        // The polynomial used to build a register is recovered from it
+        for p in crate::rngs::PrimitivePolynomials::new(7) {
+            assert_eq!(
+                Lfsr::from_polynomial_fibonacci(1, p).characteristic_polynomial(),
+                p
+            );
+            assert_eq!(
+                Lfsr::from_polynomial_galois(1, p).characteristic_polynomial(),
+                p
+            );
+        }
+        // It agrees with the minimal polynomial of the output
+        for rng in [
+            Lfsr::new_fibonacci(1, 0b1001000000),
+            Lfsr::new_galois(1, 0b1001000000),
+        ] {
+            let bits = output_bits(&mut rng.clone(), 20);
+            let m = minimal_polynomial(&bits);
+            assert_eq!(rng.characteristic_polynomial().coef(), m[0] as u128);
+        }
+    }
+
+    #[test]
+    fn periods() {
+        // Periods found without running the register match those found by running it
+        for taps in 1..256 {
+            for rng in [Lfsr::new_fibonacci(1, taps), Lfsr::new_galois(1, taps)] {
+                let mut states = std::collections::HashMap::new();
+                let mut r = rng.clone();
+                let mut step = 0;
+                let period = loop {
+                    let s = r.next().unwrap();
+                    if let Some(first) = states.insert(s, step) {
+                        break step - first;
+                    }
+                    step += 1;
+                };
+                assert_eq!(rng.period(), period, "{taps:b}");
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::rngs::gf2::{berlekamp_massey, coefficient};
+
+/// The linear complexity of a sequence of bits, the length of the shortest LFSR that generates it. Only the lowest bit of each value is used.
+///
+/// A random sequence of n bits has linear complexity close to n/2. An LFSR of length L has linear complexity at most L, so observing 2L bits of its output is enough to reconstruct it with the Berlekamp-Massey algorithm.
+pub fn linear_complexity(bits: &[u8]) -> usize {
+    berlekamp_massey(bits, |_| {}).0
+}
+
+/// The linear complexity of each prefix of a sequence of bits. Only the lowest bit of each value is used.
+pub fn linear_complexity_profile(bits: &[u8]) -> Vec<usize> {
+    let mut profile = Vec::with_capacity(bits.len());
+    berlekamp_massey(bits, |l| profile.push(l));
+    profile
+}
+
+/// The connection polynomial 1 + c_1 x + ... + c_L x^L over GF(2) of the shortest LFSR that generates a sequence of bits, so that s_i = c_1 s_(i-1) + ... + c_L s_(i-L). The coefficients are given starting with the constant term and there are always L + 1 of them. Only the lowest bit of each value is used.
+pub fn connection_polynomial(bits: &[u8]) -> Vec<u8> {
+    let (l, c) = berlekamp_massey(bits, |_| {});
+    (0..=l).map(|i| coefficient(&c, i) as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Number, rngs::Lcg, rueppel::Rueppel, thue_morse::ThueMorse};
+    use num::One;
+
+    #[test]
+    fn recurrences() {
+        // s_i = s_(i-1) + s_(i-4)
+        let mut s = vec![1, 0, 0, 0];
+        for i in 4..30 {
+            s.push(s[i - 1] ^ s[i - 4]);
+        }
+        assert_eq!(connection_polynomial(&s), vec![1, 1, 0, 0, 1]);
+        assert_eq!(linear_complexity(&s), 4);
+        assert_eq!(connection_polynomial(&[0, 0, 0]), vec![1]);
+        assert_eq!(connection_polynomial(&[0, 0, 1]), vec![1, 0, 0, 1]);
+        assert_eq!(linear_complexity_profile(&[0, 0, 1, 1]), vec![0, 0, 3, 3]);
+    }
+
+    #[test]
+    fn rueppel_profile() {
+        // The Rueppel sequence has the ideal profile, following n/2 as closely as possible
+        let bits: Vec<u8> = Rueppel::new_big()
+            .take(300)
+            .map(|b| b.is_one() as u8)
+            .collect();
+        let profile = linear_complexity_profile(&bits);
+        for (n, l) in profile.iter().enumerate() {
+            assert_eq!(*l, (n + 2) / 2);
+        }
+    }
+
+    #[test]
+    fn thue_morse_is_not_linear() {
+        // Thue-Morse is not eventually periodic so its linear complexity never stops growing, and is exactly half the length of prefixes of length 2^k for k > 1
+        let bits: Vec<u8> = ThueMorse::<Number>::new()
+            .take(1024)
+            .map(|b| b as u8)
+            .collect();
+        let profile = linear_complexity_profile(&bits);
+        for k in 2..=10 {
+            assert_eq!(profile[(1 << k) - 1], 1 << (k - 1));
+        }
+    }
+
+    #[test]
+    fn lcg_low_bits() {
+        // Bit k of an LCG with a power of two modulus has period 2^(k+1) and is generated by an LFSR of length 2^k + 1
+        let values: Vec<u64> = Lcg::new(12345, 1103515245, 12345, 1 << 31)
+            .take(1000)
+            .collect();
+        for k in 0..6 {
+            let bits: Vec<u8> = values.iter().map(|v| (v >> k) as u8 & 1).collect();
+            assert_eq!(linear_complexity(&bits), (1 << k) + 1);
+        }
+        // The high bits look far more random
+        let bits: Vec<u8> = values.iter().map(|v| (v >> 30) as u8 & 1).collect();
+        assert!(linear_complexity(&bits) > 400);
+    }
+}
@@ -1,3 +1,9 @@
+use crate::rngs::{
+    Advance, Rng, SplitMix64,
+    gf2::{coefficient, degree, minimal_polynomial, x_pow_mod},
+};
+use std::sync::OnceLock;
+
 const N32: usize = 624;
 const M32: usize = 397;
 const A32: u32 = 0x9908b0df;
@@ -16,6 +22,7 @@ const LOWER_MASK64: u64 = 0x000000007fffffff;
 /// seed = 5489
 /// 3499211612, 581869302, 3890346734, 3586334585, 545404204...
 /// ```
+#[derive(Clone)]
 pub struct Mt19937 {
     index: usize,
     array: [u32; N32],
@@ -67,6 +74,15 @@ impl Mt19937 {
         rng
     }
 
+    /// Initialize from a 64-bit seed by passing a key of 624 words, the size of the state, from SplitMix64 to from_array. Unlike new this lets every seed reach a well mixed state.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        let key: Vec<u32> = SplitMix64::new(seed)
+            .take(N32 / 2)
+            .flat_map(|x| [x as u32, (x >> 32) as u32])
+            .collect();
+        Self::from_array(&key)
+    }
+
     fn twist(&mut self) {
         for i in 0..N32 - M32 {
             let x = (self.array[i] & UPPER_MASK32) | (self.array[i + 1] & LOWER_MASK32);
@@ -80,6 +96,27 @@ impl Mt19937 {
         self.array[N32 - 1] = self.array[M32 - 1] ^ (x >> 1) ^ ((x & 1).wrapping_mul(A32));
     }
 
+    /// Produce one new word of the state held in a circular buffer, where p is the position of the oldest word. This is the same recurrence that twist applies to the whole array.
+    fn step_circular(buffer: &mut [u32; N32], p: &mut usize) {
+        let x = (buffer[*p] & UPPER_MASK32) | (buffer[(*p + 1) % N32] & LOWER_MASK32);
+        buffer[*p] = buffer[(*p + M32) % N32] ^ (x >> 1) ^ ((x & 1).wrapping_mul(A32));
+        *p = (*p + 1) % N32;
+    }
+
+    /// The characteristic polynomial of the state transition, which has degree 19937. It is found once from the output of the generator by the Berlekamp-Massey algorithm.
+    fn characteristic_polynomial() -> &'static [u64] {
+        static POLYNOMIAL: OnceLock<Vec<u64>> = OnceLock::new();
+        POLYNOMIAL.get_or_init(|| {
+            let mut rng = Self::new(5489);
+            let bits: Vec<u8> = (0..2 * 19937)
+                .map(|_| (rng.next().unwrap() & 1) as u8)
+                .collect();
+            let p = minimal_polynomial(&bits);
+            debug_assert_eq!(degree(&p), Some(19937));
+            p
+        })
+    }
+
     fn temper(mut x: u32) -> u32 {
         x ^= x >> 11;
         x ^= (x << 7) & 0x9d2c5680;
@@ -103,6 +140,44 @@ impl Iterator for Mt19937 {
     }
 }
 
+impl Advance for Mt19937 {
+    /// A step is one output. The state after n steps is g(T) applied to the current state, where T is the state transition and g is x^n reduced modulo the characteristic polynomial of T, so the cost only grows with the logarithm of n.
+    fn advance(&mut self, n: u64) {
+        // Outputs that have already been computed can be skipped directly
+        let buffered = (N32 - self.index) as u64;
+        if n <= buffered {
+            self.index += n as usize;
+            return;
+        }
+        // The array now holds the N32 words preceding the next output, oldest first
+        let n = n - buffered;
+        let g = x_pow_mod(n, Self::characteristic_polynomial());
+        let state = self.array;
+        let mut acc = [0_u32; N32];
+        let mut p = 0;
+        // Horner's method
+        for i in (0..=degree(&g).unwrap_or(0)).rev() {
+            Self::step_circular(&mut acc, &mut p);
+            if coefficient(&g, i) {
+                let (front, back) = acc.split_at_mut(p);
+                for (a, s) in back.iter_mut().chain(front.iter_mut()).zip(state.iter()) {
+                    *a ^= s;
+                }
+            }
+        }
+        for (i, a) in acc[p..].iter().chain(acc[..p].iter()).enumerate() {
+            self.array[i] = *a;
+        }
+        self.index = N32;
+    }
+}
+
+impl Rng for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        self.next().unwrap()
+    }
+}
+
 /// The 64-bit version of the Mersenne Twister.
 ///
 /// ```text
@@ -198,8 +273,20 @@ impl Iterator for Mt19937_64 {
     }
 }
 
+impl Rng for Mt19937_64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
 crate::check_sequences!(
     Mt19937::new(5489), [3499211612_u32, 581869302, 3890346734, 3586334585, 545404204, 4161255391, 3922919429, 949333985, 2715962298, 1323567403, 418932835, 2350294565, 1196140740, 809094426, 2348838239, 4264392720, 4112460519, 4279768804, 4144164697, 4156218106, 676943009, 3117454609];
+    Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]), [1067595299_u32, 955945823, 477289528, 4107218783, 4228976476];
+    Mt19937::seed_from_u64(5489), [2955005156_u32, 2854085531, 1135070955, 2819056715, 1932532059];
     Mt19937_64::new(5489), [14514284786278117030_u64, 4620546740167642908, 13109570281517897720, 17462938647148434322, 355488278567739596, 7469126240319926998, 4635995468481642529, 418970542659199878, 9604170989252516556, 6358044926049913402, 5058016125798318033, 10349215569089701407];
 );
 
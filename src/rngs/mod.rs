@@ -1,22 +1,44 @@
 pub mod blum_blum_shub;
+pub mod chacha;
+pub mod distributions;
+pub(crate) mod gf2;
+pub mod gf2_polynomial;
+pub mod jsf;
 pub mod lcg;
 pub mod lfg;
 pub mod lfsr;
+pub mod linear_complexity;
 pub mod mersenne_twister;
 pub mod pcg;
 pub mod rc4;
+pub mod rng;
+pub mod sfc;
+pub mod splitmix;
+pub mod statistical_tests;
+pub mod wyrand;
 pub mod xoroshiro;
 pub mod xorshift;
+pub mod xoshiro;
 
 pub use blum_blum_shub::*;
+pub use chacha::*;
+pub use distributions::*;
+pub use gf2_polynomial::*;
+pub use jsf::*;
 pub use lcg::*;
 pub use lfg::*;
 pub use lfsr::*;
+pub use linear_complexity::*;
 pub use mersenne_twister::*;
 pub use pcg::*;
 pub use rc4::*;
+pub use rng::*;
+pub use sfc::*;
+pub use splitmix::*;
+pub use wyrand::*;
 pub use xoroshiro::*;
 pub use xorshift::*;
+pub use xoshiro::*;
 
 pub type UNumber = u64;
 pub const UMAX: UNumber = UNumber::MAX;
@@ -1,16 +1,74 @@
+use crate::rngs::{Advance, Rng};
+
+const MULTIPLIER_64: u64 = 6364136223846793005;
+const MULTIPLIER_128: u128 = 0x2360ed051fc65da44385df649fccf645;
+/// The multiplier used by the DXSM variant for both its state update and output function.
+const CHEAP_MULTIPLIER_128: u64 = 0xda942042e4dd58b5;
+
+/// Seeding from the reference implementation. The seed is added to the state between two steps so that nearby seeds do not give nearby states.
+fn seed_state_64(seed: u64, multiplier: u64, increment: u64) -> u64 {
+    increment
+        .wrapping_add(seed)
+        .wrapping_mul(multiplier)
+        .wrapping_add(increment)
+}
+
+fn seed_state_128(seed: u128, multiplier: u128, increment: u128) -> u128 {
+    increment
+        .wrapping_add(seed)
+        .wrapping_mul(multiplier)
+        .wrapping_add(increment)
+}
+
+/// Brown's method for jumping an LCG modulo 2^64 ahead by n steps.
+fn advance_lcg_64(state: u64, mut mul: u64, mut add: u64, mut n: u64) -> u64 {
+    let (mut acc_mul, mut acc_add) = (1_u64, 0_u64);
+    while n > 0 {
+        if n & 1 == 1 {
+            acc_mul = acc_mul.wrapping_mul(mul);
+            acc_add = acc_add.wrapping_mul(mul).wrapping_add(add);
+        }
+        add = mul.wrapping_add(1).wrapping_mul(add);
+        mul = mul.wrapping_mul(mul);
+        n >>= 1;
+    }
+    acc_mul.wrapping_mul(state).wrapping_add(acc_add)
+}
+
+/// Brown's method for jumping an LCG modulo 2^128 ahead by n steps.
+fn advance_lcg_128(state: u128, mut mul: u128, mut add: u128, mut n: u128) -> u128 {
+    let (mut acc_mul, mut acc_add) = (1_u128, 0_u128);
+    while n > 0 {
+        if n & 1 == 1 {
+            acc_mul = acc_mul.wrapping_mul(mul);
+            acc_add = acc_add.wrapping_mul(mul).wrapping_add(add);
+        }
+        add = mul.wrapping_add(1).wrapping_mul(add);
+        mul = mul.wrapping_mul(mul);
+        n >>= 1;
+    }
+    acc_mul.wrapping_mul(state).wrapping_add(acc_add)
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PcgTransform {
     // Xorshift with Random Rotation
     XshRr,
     // Xorshift with Random Shift
     XshRs,
+    // Xorshift Low with Random Rotation
+    XslRr,
 }
 
 impl PcgTransform {
     pub fn apply(&self, n: u64) -> u64 {
         match self {
-            Self::XshRr => u64::rotate_right((n ^ (n >> 18)) >> 27, (n >> 59) as u32),
+            // The rotation acts on the 32-bit output, not the full state
+            Self::XshRr => {
+                u32::rotate_right(((n ^ (n >> 18)) >> 27) as u32, (n >> 59) as u32) as u64
+            }
             Self::XshRs => (n ^ (n >> 22)) >> (22 + (n >> 61)),
+            Self::XslRr => u32::rotate_right(((n >> 32) ^ n) as u32, (n >> 59) as u32) as u64,
         }
     }
 }
@@ -20,12 +78,17 @@ impl PcgTransform {
 /// ```text
 /// Xorshift with Random Rotation (recommended settings)
 /// seed = 0, multiplier = 6364136223846793005, increment = 1442695040888963407
-/// 0, 2687235069, 1747165774, 158681, 13462534, 12872360, 588101...
+/// 0, 1613493245, 3894649422, 2055130073, 2315086854, 2925816488, 3443325253...
 ///
 /// Xorshift with Random Shift (recommended settings)
 /// seed = 0, multiplier = 6364136223846793005, increment = 1442695040888963407
 /// 0, 367836042, 599385756, 3181286013, 1527626195, 447129947...
+///
+/// Xorshift Low with Random Rotation (recommended settings)
+/// seed = 0, multiplier = 6364136223846793005, increment = 1442695040888963407
+/// 0, 2027470476, 1943425124, 2670163600, 902928744, 3532652857, 1150824354...
 /// ```
+#[derive(Clone)]
 pub struct Pcg64_32 {
     state: u64,
     multiplier: u64,
@@ -53,6 +116,31 @@ impl Pcg64_32 {
             transform: PcgTransform::XshRs,
         }
     }
+
+    /// Output permutation is xorshift low with random rotation.
+    pub fn new_xsl_rr(seed: u64, multiplier: u64, increment: u64) -> Self {
+        Self {
+            state: seed,
+            multiplier,
+            increment,
+            transform: PcgTransform::XslRr,
+        }
+    }
+
+    /// The standard pcg32 generator, seeded as pcg32_srandom_r in the reference implementation. Each value of stream selects a different sequence.
+    pub fn pcg32(seed: u64, stream: u64) -> Self {
+        let increment = (stream << 1) | 1;
+        Self::new_xsh_rr(
+            seed_state_64(seed, MULTIPLIER_64, increment),
+            MULTIPLIER_64,
+            increment,
+        )
+    }
+
+    /// The stream selected by the increment.
+    pub fn stream(&self) -> u64 {
+        self.increment >> 1
+    }
 }
 
 impl Iterator for Pcg64_32 {
@@ -68,7 +156,348 @@ impl Iterator for Pcg64_32 {
     }
 }
 
+impl Advance for Pcg64_32 {
+    /// A step is one output. Uses Brown's method of composing the underlying LCG with itself.
+    fn advance(&mut self, n: u64) {
+        self.state = advance_lcg_64(self.state, self.multiplier, self.increment, n);
+    }
+}
+
+impl Rng for Pcg64_32 {
+    fn next_u32(&mut self) -> u32 {
+        self.next().unwrap()
+    }
+}
+
+/// A Permuted Congruential Generator with a 64-bit state and a 64-bit output, using the random xorshift, multiply, xorshift output permutation. The output is a bijection of the state so every 64-bit value appears exactly once per period.
+///
+/// ```text
+/// seed = 0, multiplier = 6364136223846793005, increment = 1442695040888963407
+/// 0, 7804475297823432871, 5248727431820822368, 8252840206086834016...
+/// ```
+#[derive(Clone)]
+pub struct Pcg64_64 {
+    state: u64,
+    multiplier: u64,
+    increment: u64,
+}
+
+impl Pcg64_64 {
+    /// Output permutation is random xorshift, multiply, xorshift.
+    pub fn new_rxs_m_xs(seed: u64, multiplier: u64, increment: u64) -> Self {
+        Self {
+            state: seed,
+            multiplier,
+            increment,
+        }
+    }
+
+    /// Seeded like the 64-bit setseq generators of the reference implementation, as in pcg32_srandom_r: the increment is 2 * stream + 1 and the seed is added to the state after one step from zero. Each value of stream selects a different sequence.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let increment = (stream << 1) | 1;
+        Self::new_rxs_m_xs(
+            seed_state_64(seed, MULTIPLIER_64, increment),
+            MULTIPLIER_64,
+            increment,
+        )
+    }
+
+    /// The stream selected by the increment.
+    pub fn stream(&self) -> u64 {
+        self.increment >> 1
+    }
+
+    fn output(n: u64) -> u64 {
+        let word = ((n >> ((n >> 59) + 5)) ^ n).wrapping_mul(12605985483714917081);
+        (word >> 43) ^ word
+    }
+}
+
+impl Iterator for Pcg64_64 {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.state;
+        self.state = self
+            .state
+            .wrapping_mul(self.multiplier)
+            .wrapping_add(self.increment);
+        Some(Self::output(x))
+    }
+}
+
+impl Advance for Pcg64_64 {
+    /// A step is one output.
+    fn advance(&mut self, n: u64) {
+        self.state = advance_lcg_64(self.state, self.multiplier, self.increment, n);
+    }
+}
+
+impl Rng for Pcg64_64 {
+    /// The upper half of the output.
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Pcg128Transform {
+    // Xorshift Low with Random Rotation
+    XslRr,
+    // Double Xorshift Multiply
+    Dxsm,
+}
+
+/// A Permuted Congruential Generator with a 128-bit state and a 64-bit output.
+///
+/// The XSL-RR variant is pcg64 from the reference implementation and outputs from the state after each step. The DXSM variant is the default generator of NumPy, it uses a 64-bit multiplier for the state update and outputs from the state before each step.
+///
+/// ```text
+/// pcg64, seed = 42, stream = 54
+/// 9706119232150784872, 1370959062016458041, 11774925180826649432, 17944010045993266688...
+///
+/// pcg64_dxsm, seed = 42, stream = 54
+/// 17331114245835578256, 10267467544499227306, 9726600296081716989, 10165951391103677450...
+/// ```
+#[derive(Clone)]
+pub struct Pcg128_64 {
+    state: u128,
+    multiplier: u128,
+    increment: u128,
+    transform: Pcg128Transform,
+}
+
+impl Pcg128_64 {
+    /// Output permutation is xorshift low with random rotation.
+    pub fn new_xsl_rr(seed: u128, multiplier: u128, increment: u128) -> Self {
+        Self {
+            state: seed,
+            multiplier,
+            increment,
+            transform: Pcg128Transform::XslRr,
+        }
+    }
+
+    /// Output permutation is double xorshift multiply.
+    pub fn new_dxsm(seed: u128, multiplier: u128, increment: u128) -> Self {
+        Self {
+            state: seed,
+            multiplier,
+            increment,
+            transform: Pcg128Transform::Dxsm,
+        }
+    }
+
+    /// The standard pcg64 generator, seeded as pcg64_srandom_r in the reference implementation. Each value of stream selects a different sequence.
+    pub fn pcg64(seed: u128, stream: u128) -> Self {
+        let increment = (stream << 1) | 1;
+        Self::new_xsl_rr(
+            seed_state_128(seed, MULTIPLIER_128, increment),
+            MULTIPLIER_128,
+            increment,
+        )
+    }
+
+    /// The PCG64DXSM generator from NumPy, seeded as pcg_cm_srandom_r. Each value of stream selects a different sequence.
+    pub fn pcg64_dxsm(seed: u128, stream: u128) -> Self {
+        let increment = (stream << 1) | 1;
+        let multiplier = CHEAP_MULTIPLIER_128 as u128;
+        Self::new_dxsm(
+            seed_state_128(seed, multiplier, increment),
+            multiplier,
+            increment,
+        )
+    }
+
+    /// The PCG64DXSM generator with the state and inc that NumPy reports in bit_generator.state, so the outputs continue those of random_raw.
+    pub fn pcg64_dxsm_from_state(state: u128, inc: u128) -> Self {
+        Self::new_dxsm(state, CHEAP_MULTIPLIER_128 as u128, inc)
+    }
+
+    /// The current state and increment, in the form NumPy reports in bit_generator.state.
+    pub fn state(&self) -> (u128, u128) {
+        (self.state, self.increment)
+    }
+
+    /// The stream selected by the increment.
+    pub fn stream(&self) -> u128 {
+        self.increment >> 1
+    }
+
+    /// Jump forward by any number of steps, including those beyond the range of Advance.
+    pub fn advance_u128(&mut self, n: u128) {
+        self.state = advance_lcg_128(self.state, self.multiplier, self.increment, n);
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(self.multiplier)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl Iterator for Pcg128_64 {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.transform {
+            Pcg128Transform::XslRr => {
+                self.step();
+                let n = self.state;
+                Some(u64::rotate_right(((n >> 64) ^ n) as u64, (n >> 122) as u32))
+            }
+            Pcg128Transform::Dxsm => {
+                let mut hi = (self.state >> 64) as u64;
+                let lo = self.state as u64 | 1;
+                hi ^= hi >> 32;
+                hi = hi.wrapping_mul(CHEAP_MULTIPLIER_128);
+                hi ^= hi >> 48;
+                hi = hi.wrapping_mul(lo);
+                self.step();
+                Some(hi)
+            }
+        }
+    }
+}
+
+impl Advance for Pcg128_64 {
+    /// A step is one output.
+    fn advance(&mut self, n: u64) {
+        self.advance_u128(n as u128);
+    }
+}
+
+impl Rng for Pcg128_64 {
+    /// The upper half of the output.
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
 crate::sample_sequences!(
     Pcg64_32::new_xsh_rr(0, 6364136223846793005, 1442695040888963407);
     Pcg64_32::new_xsh_rs(0, 6364136223846793005, 1442695040888963407);
+    Pcg64_32::new_xsl_rr(0, 6364136223846793005, 1442695040888963407);
+    Pcg64_64::new_rxs_m_xs(0, 6364136223846793005, 1442695040888963407);
+    Pcg128_64::pcg64(42, 54);
+    Pcg128_64::pcg64_dxsm(42, 54);
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_pcg32() {
+        // pcg32_srandom_r(&rng, 42, 54) from the PCG reference implementation
+        let increment = (54 << 1) | 1;
+        let multiplier = 6364136223846793005;
+        let state = 42_u64
+            .wrapping_add(increment)
+            .wrapping_mul(multiplier)
+            .wrapping_add(increment);
+        let mut rng = Pcg64_32::new_xsh_rr(state, multiplier, increment);
+        assert_eq!(
+            rng.by_ref().take(6).collect::<Vec<u32>>(),
+            [
+                0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e
+            ]
+        );
+    }
+
+    #[test]
+    fn reference_streams() {
+        let mut rng = Pcg64_32::pcg32(42, 54);
+        assert_eq!(rng.stream(), 54);
+        assert_eq!(rng.next(), Some(0xa15c02b7));
+
+        // pcg64_srandom_r(&rng, 42, 54) with 128-bit state
+        let mut rng = Pcg128_64::pcg64(42, 54);
+        assert_eq!(
+            rng.by_ref().take(6).collect::<Vec<u64>>(),
+            [
+                0x86b1da1d72062b68,
+                0x1304aa46c9853d39,
+                0xa3670e9e0dd50358,
+                0xf9090e529a7dae00,
+                0xc85b9fd837996f2c,
+                0x606121f8e3919196
+            ]
+        );
+
+        // cm_setseq_dxsm_128_64 from the C++ reference implementation, which NumPy ports as PCG64DXSM
+        let rng = Pcg128_64::pcg64_dxsm(42, 54);
+        assert_eq!(
+            rng.take(6).collect::<Vec<u64>>(),
+            [
+                0xf0847c9518bddb90,
+                0x8e7d5f5514ba8aaa,
+                0x86fbd36f8028f6fd,
+                0x8d14b6edbe9f740a,
+                0xa85b2896c7cad55d,
+                0x8ca3894a1d9227bb
+            ]
+        );
+
+        let rng = Pcg64_64::with_stream(42, 54);
+        assert_eq!(
+            rng.take(3).collect::<Vec<u64>>(),
+            [0xe1cbc180b69606bb, 0x6573bce7abaee684, 0xc744f07442006076]
+        );
+
+        let increment = (54 << 1) | 1;
+        let state = seed_state_64(42, MULTIPLIER_64, increment);
+        let rng = Pcg64_32::new_xsl_rr(state, MULTIPLIER_64, increment);
+        assert_eq!(
+            rng.take(3).collect::<Vec<u32>>(),
+            [0x068f20a8, 0xed610a2e, 0x3911c946]
+        );
+    }
+
+    #[test]
+    fn dxsm_from_state() {
+        let mut rng = Pcg128_64::pcg64_dxsm(42, 54);
+        rng.advance(3);
+        let (state, inc) = rng.state();
+        assert_eq!(inc, (54 << 1) | 1);
+        let resumed = Pcg128_64::pcg64_dxsm_from_state(state, inc);
+        assert_eq!(
+            resumed.take(3).collect::<Vec<u64>>(),
+            [0x8d14b6edbe9f740a, 0xa85b2896c7cad55d, 0x8ca3894a1d9227bb]
+        );
+    }
+
+    #[test]
+    fn distinct_streams() {
+        let a: Vec<u64> = Pcg128_64::pcg64_dxsm(1, 0).take(10).collect();
+        let b: Vec<u64> = Pcg128_64::pcg64_dxsm(1, 1).take(10).collect();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn advance_128() {
+        for mut rng in [Pcg128_64::pcg64(42, 54), Pcg128_64::pcg64_dxsm(42, 54)] {
+            let mut jumped = rng.clone();
+            jumped.advance(1000);
+            for _ in 0..1000 {
+                rng.next();
+            }
+            assert_eq!(jumped.next(), rng.next());
+
+            // The period is 2^128
+            let start = rng.clone().next();
+            rng.advance_u128(u128::MAX);
+            rng.next();
+            assert_eq!(rng.next(), start);
+        }
+    }
+}
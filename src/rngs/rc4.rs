@@ -1,3 +1,5 @@
+use crate::rngs::Rng;
+
 /// Rivest Cipher 4 (RC4)
 ///
 /// ```text
@@ -41,6 +43,23 @@ impl Iterator for Rc4 {
     }
 }
 
+impl Rng for Rc4 {
+    /// Four bytes of the keystream in little-endian order.
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        for b in bytes.iter_mut() {
+            *b = self.next().unwrap();
+        }
+        u32::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for b in dest.iter_mut() {
+            *b = self.next().unwrap();
+        }
+    }
+}
+
 crate::sample_sequences!(
     Rc4::new(&[0]);
 );
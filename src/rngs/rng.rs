@@ -0,0 +1,391 @@
+/// A common interface for the generators in this module. Only next_u32 needs to be implemented, everything else is derived from it, though generators with 64-bit output should also implement next_u64.
+pub trait Rng {
+    /// 32 random bits.
+    fn next_u32(&mut self) -> u32;
+
+    /// 64 random bits. By default this combines two calls to next_u32 with the first call providing the upper half.
+    fn next_u64(&mut self) -> u64 {
+        let hi = self.next_u32() as u64;
+        let lo = self.next_u32() as u64;
+        (hi << 32) | lo
+    }
+
+    /// Fill a slice with random bytes, taking four bytes at a time from next_u32 in little-endian order.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Uniformly random integer in the range 0..bound using Lemire's nearly divisionless method.
+    /// Panics if bound is zero.
+    fn bounded_u32(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "bound must be positive");
+        let mut m = self.next_u32() as u64 * bound as u64;
+        if (m as u32) < bound {
+            // Values of the low half below this threshold would bias the result
+            let threshold = bound.wrapping_neg() % bound;
+            while (m as u32) < threshold {
+                m = self.next_u32() as u64 * bound as u64;
+            }
+        }
+        (m >> 32) as u32
+    }
+
+    /// Uniformly random integer in the range 0..bound using Lemire's nearly divisionless method.
+    /// Panics if bound is zero.
+    fn bounded_u64(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+        let mut m = self.next_u64() as u128 * bound as u128;
+        if (m as u64) < bound {
+            // Values of the low half below this threshold would bias the result
+            let threshold = bound.wrapping_neg() % bound;
+            while (m as u64) < threshold {
+                m = self.next_u64() as u128 * bound as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+
+    /// Uniformly random integer in the range low..high.
+    /// Panics if the range is empty.
+    fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low < high, "range must not be empty");
+        let width = high.abs_diff(low);
+        low.wrapping_add(self.bounded_u64(width) as i64)
+    }
+
+    /// Uniformly random f64 in the interval [0, 1). Uses the upper 53 bits of next_u64 so every possible value is a multiple of 2^-53.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1_u64 << 53) as f64)
+    }
+
+    /// Uniformly random f64 in the interval [low, high).
+    fn uniform_f64(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// True with probability p.
+    fn bernoulli(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Shuffle a slice in place with the Fisher-Yates algorithm. Every permutation is equally likely.
+    fn shuffle<T>(&mut self, slice: &mut [T])
+    where
+        Self: Sized,
+    {
+        for i in (1..slice.len()).rev() {
+            let j = self.bounded_u64(i as u64 + 1) as usize;
+            slice.swap(i, j);
+        }
+    }
+
+    /// A uniformly random element of a slice. Returns None if the slice is empty.
+    fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T>
+    where
+        Self: Sized,
+    {
+        if slice.is_empty() {
+            None
+        } else {
+            Some(&slice[self.bounded_u64(slice.len() as u64) as usize])
+        }
+    }
+
+    /// Choose k elements of a slice without replacement, in random order. Uses a partial Fisher-Yates shuffle of the indices.
+    /// Panics if k is greater than the length of the slice.
+    fn sample<T: Clone>(&mut self, slice: &[T], k: usize) -> Vec<T>
+    where
+        Self: Sized,
+    {
+        assert!(k <= slice.len(), "cannot sample more elements than exist");
+        let mut indices: Vec<usize> = (0..slice.len()).collect();
+        for i in 0..k {
+            let j = i + self.bounded_u64((slice.len() - i) as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices[..k].iter().map(|i| slice[*i].clone()).collect()
+    }
+}
+
+/// Generators that can move far ahead in their sequence without producing every value along the way. This allows a single seed to provide reproducible, non-overlapping streams for parallel workers.
+pub trait Advance {
+    /// Move the generator forward as if n steps had been taken. Each generator describes what counts as a step.
+    fn advance(&mut self, n: u64);
+
+    /// The given number of copies of the generator, each one stride steps ahead of the one before and the first identical to self. No two streams overlap as long as each takes at most stride steps.
+    fn substreams(&self, count: usize, stride: u64) -> Vec<Self>
+    where
+        Self: Clone + Sized,
+    {
+        let mut streams = Vec::with_capacity(count);
+        let mut current = self.clone();
+        for i in 0..count {
+            if i > 0 {
+                current.advance(stride);
+            }
+            streams.push(current.clone());
+        }
+        streams
+    }
+}
+
+/// Number of uniformly random bits available from a generator that produces values in 0..m.
+pub(crate) fn modulus_bits(m: u64) -> u32 {
+    63 - m.leading_zeros()
+}
+
+// Consecutive rejections after which the source is taken to never produce a value below 2^k. A uniform source is rejected less than half the time so this is never reached by chance.
+const MAX_REJECTIONS: usize = 1000;
+
+/// Take 32 random bits from a source of values that are uniform in 0..m. Values of at least 2^k, where k is the largest number of bits that fit below m, are rejected so the bits that remain are uniform. Values are concatenated with the earliest as the most significant and any excess low bits are dropped.
+///
+/// Panics if the source produces too many consecutive values of at least 2^k, as a degenerate generator stuck in such values would otherwise never return.
+pub(crate) fn u32_from_modulus<F: FnMut() -> u64>(m: u64, mut source: F) -> u32 {
+    let k = modulus_bits(m);
+    assert!(k > 0, "the modulus must be at least 2");
+    let mut acc: u128 = 0;
+    let mut bits = 0;
+    let mut rejections = 0;
+    while bits < 32 {
+        let v = source();
+        if v >> k != 0 {
+            rejections += 1;
+            assert!(
+                rejections < MAX_REJECTIONS,
+                "the generator produced {MAX_REJECTIONS} consecutive values of at least 2^{k} and is not uniform in 0..{m}"
+            );
+            continue;
+        }
+        rejections = 0;
+        acc = (acc << k) | v as u128;
+        bits += k;
+    }
+    (acc >> (bits - 32)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::{
+        ChaCha, Jsf32, Jsf64, Lcg, Lfg, LfgMult, Lfsr, Mt19937, Mt19937_64, Pcg64_32, Pcg64_64,
+        Pcg128_64, Rc4, Sfc64, SplitMix64, WyRand, Xoroshiro128, Xorshift64, XorshiftRule,
+        Xoshiro256, xorshift::Scrambler,
+    };
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    fn all_generators() -> Vec<Box<dyn Rng>> {
+        vec![
+            Box::new(Lcg::new(1, 65539, 0, 1 << 31)),
+            Box::new(Lcg::new(47594118, 23, 0, 100000001)),
+            Box::new(Lfg::new(123, 456, 789)),
+            Box::new(LfgMult::new(123, 456, 789)),
+            Box::new(Lfsr::new_fibonacci(1, 0b1001000000)),
+            Box::new(Lfsr::new_galois(1, 0b1001000000)),
+            Box::new(Mt19937::new(5489)),
+            Box::new(Mt19937_64::new(5489)),
+            Box::new(Pcg64_32::new_xsh_rr(
+                0,
+                6364136223846793005,
+                1442695040888963407,
+            )),
+            Box::new(Pcg64_64::with_stream(42, 54)),
+            Box::new(Pcg128_64::pcg64(42, 54)),
+            Box::new(Pcg128_64::pcg64_dxsm(42, 54)),
+            Box::new(Xorshift64::new(
+                1,
+                (13, 7, 17),
+                XorshiftRule::A0,
+                Scrambler::Star32,
+            )),
+            Box::new(Xoroshiro128::new_plus_plus([1, 2])),
+            Box::new(Rc4::new(&[1, 2, 3])),
+            Box::new(SplitMix64::new(0)),
+            Box::new(Xoshiro256::new_star_star([1, 2, 3, 4])),
+            Box::new(WyRand::new(0)),
+            Box::new(Sfc64::new(0)),
+            Box::new(Jsf32::new(0)),
+            Box::new(Jsf64::new(0)),
+            Box::new(ChaCha::new_chacha8([0; 32], 0)),
+        ]
+    }
+
+    #[test]
+    fn bounded_values() {
+        for mut rng in all_generators() {
+            let mut counts = [0; 7];
+            for _ in 0..7000 {
+                let x = rng.bounded_u32(7);
+                counts[x as usize] += 1;
+                assert!(rng.bounded_u64(1_000_000_000_000) < 1_000_000_000_000);
+                let y = rng.range_i64(-5, 5);
+                assert!((-5..5).contains(&y));
+                let f = rng.next_f64();
+                assert!((0.0..1.0).contains(&f));
+            }
+            // Every residue should appear, even for poor generators
+            assert!(counts.iter().all(|c| *c > 0));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "consecutive values")]
+    fn stuck_generator_panics() {
+        // Stays at 5, which is never below 2^2
+        Lcg::new(5, 1, 0, 6).next_u32();
+    }
+
+    #[test]
+    fn bytes_agree_with_words() {
+        let mut a = Mt19937::new(5489);
+        let mut b = Mt19937::new(5489);
+        let mut bytes = [0; 10];
+        a.fill_bytes(&mut bytes);
+        let w0 = Rng::next_u32(&mut b).to_le_bytes();
+        let w1 = Rng::next_u32(&mut b).to_le_bytes();
+        let w2 = Rng::next_u32(&mut b).to_le_bytes();
+        assert_eq!(bytes[0..4], w0);
+        assert_eq!(bytes[4..8], w1);
+        assert_eq!(bytes[8..10], w2[0..2]);
+    }
+
+    #[test]
+    fn native_output() {
+        // Generators with 32-bit output give the same values through the trait
+        let mut a = Mt19937::new(5489);
+        assert_eq!(Rng::next_u32(&mut a), 3499211612);
+        let mut a = Mt19937_64::new(5489);
+        assert_eq!(Rng::next_u64(&mut a), 14514284786278117030);
+        // RANDU produces 31 bits at a time so only the highest bit of the second output is used
+        let mut a = Lcg::new(1, 65539, 0, 1 << 31);
+        assert_eq!(Rng::next_u32(&mut a), (1 << 1) | (65539 >> 30));
+        assert_eq!(
+            Rng::next_u32(&mut a),
+            ((393225 << 1) | (1769499 >> 30)) as u32
+        );
+    }
+
+    #[test]
+    fn shuffle_and_sample() {
+        let mut rng = Mt19937::new(42);
+        let mut v: Vec<u32> = (0..100).collect();
+        rng.shuffle(&mut v);
+        assert_ne!(v, (0..100).collect::<Vec<u32>>());
+        v.sort();
+        assert_eq!(v, (0..100).collect::<Vec<u32>>());
+
+        let s = rng.sample(&v, 10);
+        assert_eq!(s.len(), 10);
+        let mut t = s.clone();
+        t.sort();
+        t.dedup();
+        assert_eq!(t.len(), 10);
+        assert!(rng.choose(&v).is_some());
+        assert!(rng.choose::<u32>(&[]).is_none());
+
+        // All six permutations of three elements occur about equally often
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..60000 {
+            let mut p = [0, 1, 2];
+            rng.shuffle(&mut p);
+            *counts.entry(p).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        assert!(counts.values().all(|c| (9500..10500).contains(c)));
+    }
+
+    fn check_advance<R>(rng: R, n: u64)
+    where
+        R: Iterator + Advance + Clone,
+        R::Item: PartialEq + std::fmt::Debug,
+    {
+        let mut jumped = rng.clone();
+        jumped.advance(n);
+        let mut stepped = rng;
+        for _ in 0..n {
+            stepped.next();
+        }
+        for _ in 0..10 {
+            assert_eq!(jumped.next(), stepped.next());
+        }
+    }
+
+    #[test]
+    fn advance_agrees_with_stepping() {
+        for n in [0, 1, 2, 623, 624, 625, 1000, 5000] {
+            check_advance(Lcg::new(1, 65539, 0, 1 << 31), n);
+            check_advance(Lcg::new(47594118, 23, 7, 100000001), n);
+            check_advance(Lfsr::new_fibonacci(1, 0b1001000000), n);
+            check_advance(Lfsr::new_galois(1, 0b1001000000), n);
+            check_advance(
+                Pcg64_32::new_xsh_rr(0, 6364136223846793005, 1442695040888963407),
+                n,
+            );
+            check_advance(Pcg64_32::pcg32(42, 54), n);
+            check_advance(Pcg64_64::with_stream(42, 54), n);
+            check_advance(Pcg128_64::pcg64(42, 54), n);
+            check_advance(Pcg128_64::pcg64_dxsm(42, 54), n);
+            check_advance(
+                Xorshift64::new(1, (13, 7, 17), XorshiftRule::A0, Scrambler::Star32),
+                n,
+            );
+            check_advance(Xoroshiro128::new_plus_plus([1, 2]), n);
+            check_advance(Xoroshiro128::new_star_star([1, 2]), n);
+            check_advance(SplitMix64::new(0), n);
+            check_advance(WyRand::new(0), n);
+            check_advance(ChaCha::new_chacha20([0; 32], 0), n);
+        }
+        // The Mersenne Twister can be advanced from partway through its array
+        let mut rng = Mt19937::new(5489);
+        check_advance(rng.clone(), 100_000);
+        for _ in 0..100 {
+            rng.next();
+        }
+        check_advance(rng.clone(), 600);
+        check_advance(rng, 10_000);
+    }
+
+    #[test]
+    fn advance_is_additive() {
+        let mut a = Mt19937::new(1);
+        a.advance(1 << 40);
+        a.advance(12345);
+        let mut b = Mt19937::new(1);
+        b.advance((1 << 40) + 12345);
+        assert!(a.take(5).eq(b.take(5)));
+
+        // An LCG returns to its starting point after a full period
+        let mut rng = Lcg::new(1, 65539, 0, 1 << 31);
+        rng.advance(1 << 29);
+        assert_eq!(rng.next(), Some(1));
+    }
+
+    #[test]
+    fn parallel_substreams() {
+        let stride = 1000;
+        let rng = Pcg64_32::new_xsh_rr(42, 6364136223846793005, 1442695040888963407);
+        let sums: Vec<u64> = rng
+            .substreams(8, stride)
+            .into_par_iter()
+            .map(|mut r| (0..stride).map(|_| r.next_u32() as u64).sum())
+            .collect();
+        let mut sequential = rng.clone();
+        for s in sums {
+            assert_eq!(
+                s,
+                (0..stride)
+                    .map(|_| sequential.next_u32() as u64)
+                    .sum::<u64>()
+            );
+        }
+
+        let rng = Mt19937::new(5489);
+        let streams = rng.substreams(3, 1 << 50);
+        assert!(rng.clone().take(5).eq(streams[0].clone().take(5)));
+        let mut second = rng;
+        second.advance(1 << 50);
+        assert!(second.take(5).eq(streams[1].clone().take(5)));
+    }
+}
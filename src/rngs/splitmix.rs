@@ -0,0 +1,58 @@
+use crate::rngs::{Advance, Rng};
+
+const GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// SplitMix64, a Weyl sequence passed through a strong mixing function. Recommended by the authors of the xorshift family for expanding a single 64-bit seed into the state of a larger generator.
+///
+/// ```text
+/// seed = 0
+/// 16294208416658607535, 7960286522194355700, 487617019471545679, 17909611376780542444...
+/// ```
+#[derive(Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Iterator for SplitMix64 {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state = self.state.wrapping_add(GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        Some(z ^ (z >> 31))
+    }
+}
+
+impl Advance for SplitMix64 {
+    /// A step is one output. The underlying Weyl sequence is advanced directly.
+    fn advance(&mut self, n: u64) {
+        self.state = self.state.wrapping_add(n.wrapping_mul(GAMMA));
+    }
+}
+
+impl Rng for SplitMix64 {
+    /// The upper half of the output.
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
+crate::check_sequences!(
+    SplitMix64::new(1234567), [6457827717110365317_u64, 3203168211198807973, 9817491932198370423, 4593380528125082431, 16408922859458223821];
+);
+
+crate::sample_sequences!(
+    SplitMix64::new(0);
+);
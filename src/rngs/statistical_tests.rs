@@ -0,0 +1,338 @@
+use crate::{
+    rngs::{Rng, linear_complexity::linear_complexity as linear_complexity_of_bits},
+    utils::special_functions::{chi_square_sf, erfc, kolmogorov_sf},
+};
+use std::fmt::Display;
+
+/// The outcome of a statistical test. Under the hypothesis that the generator is ideal the p-value is uniformly distributed on [0, 1], so very small values are evidence that it is not.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl TestResult {
+    fn new(name: &'static str, statistic: f64, p_value: f64) -> Self {
+        Self {
+            name,
+            statistic,
+            p_value: p_value.clamp(0.0, 1.0),
+        }
+    }
+
+    /// True if the p-value is at least alpha.
+    pub fn passed(&self, alpha: f64) -> bool {
+        self.p_value >= alpha
+    }
+}
+
+impl Display for TestResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<20} statistic = {:<14.4} p = {:.6}",
+            self.name, self.statistic, self.p_value
+        )
+    }
+}
+
+/// Pearson's chi-square statistic for observed counts against expected counts.
+fn chi_square(observed: &[u64], expected: &[f64]) -> f64 {
+    observed
+        .iter()
+        .zip(expected.iter())
+        .map(|(o, e)| (*o as f64 - e).powi(2) / e)
+        .sum()
+}
+
+/// n bits from the generator, taken from each word of next_u32 beginning with the most significant bit.
+fn bits<R: Rng + ?Sized>(rng: &mut R, n: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(n);
+    while out.len() < n {
+        let w = rng.next_u32();
+        for i in (0..32).rev() {
+            if out.len() == n {
+                break;
+            }
+            out.push(((w >> i) & 1) as u8);
+        }
+    }
+    out
+}
+
+/// Chi-square test of counts over equally likely categories.
+pub fn chi_square_uniform(counts: &[u64]) -> TestResult {
+    let total: u64 = counts.iter().sum();
+    let expected = vec![total as f64 / counts.len() as f64; counts.len()];
+    let x = chi_square(counts, &expected);
+    TestResult::new(
+        "chi-square uniform",
+        x,
+        chi_square_sf(x, (counts.len() - 1) as f64),
+    )
+}
+
+/// Kolmogorov-Smirnov test that the values are drawn from the uniform distribution on [0, 1]. Applied to the p-values of repeated tests this checks that they are distributed as they should be.
+/// Panics if there are no values.
+pub fn ks_uniform(values: &[f64]) -> TestResult {
+    assert!(!values.is_empty(), "there must be at least one value");
+    let mut v = values.to_vec();
+    v.sort_by(|a, b| a.total_cmp(b));
+    let n = v.len() as f64;
+    let d = v
+        .iter()
+        .enumerate()
+        .map(|(i, x)| ((i + 1) as f64 / n - x).max(x - i as f64 / n))
+        .fold(0.0, f64::max);
+    // Stephens' correction makes the limiting distribution accurate for small n
+    let s = n.sqrt();
+    TestResult::new(
+        "kolmogorov-smirnov",
+        d,
+        kolmogorov_sf((s + 0.12 + 0.11 / s) * d),
+    )
+}
+
+/// Chi-square test that p-values are uniform, counting them in ten equal bins.
+/// Panics if there are no p-values.
+pub fn chi_square_p_values(p_values: &[f64]) -> TestResult {
+    assert!(!p_values.is_empty(), "there must be at least one p-value");
+    let mut counts = [0; 10];
+    for p in p_values {
+        counts[((p * 10.0) as usize).min(9)] += 1;
+    }
+    chi_square_uniform(&counts)
+}
+
+/// NIST frequency (monobit) test: the proportion of ones among n bits.
+pub fn monobit<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    let sum: i64 = bits(rng, n).iter().map(|b| 2 * *b as i64 - 1).sum();
+    let s = sum.unsigned_abs() as f64 / (n as f64).sqrt();
+    TestResult::new("monobit", s, erfc(s / std::f64::consts::SQRT_2))
+}
+
+/// NIST runs test: the number of uninterrupted runs of identical bits among n bits. If the proportion of ones is too far from one half the test is not applicable and a p-value of zero is reported.
+pub fn runs<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    let b = bits(rng, n);
+    let nf = n as f64;
+    let pi = b.iter().map(|x| *x as f64).sum::<f64>() / nf;
+    if (pi - 0.5).abs() >= 2.0 / nf.sqrt() {
+        return TestResult::new("runs", f64::NAN, 0.0);
+    }
+    let v = 1 + b.windows(2).filter(|w| w[0] != w[1]).count();
+    let v = v as f64;
+    let q = pi * (1.0 - pi);
+    let p = erfc((v - 2.0 * nf * q).abs() / (2.0 * (2.0 * nf).sqrt() * q));
+    TestResult::new("runs", v, p)
+}
+
+/// Knuth's serial test: counts of pairs of successive values, each made from the top six bits of a word, among n pairs.
+pub fn serial<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    let mut counts = vec![0; 64 * 64];
+    for _ in 0..n {
+        let a = rng.next_u32() >> 26;
+        let b = rng.next_u32() >> 26;
+        counts[(a * 64 + b) as usize] += 1;
+    }
+    TestResult {
+        name: "serial",
+        ..chi_square_uniform(&counts)
+    }
+}
+
+/// Knuth's gap test: lengths of the gaps between values of next_f64 that fall below 1/4, among n gaps. Gaps of 16 or more are counted together.
+pub fn gap<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    const P: f64 = 0.25;
+    const T: usize = 16;
+    let mut counts = [0; T + 1];
+    for _ in 0..n {
+        let mut r = 0;
+        while rng.next_f64() >= P {
+            r += 1;
+        }
+        counts[r.min(T)] += 1;
+    }
+    let mut expected: Vec<f64> = (0..T)
+        .map(|r| n as f64 * P * (1.0 - P).powi(r as i32))
+        .collect();
+    expected.push(n as f64 * (1.0 - P).powi(T as i32));
+    let x = chi_square(&counts, &expected);
+    TestResult::new("gap", x, chi_square_sf(x, T as f64))
+}
+
+/// Marsaglia's birthday spacings test. Each of the given number of repetitions places 512 birthdays in a year of 2^24 days using the top 24 bits of a word, and counts the spacings between sorted birthdays that are repeated. The count is approximately Poisson distributed with mean 2.
+pub fn birthday_spacings<R: Rng + ?Sized>(rng: &mut R, repetitions: usize) -> TestResult {
+    const M: usize = 512;
+    const LAMBDA: f64 = 2.0;
+    const BINS: usize = 7;
+    let mut counts = [0; BINS];
+    for _ in 0..repetitions {
+        let mut days: Vec<u32> = (0..M).map(|_| rng.next_u32() >> 8).collect();
+        days.sort();
+        let mut spacings: Vec<u32> = std::iter::once(days[0])
+            .chain(days.windows(2).map(|w| w[1] - w[0]))
+            .collect();
+        spacings.sort();
+        let repeats = spacings.windows(2).filter(|w| w[0] == w[1]).count();
+        counts[repeats.min(BINS - 1)] += 1;
+    }
+    let mut probs = Vec::with_capacity(BINS);
+    let mut p = (-LAMBDA).exp();
+    for k in 0..BINS - 1 {
+        probs.push(p);
+        p *= LAMBDA / (k + 1) as f64;
+    }
+    probs.push(1.0 - probs.iter().sum::<f64>());
+    let expected: Vec<f64> = probs.iter().map(|p| p * repetitions as f64).collect();
+    let x = chi_square(&counts, &expected);
+    TestResult::new("birthday spacings", x, chi_square_sf(x, (BINS - 1) as f64))
+}
+
+/// Knuth's poker test: the number of distinct values in hands of five values, each made from the top three bits of a word, among n hands. Hands with one or two distinct values are counted together.
+pub fn poker<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    // For d = 8 the probability of r distinct values is 8!/(8-r)! * S(5, r) / 8^5, with S the Stirling numbers of the second kind
+    const PROBS: [f64; 4] = [
+        (8.0 + 56.0 * 15.0) / 32768.0,
+        336.0 * 25.0 / 32768.0,
+        1680.0 * 10.0 / 32768.0,
+        6720.0 / 32768.0,
+    ];
+    let mut counts = [0; 4];
+    for _ in 0..n {
+        let mut seen = 0_u8;
+        for _ in 0..5 {
+            seen |= 1 << (rng.next_u32() >> 29);
+        }
+        counts[(seen.count_ones() as usize).max(2) - 2] += 1;
+    }
+    let expected: Vec<f64> = PROBS.iter().map(|p| p * n as f64).collect();
+    let x = chi_square(&counts, &expected);
+    TestResult::new("poker", x, chi_square_sf(x, 3.0))
+}
+
+/// Rank over GF(2) of a square binary matrix with one row per word.
+fn gf2_rank(mut rows: Vec<u32>) -> u32 {
+    let mut rank = 0;
+    for bit in (0..32).rev() {
+        let mask = 1 << bit;
+        if let Some(pivot) = (rank as usize..rows.len()).find(|i| rows[*i] & mask != 0) {
+            rows.swap(rank as usize, pivot);
+            let p = rows[rank as usize];
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != rank as usize && *row & mask != 0 {
+                    *row ^= p;
+                }
+            }
+            rank += 1;
+        }
+    }
+    rank
+}
+
+/// NIST binary matrix rank test: the ranks of n random 32x32 matrices over GF(2), one word per row.
+pub fn matrix_rank<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    const PROBS: [f64; 3] = [0.2887880952, 0.5775761902, 0.1336357146];
+    let mut counts = [0; 3];
+    for _ in 0..n {
+        let rows = (0..32).map(|_| rng.next_u32()).collect();
+        counts[32 - gf2_rank(rows).max(30) as usize] += 1;
+    }
+    let expected: Vec<f64> = PROBS.iter().map(|p| p * n as f64).collect();
+    let x = chi_square(&counts, &expected);
+    TestResult::new("matrix rank", x, chi_square_sf(x, 2.0))
+}
+
+/// NIST linear complexity test: the linear complexity of n blocks of 500 bits each, compared to its distribution for random bits.
+pub fn linear_complexity<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    const M: usize = 500;
+    const PROBS: [f64; 7] = [0.010417, 0.03125, 0.125, 0.5, 0.25, 0.0625, 0.020833];
+    let mf = M as f64;
+    // (-1)^(M+1), which is -1 for the even M used here
+    let sign = if M.is_multiple_of(2) { -1.0 } else { 1.0 };
+    let mu = mf / 2.0 + (9.0 + sign) / 36.0 - (mf / 3.0 + 2.0 / 9.0) / 2.0_f64.powf(mf);
+    let mut counts = [0; 7];
+    for _ in 0..n {
+        let l = linear_complexity_of_bits(&bits(rng, M)) as f64;
+        // M is even so the sign factor (-1)^M is one
+        let t = l - mu + 2.0 / 9.0;
+        let bin = ((t + 2.5).ceil().max(0.0) as usize).min(6);
+        counts[bin] += 1;
+    }
+    let expected: Vec<f64> = PROBS.iter().map(|p| p * n as f64).collect();
+    let x = chi_square(&counts, &expected);
+    TestResult::new("linear complexity", x, chi_square_sf(x, 6.0))
+}
+
+/// Kolmogorov-Smirnov test of n values of next_f64.
+pub fn ks_f64<R: Rng + ?Sized>(rng: &mut R, n: usize) -> TestResult {
+    let values: Vec<f64> = (0..n).map(|_| rng.next_f64()).collect();
+    TestResult {
+        name: "ks f64",
+        ..ks_uniform(&values)
+    }
+}
+
+/// Run every test once with sample sizes that take a fraction of a second for a fast generator.
+pub fn run_battery<R: Rng + ?Sized>(rng: &mut R) -> Vec<TestResult> {
+    vec![
+        monobit(rng, 1 << 20),
+        runs(rng, 1 << 20),
+        serial(rng, 100_000),
+        gap(rng, 10_000),
+        birthday_spacings(rng, 1000),
+        poker(rng, 20_000),
+        matrix_rank(rng, 1000),
+        linear_complexity(rng, 200),
+        ks_f64(rng, 10_000),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::{Lcg, Mt19937, Pcg64_32};
+
+    #[test]
+    fn helpers() {
+        // Berlekamp-Massey recovers the length of an LFSR
+        let s = [0, 0, 1, 1, 0, 1, 1, 1, 0];
+        assert_eq!(linear_complexity_of_bits(&s), 5);
+        assert_eq!(linear_complexity_of_bits(&[0, 0, 0, 1]), 4);
+        assert_eq!(linear_complexity_of_bits(&[1, 0, 1, 0, 1, 0]), 2);
+        assert_eq!(gf2_rank((0..32).map(|i| 1 << i).collect()), 32);
+        assert_eq!(gf2_rank(vec![0b11, 0b01, 0b10]), 2);
+        // Evenly spread values pass and clustered values fail
+        let even: Vec<f64> = (0..1000).map(|i| (i as f64 + 0.5) / 1000.0).collect();
+        assert!(ks_uniform(&even).p_value > 0.99);
+        assert!(ks_uniform(&even[..500]).p_value < 1e-6);
+        assert!(chi_square_p_values(&even).p_value > 0.99);
+    }
+
+    #[test]
+    fn battery() {
+        let mut randu = Lcg::new(1, 65539, 0, 1 << 31);
+        let results = run_battery(&mut randu);
+        assert!(results.iter().any(|r| !r.passed(1e-6)));
+
+        let mut mt = Mt19937::new(5489);
+        let mut pcg = Pcg64_32::new_xsh_rr(42, 6364136223846793005, 1442695040888963407);
+        let mut p_values = Vec::new();
+        for results in [run_battery(&mut mt), run_battery(&mut pcg)] {
+            for r in results {
+                assert!(r.passed(1e-4), "{r}");
+                p_values.push(r.p_value);
+            }
+        }
+        assert!(ks_uniform(&p_values).passed(1e-4));
+    }
+
+    #[test]
+    #[ignore = "visualization"]
+    fn print_battery() {
+        let mut randu = Lcg::new(1, 65539, 0, 1 << 31);
+        for r in run_battery(&mut randu) {
+            println!("{r}");
+        }
+    }
+}
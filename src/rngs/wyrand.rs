@@ -0,0 +1,56 @@
+use crate::rngs::{Advance, Rng};
+
+const INCREMENT: u64 = 0xa0761d6478bd642f;
+
+/// WyRand, from the wyhash family. A Weyl sequence mixed by a single 128-bit multiplication.
+///
+/// ```text
+/// seed = 0
+/// 1233057930238600590, 14892235431655409005, 7060326114132480676, 8700114197940311904...
+/// ```
+#[derive(Clone)]
+pub struct WyRand {
+    state: u64,
+}
+
+impl WyRand {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Iterator for WyRand {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state = self.state.wrapping_add(INCREMENT);
+        let t = (self.state as u128) * ((self.state ^ 0xe7037ed1a0b428db) as u128);
+        Some(((t >> 64) ^ t) as u64)
+    }
+}
+
+impl Advance for WyRand {
+    /// A step is one output. The underlying Weyl sequence is advanced directly.
+    fn advance(&mut self, n: u64) {
+        self.state = self.state.wrapping_add(n.wrapping_mul(INCREMENT));
+    }
+}
+
+impl Rng for WyRand {
+    /// The upper half of the output.
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
+crate::check_sequences!(
+    WyRand::new(42), [12558987674375533620_u64, 16846851108956068306, 14652274819296609082, 16945271478357465713, 6502026092014180032, 17023095405122205773];
+);
+
+crate::sample_sequences!(
+    WyRand::new(0);
+);
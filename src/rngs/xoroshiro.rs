@@ -1,3 +1,5 @@
+use crate::rngs::{Advance, Rng, SplitMix64, gf2::advance_linear};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Scrambler {
     Plus,
@@ -6,6 +8,7 @@ pub enum Scrambler {
 }
 
 /// The Xoroshiro128 PRNG.
+#[derive(Clone)]
 pub struct Xoroshiro128 {
     state: [u64; 2],
     scrambler: Scrambler,
@@ -36,6 +39,15 @@ impl Xoroshiro128 {
         }
     }
 
+    /// Fill the state from SplitMix64, as recommended by the reference implementation.
+    pub fn seed_from_u64(seed: u64, scrambler: Scrambler) -> Self {
+        let mut sm = SplitMix64::new(seed);
+        Self {
+            state: [sm.next().unwrap(), sm.next().unwrap()],
+            scrambler,
+        }
+    }
+
     fn step(&mut self) {
         if self.scrambler == Scrambler::PlusPlus {
             self.state[1] ^= self.state[0];
@@ -56,14 +68,13 @@ impl Xoroshiro128 {
 
     /// Jump forward by 2^64 steps.
     pub fn jump(&mut self) {
-        let mut s = [0; 4];
+        let mut s = [0; 2];
         if self.scrambler == Scrambler::PlusPlus {
             for j in Self::JUMP_PP {
                 for b in 0..64 {
                     if j & (1 << b) != 0 {
-                        for n in 0..4 {
-                            s[n] ^= self.state[n]
-                        }
+                        s[0] ^= self.state[0];
+                        s[1] ^= self.state[1];
                     }
                     self.step()
                 }
@@ -72,29 +83,25 @@ impl Xoroshiro128 {
             for j in Self::JUMP {
                 for b in 0..64 {
                     if j & (1 << b) != 0 {
-                        for n in 0..4 {
-                            s[n] ^= self.state[n]
-                        }
+                        s[0] ^= self.state[0];
+                        s[1] ^= self.state[1];
                     }
                     self.step()
                 }
             }
         }
-        for n in 0..4 {
-            self.state[n] = s[n];
-        }
+        self.state = s;
     }
 
     /// Jump forward by 2^96 steps.
     pub fn long_jump(&mut self) {
-        let mut s = [0; 4];
+        let mut s = [0; 2];
         if self.scrambler == Scrambler::PlusPlus {
             for j in Self::LONG_JUMP_PP {
                 for b in 0..64 {
                     if j & (1 << b) != 0 {
-                        for n in 0..4 {
-                            s[n] ^= self.state[n]
-                        }
+                        s[0] ^= self.state[0];
+                        s[1] ^= self.state[1];
                     }
                     self.step()
                 }
@@ -103,18 +110,15 @@ impl Xoroshiro128 {
             for j in Self::LONG_JUMP {
                 for b in 0..64 {
                     if j & (1 << b) != 0 {
-                        for n in 0..4 {
-                            s[n] ^= self.state[n]
-                        }
+                        s[0] ^= self.state[0];
+                        s[1] ^= self.state[1];
                     }
                     self.step()
                 }
             }
         }
 
-        for n in 0..4 {
-            self.state[n] = s[n];
-        }
+        self.state = s;
     }
 }
 
@@ -136,3 +140,58 @@ impl Iterator for Xoroshiro128 {
         Some(out)
     }
 }
+
+impl Advance for Xoroshiro128 {
+    /// A step is one output.
+    fn advance(&mut self, n: u64) {
+        let mut rng = self.clone();
+        let step = |s: u128| {
+            rng.state = [s as u64, (s >> 64) as u64];
+            rng.step();
+            rng.state[0] as u128 | ((rng.state[1] as u128) << 64)
+        };
+        let s = advance_linear(
+            step,
+            self.state[0] as u128 | ((self.state[1] as u128) << 64),
+            n,
+        );
+        self.state = [s as u64, (s >> 64) as u64];
+    }
+}
+
+impl Rng for Xoroshiro128 {
+    /// The upper half of the output, as the lowest bits are the weakest.
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jump_constants() {
+        // The jump polynomials move exactly 2^64 steps ahead
+        for mut rng in [
+            Xoroshiro128::new_plus([1, 2]),
+            Xoroshiro128::new_plus_plus([1, 2]),
+        ] {
+            let mut jumped = rng.clone();
+            jumped.jump();
+            rng.advance(u64::MAX);
+            rng.step();
+            assert_eq!(jumped.state, rng.state);
+        }
+    }
+
+    #[test]
+    fn splitmix_seeding() {
+        let rng = Xoroshiro128::seed_from_u64(0, Scrambler::PlusPlus);
+        assert_eq!(rng.state, [16294208416658607535, 7960286522194355700]);
+    }
+}
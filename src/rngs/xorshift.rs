@@ -1,3 +1,5 @@
+use crate::rngs::{Advance, Rng, gf2::advance_linear};
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum XorshiftRule {
     A0,
@@ -84,6 +86,7 @@ impl Scrambler {
     }
 }
 
+#[derive(Clone)]
 pub struct Xorshift64 {
     state: u64,
     triple: (u64, u64, u64),
@@ -92,6 +95,23 @@ pub struct Xorshift64 {
 }
 
 impl Xorshift64 {
+    /// The triple gives the shift amounts used by the rule. Marsaglia's full period triples include (13, 7, 17).
+    /// Panics if the state is zero as the generator would never leave it.
+    pub fn new(
+        state: u64,
+        triple: (u64, u64, u64),
+        rule: XorshiftRule,
+        scrambler: Scrambler,
+    ) -> Self {
+        assert!(state != 0, "state must be nonzero");
+        Self {
+            state,
+            triple,
+            rule,
+            scrambler,
+        }
+    }
+
     fn step(&mut self) {
         self.rule.apply(&mut self.state, self.triple);
     }
@@ -106,3 +126,21 @@ impl Iterator for Xorshift64 {
         Some(out)
     }
 }
+
+impl Advance for Xorshift64 {
+    /// A step is one output.
+    fn advance(&mut self, n: u64) {
+        let step = |s: u128| {
+            let mut s = s as u64;
+            self.rule.apply(&mut s, self.triple);
+            s as u128
+        };
+        self.state = advance_linear(step, self.state as u128, n) as u64;
+    }
+}
+
+impl Rng for Xorshift64 {
+    fn next_u32(&mut self) -> u32 {
+        self.next().unwrap()
+    }
+}
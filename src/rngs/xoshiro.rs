@@ -0,0 +1,96 @@
+use crate::rngs::{Rng, SplitMix64, xoroshiro::Scrambler};
+
+/// The Xoshiro256 PRNG, with 256 bits of state.
+///
+/// ```text
+/// state = [1, 2, 3, 4]
+/// Xoshiro256**
+/// 11520, 0, 1509978240, 1215971899390074240, 1216172134540287360, 607988272756665600...
+///
+/// Xoshiro256++
+/// 41943041, 58720359, 3588806011781223, 3591011842654386, 9228616714210784205...
+/// ```
+#[derive(Clone)]
+pub struct Xoshiro256 {
+    state: [u64; 4],
+    scrambler: Scrambler,
+}
+
+impl Xoshiro256 {
+    /// Initialize Xoshiro256+, which is only recommended for generating floats as its lowest bits are weak.
+    /// Panics if the state is all zero.
+    pub fn new_plus(state: [u64; 4]) -> Self {
+        Self::with_scrambler(state, Scrambler::Plus)
+    }
+
+    /// Initialize Xoshiro256++
+    /// Panics if the state is all zero.
+    pub fn new_plus_plus(state: [u64; 4]) -> Self {
+        Self::with_scrambler(state, Scrambler::PlusPlus)
+    }
+
+    /// Initialize Xoshiro256**
+    /// Panics if the state is all zero.
+    pub fn new_star_star(state: [u64; 4]) -> Self {
+        Self::with_scrambler(state, Scrambler::StarStar)
+    }
+
+    /// Fill the state from SplitMix64, as recommended by the reference implementation.
+    pub fn seed_from_u64(seed: u64, scrambler: Scrambler) -> Self {
+        let mut sm = SplitMix64::new(seed);
+        let state = std::array::from_fn(|_| sm.next().unwrap());
+        Self::with_scrambler(state, scrambler)
+    }
+
+    fn with_scrambler(state: [u64; 4], scrambler: Scrambler) -> Self {
+        assert!(state != [0; 4], "state must not be all zero");
+        Self { state, scrambler }
+    }
+
+    fn step(&mut self) {
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+    }
+}
+
+impl Iterator for Xoshiro256 {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let s = self.state;
+        let out = match self.scrambler {
+            Scrambler::PlusPlus => s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]),
+            Scrambler::StarStar => s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9),
+            Scrambler::Plus => s[0].wrapping_add(s[3]),
+        };
+        self.step();
+        Some(out)
+    }
+}
+
+impl Rng for Xoshiro256 {
+    /// The upper half of the output, as the lowest bits are the weakest.
+    fn next_u32(&mut self) -> u32 {
+        (self.next().unwrap() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next().unwrap()
+    }
+}
+
+crate::check_sequences!(
+    Xoshiro256::new_star_star([1, 2, 3, 4]), [11520_u64, 0, 1509978240, 1215971899390074240, 1216172134540287360, 607988272756665600];
+    Xoshiro256::new_plus_plus([1, 2, 3, 4]), [41943041_u64, 58720359, 3588806011781223, 3591011842654386, 9228616714210784205, 9973669472204895162];
+    Xoshiro256::new_plus([1, 2, 3, 4]), [5_u64, 211106232532999, 211106635186183, 9223759065350669058, 9250833439874351877, 13862484359527728515];
+);
+
+crate::sample_sequences!(
+    Xoshiro256::new_star_star([1, 2, 3, 4]);
+    Xoshiro256::new_plus_plus([1, 2, 3, 4]);
+);
@@ -0,0 +1,402 @@
+//! Set partitions of 0..n. A partition is represented either by its blocks, each sorted and ordered by their smallest element, or by its restricted growth string where element i is in block a_i, a_0 = 0 and each a_i is at most one more than every value before it.
+
+/// The blocks of the set partition with the given restricted growth string.
+pub fn rgs_to_blocks(rgs: &[usize]) -> Vec<Vec<usize>> {
+    let mut blocks: Vec<Vec<usize>> = Vec::new();
+    for (element, &a) in rgs.iter().enumerate() {
+        if a == blocks.len() {
+            blocks.push(vec![element]);
+        } else {
+            blocks[a].push(element);
+        }
+    }
+    blocks
+}
+
+/// The restricted growth string of a set partition given by its blocks, in any order. Returns None unless the blocks are nonempty and partition 0..n for some n.
+pub fn blocks_to_rgs(blocks: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let n = blocks.iter().map(|b| b.len()).sum();
+    let mut block_of = vec![None; n];
+    for (i, block) in blocks.iter().enumerate() {
+        if block.is_empty() {
+            return None;
+        }
+        for &element in block {
+            if block_of.get(element)?.is_some() {
+                return None;
+            }
+            block_of[element] = Some(i);
+        }
+    }
+    // Relabel the blocks in order of first appearance
+    let mut labels = vec![None; blocks.len()];
+    let mut next = 0;
+    let mut out = Vec::with_capacity(n);
+    for b in block_of {
+        let b = b?;
+        let label = *labels[b].get_or_insert_with(|| {
+            next += 1;
+            next - 1
+        });
+        out.push(label);
+    }
+    Some(out)
+}
+
+// The largest value allowed at each position, one more than the maximum of the values before it
+fn limits(rgs: &[usize]) -> Vec<usize> {
+    rgs.iter()
+        .scan(0, |max, &a| {
+            let limit = *max + 1;
+            *max = (*max).max(a);
+            Some(limit)
+        })
+        .collect()
+}
+
+/// The restricted growth strings of length n in lexicographic order, optionally with exactly k distinct values. These correspond to the set partitions of 0..n, with k blocks, and their number is the Bell number or the Stirling number of the second kind.
+///
+/// ```text
+/// n = 3
+/// [0, 0, 0], [0, 0, 1], [0, 1, 0], [0, 1, 1], [0, 1, 2]
+/// ```
+pub struct RestrictedGrowthStrings {
+    blocks: Option<usize>,
+    rgs: Option<Vec<usize>>,
+}
+
+impl RestrictedGrowthStrings {
+    pub fn new(n: usize) -> Self {
+        Self {
+            blocks: None,
+            rgs: Some(vec![0; n]),
+        }
+    }
+
+    /// Only those with exactly k blocks.
+    pub fn with_blocks(n: usize, k: usize) -> Self {
+        let rgs = if k > n || (k == 0 && n > 0) {
+            None
+        } else {
+            // Zeroes then each new block as late as possible
+            let mut rgs = vec![0; n - k];
+            rgs.extend(0..k);
+            Some(rgs)
+        };
+        Self {
+            blocks: Some(k),
+            rgs,
+        }
+    }
+
+    fn successor(&self, rgs: &[usize]) -> Option<Vec<usize>> {
+        let n = rgs.len();
+        let limits = limits(rgs);
+        for i in (1..n).rev() {
+            let cap = match self.blocks {
+                Some(k) => limits[i].min(k - 1),
+                None => limits[i],
+            };
+            for v in rgs[i] + 1..=cap {
+                let used = limits[i].max(v + 1);
+                let missing = self.blocks.map_or(0, |k| k - used);
+                let remaining = n - 1 - i;
+                if missing <= remaining {
+                    let mut out = rgs[..i].to_vec();
+                    out.push(v);
+                    out.extend(std::iter::repeat_n(0, remaining - missing));
+                    out.extend(used..used + missing);
+                    return Some(out);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for RestrictedGrowthStrings {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.rgs.take()?;
+        self.rgs = self.successor(&out);
+        Some(out)
+    }
+}
+
+/// The set partitions of 0..n in the lexicographic order of their restricted growth strings, optionally with exactly k blocks.
+///
+/// ```text
+/// n = 3
+/// [[0, 1, 2]], [[0, 1], [2]], [[0, 2], [1]], [[0], [1, 2]], [[0], [1], [2]]
+/// ```
+pub struct SetPartitionsN {
+    rgs: RestrictedGrowthStrings,
+}
+
+impl SetPartitionsN {
+    pub fn new(n: usize) -> Self {
+        Self {
+            rgs: RestrictedGrowthStrings::new(n),
+        }
+    }
+
+    /// Only those with exactly k blocks.
+    pub fn with_blocks(n: usize, k: usize) -> Self {
+        Self {
+            rgs: RestrictedGrowthStrings::with_blocks(n, k),
+        }
+    }
+}
+
+impl Iterator for SetPartitionsN {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(rgs_to_blocks(&self.rgs.next()?))
+    }
+}
+
+/// The set partitions of 0..n in a Gray code order where each partition is reached from the one before by moving a single element to another block, possibly a new one.
+/// Element j sweeps back and forth between the block containing 0 and a block of its own, passing through the blocks in between, while the elements after it stay at one end of their sweeps.
+///
+/// ```text
+/// n = 3
+/// [[0, 1, 2]], [[0, 1], [2]], [[0], [1], [2]], [[0], [1, 2]], [[0, 2], [1]]
+/// ```
+pub struct SetPartitionsGray {
+    rgs: Vec<usize>,
+    up: Vec<bool>,
+    done: bool,
+}
+
+impl SetPartitionsGray {
+    pub fn new(n: usize) -> Self {
+        Self {
+            rgs: vec![0; n],
+            up: vec![true; n],
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SetPartitionsGray {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let out = rgs_to_blocks(&self.rgs);
+
+        let n = self.rgs.len();
+        let limits = limits(&self.rgs);
+        let movable = (1..n).rev().find(|&j| {
+            if self.up[j] {
+                self.rgs[j] < limits[j]
+            } else {
+                self.rgs[j] > 0
+            }
+        });
+        let Some(j) = movable else {
+            self.done = true;
+            return Some(out);
+        };
+        if self.up[j] {
+            self.rgs[j] += 1;
+        } else {
+            self.rgs[j] -= 1;
+        }
+        // Every later element is at the end of its sweep and turns around. Those in their own block stay there, though its label may change.
+        let mut max = self.rgs[..=j].iter().copied().max().unwrap_or(0);
+        for i in j + 1..n {
+            self.up[i] = !self.up[i];
+            self.rgs[i] = if self.up[i] { 0 } else { max + 1 };
+            max = max.max(self.rgs[i]);
+        }
+        Some(out)
+    }
+}
+
+/// The noncrossing partitions of 0..n, those with no a < b < c < d where a and c are in one block and b and d in another, in the lexicographic order of their restricted growth strings. They are counted by the Catalan numbers and by number of blocks with the Narayana triangle.
+///
+/// ```text
+/// n = 3
+/// [[0, 1, 2]], [[0, 1], [2]], [[0, 2], [1]], [[0], [1, 2]], [[0], [1], [2]]
+/// ```
+pub struct NoncrossingPartitionsN {
+    rgs: Option<Vec<usize>>,
+}
+
+impl NoncrossingPartitionsN {
+    pub fn new(n: usize) -> Self {
+        Self {
+            rgs: Some(vec![0; n]),
+        }
+    }
+
+    // The blocks that can still be extended after the prefix, along with the number of blocks used. Adding an element to a block closes every block opened after it.
+    fn open_blocks(prefix: &[usize]) -> (Vec<usize>, usize) {
+        let mut open = Vec::new();
+        let mut used = 0;
+        for &a in prefix {
+            if a == used {
+                open.push(a);
+                used += 1;
+            } else {
+                while open.last() != Some(&a) {
+                    open.pop();
+                }
+            }
+        }
+        (open, used)
+    }
+
+    fn successor(rgs: &[usize]) -> Option<Vec<usize>> {
+        for i in (1..rgs.len()).rev() {
+            let (open, used) = Self::open_blocks(&rgs[..i]);
+            // The block containing 0 is always open so the rest can be filled with zeroes
+            if let Some(v) = open
+                .into_iter()
+                .chain(std::iter::once(used))
+                .find(|&v| v > rgs[i])
+            {
+                let mut out = rgs[..i].to_vec();
+                out.push(v);
+                out.resize(rgs.len(), 0);
+                return Some(out);
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for NoncrossingPartitionsN {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let out = self.rgs.take()?;
+        self.rgs = Self::successor(&out);
+        Some(rgs_to_blocks(&out))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::narayana_triangle::NarayanaTriangle;
+    use crate::ranking::{RankedSetPartitions, Ranking};
+    use crate::stirling::StirlingSecond;
+    use num::BigInt;
+    use std::collections::HashSet;
+
+    fn crossing(blocks: &[Vec<usize>]) -> bool {
+        let rgs = blocks_to_rgs(blocks).unwrap();
+        let n = rgs.len();
+        (0..n).any(|a| {
+            (a + 1..n).any(|b| {
+                (b + 1..n).any(|c| {
+                    (c + 1..n).any(|d| rgs[a] == rgs[c] && rgs[b] == rgs[d] && rgs[a] != rgs[b])
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn stirling_rows() {
+        for (n, row) in StirlingSecond::new_big().take(9).enumerate() {
+            for (k, s) in row.iter().enumerate() {
+                let partitions: Vec<_> = SetPartitionsN::with_blocks(n, k).collect();
+                assert_eq!(&BigInt::from(partitions.len()), s, "S({n}, {k})");
+                assert!(partitions.iter().all(|p| p.len() == k));
+                assert!(partitions.is_sorted_by_key(|p| blocks_to_rgs(p)));
+            }
+            assert_eq!(SetPartitionsN::with_blocks(n, n + 1).count(), 0);
+        }
+    }
+
+    #[test]
+    fn matches_ranking() {
+        for n in 0..8 {
+            let family = RankedSetPartitions::new(n);
+            for (i, rgs) in RestrictedGrowthStrings::new(n).enumerate() {
+                assert_eq!(family.rank(&rgs), Some(BigInt::from(i)));
+                assert_eq!(
+                    rgs_to_blocks(&rgs).len(),
+                    rgs.iter().max().map_or(0, |m| m + 1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gray_code() {
+        for n in 0..9 {
+            let all: Vec<Vec<Vec<usize>>> = SetPartitionsGray::new(n).collect();
+            let distinct: HashSet<Vec<usize>> =
+                all.iter().map(|p| blocks_to_rgs(p).unwrap()).collect();
+            assert_eq!(distinct.len(), all.len());
+            assert_eq!(all.len(), SetPartitionsN::new(n).count());
+            for pair in all.windows(2) {
+                // Removing the moved element from both leaves the same partition
+                let (a, b) = (
+                    blocks_to_rgs(&pair[0]).unwrap(),
+                    blocks_to_rgs(&pair[1]).unwrap(),
+                );
+                let moved: Vec<usize> = (0..n)
+                    .filter(|&e| {
+                        let remove = |rgs: &[usize]| {
+                            let mut p = rgs_to_blocks(rgs);
+                            p.iter_mut().for_each(|block| block.retain(|&x| x != e));
+                            p.retain(|block| !block.is_empty());
+                            p
+                        };
+                        remove(&a) == remove(&b)
+                    })
+                    .collect();
+                assert!(!moved.is_empty(), "{:?} to {:?}", pair[0], pair[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn noncrossing() {
+        for (n, row) in NarayanaTriangle::new().take(9).enumerate() {
+            let n = n + 1;
+            let all: Vec<_> = NoncrossingPartitionsN::new(n).collect();
+            let expected: Vec<_> = SetPartitionsN::new(n).filter(|p| !crossing(p)).collect();
+            assert_eq!(all, expected);
+            for (k, count) in row.iter().enumerate() {
+                assert_eq!(
+                    all.iter().filter(|p| p.len() == k + 1).count() as i64,
+                    *count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        let blocks = vec![vec![4, 1], vec![0, 3], vec![2]];
+        assert_eq!(blocks_to_rgs(&blocks), Some(vec![0, 1, 2, 0, 1]));
+        assert_eq!(
+            rgs_to_blocks(&[0, 1, 2, 0, 1]),
+            vec![vec![0, 3], vec![1, 4], vec![2]]
+        );
+        assert_eq!(blocks_to_rgs(&[vec![0, 1], vec![1]]), None);
+        assert_eq!(blocks_to_rgs(&[vec![0, 2]]), None);
+        assert_eq!(blocks_to_rgs(&[vec![0], vec![]]), None);
+    }
+}
+
+crate::check_sequences!(
+    (0..).map(|n| SetPartitionsN::new(n).count()), [1, 1, 2, 5, 15, 52, 203, 877, 4140];
+    (0..).map(|n| SetPartitionsGray::new(n).count()), [1, 1, 2, 5, 15, 52, 203, 877, 4140];
+    (0..).map(|n| NoncrossingPartitionsN::new(n).count()), [1, 1, 2, 5, 14, 42, 132, 429, 1430];
+);
+
+crate::print_sequences!(
+    SetPartitionsN::new(4), 15, "{:?}", "\n";
+    SetPartitionsGray::new(4), 15, "{:?}", "\n";
+    NoncrossingPartitionsN::new(4), 14, "{:?}", "\n";
+);
@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+
+/// The shape of an eventually periodic sequence x_0, x_1 = f(x_0), x_2 = f(x_1)... The values x_0 through x_(tail-1) never repeat and every value after them repeats with the given period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+/// Counts calls to a step function so that the search can be abandoned.
+struct Stepper<F> {
+    step: F,
+    calls: usize,
+    max_calls: usize,
+}
+
+impl<F> Stepper<F> {
+    fn call<T>(&mut self, x: &T) -> Option<T>
+    where
+        F: FnMut(&T) -> T,
+    {
+        if self.calls >= self.max_calls {
+            return None;
+        }
+        self.calls += 1;
+        Some((self.step)(x))
+    }
+}
+
+/// Once the period is known the tail is found by moving two pointers, one period apart, forward together until they meet.
+fn find_tail<T, F>(start: &T, period: usize, stepper: &mut Stepper<F>) -> Option<usize>
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    for _ in 0..period {
+        hare = stepper.call(&hare)?;
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = stepper.call(&tortoise)?;
+        hare = stepper.call(&hare)?;
+        tail += 1;
+    }
+    Some(tail)
+}
+
+/// Floyd's tortoise and hare algorithm. Uses constant memory and about 3(tail + period) calls to step.
+/// Returns None if the cycle is not found within max_steps calls to step.
+pub fn floyd<T, F>(start: T, step: F, max_steps: usize) -> Option<Cycle>
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut stepper = Stepper {
+        step,
+        calls: 0,
+        max_calls: max_steps,
+    };
+    let mut tortoise = stepper.call(&start)?;
+    let mut hare = stepper.call(&tortoise)?;
+    while tortoise != hare {
+        tortoise = stepper.call(&tortoise)?;
+        let h = stepper.call(&hare)?;
+        hare = stepper.call(&h)?;
+    }
+    // The hare is now a multiple of the period ahead of the tortoise
+    let mut tail = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = stepper.call(&tortoise)?;
+        hare = stepper.call(&hare)?;
+        tail += 1;
+    }
+    let mut period = 1;
+    hare = stepper.call(&tortoise)?;
+    while tortoise != hare {
+        hare = stepper.call(&hare)?;
+        period += 1;
+    }
+    Some(Cycle { tail, period })
+}
+
+/// Brent's algorithm. The tortoise teleports to the hare at each power of two, which finds the period directly and usually takes fewer calls to step than Floyd's algorithm.
+/// Returns None if the cycle is not found within max_steps calls to step.
+pub fn brent<T, F>(start: T, step: F, max_steps: usize) -> Option<Cycle>
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut stepper = Stepper {
+        step,
+        calls: 0,
+        max_calls: max_steps,
+    };
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = stepper.call(&start)?;
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = stepper.call(&hare)?;
+        period += 1;
+    }
+    let tail = find_tail(&start, period, &mut stepper)?;
+    Some(Cycle { tail, period })
+}
+
+/// Gosper's algorithm from HAKMEM. Each value is compared against a table of earlier values, where x_n is stored in position k when 2^k is the largest power of two dividing n. The table never holds more than about log2(tail + period) values and a repeat is found within tail + 2 * period steps.
+/// Returns None if the cycle is not found within max_steps calls to step.
+pub fn gosper<T, F>(start: T, step: F, max_steps: usize) -> Option<Cycle>
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    let mut stepper = Stepper {
+        step,
+        calls: 0,
+        max_calls: max_steps,
+    };
+    let mut table: Vec<T> = vec![start.clone()];
+    let mut x = start.clone();
+    let mut n: usize = 0;
+    loop {
+        x = stepper.call(&x)?;
+        n += 1;
+        if table.contains(&x) {
+            break;
+        }
+        let k = n.trailing_zeros() as usize;
+        if k < table.len() {
+            table[k] = x.clone();
+        } else {
+            table.push(x.clone());
+        }
+    }
+    // A repeat shows that x is on the cycle, so the period is the distance back to x
+    let mut period = 1;
+    let mut y = stepper.call(&x)?;
+    while y != x {
+        y = stepper.call(&y)?;
+        period += 1;
+    }
+    let tail = find_tail(&start, period, &mut stepper)?;
+    Some(Cycle { tail, period })
+}
+
+/// The complete structure of a function from the states 0..n to themselves. Each state leads along a tail into exactly one cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionalGraph {
+    /// The cycles, each beginning with its least state, in order of their least state.
+    pub cycles: Vec<Vec<usize>>,
+    /// For each state the position in cycles of the cycle it reaches.
+    pub cycle_of: Vec<usize>,
+    /// For each state the number of steps it takes to reach its cycle. This is zero for states on a cycle.
+    pub tail: Vec<usize>,
+}
+
+impl FunctionalGraph {
+    /// Analyze the function by following every state once.
+    /// Panics if step leaves the range 0..size.
+    pub fn new<F: FnMut(usize) -> usize>(size: usize, mut step: F) -> Self {
+        const UNSEEN: usize = usize::MAX;
+        const IN_PROGRESS: usize = usize::MAX - 1;
+        let mut cycle_of = vec![UNSEEN; size];
+        let mut tail = vec![0; size];
+        let mut cycles: Vec<Vec<usize>> = Vec::new();
+        let mut path = Vec::new();
+        for s in 0..size {
+            if cycle_of[s] != UNSEEN {
+                continue;
+            }
+            // Follow the path until it reaches a state that is already resolved or on this path
+            let mut x = s;
+            while cycle_of[x] == UNSEEN {
+                cycle_of[x] = IN_PROGRESS;
+                path.push(x);
+                x = step(x);
+                assert!(x < size, "step must stay within 0..{size}");
+            }
+            let (c, mut t) = if cycle_of[x] == IN_PROGRESS {
+                // A new cycle
+                let start = path.iter().position(|p| *p == x).unwrap();
+                let cycle: Vec<usize> = path.split_off(start);
+                let c = cycles.len();
+                for p in cycle.iter() {
+                    cycle_of[*p] = c;
+                    tail[*p] = 0;
+                }
+                cycles.push(cycle);
+                (c, 0)
+            } else {
+                (cycle_of[x], tail[x])
+            };
+            for p in path.drain(..).rev() {
+                t += 1;
+                cycle_of[p] = c;
+                tail[p] = t;
+            }
+        }
+
+        // Put each cycle in canonical form and sort them
+        let mut order: Vec<usize> = (0..cycles.len()).collect();
+        for cycle in cycles.iter_mut() {
+            let min = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+            cycle.rotate_left(min);
+        }
+        order.sort_by_key(|i| cycles[*i][0]);
+        let mut position = vec![0; cycles.len()];
+        for (new, old) in order.iter().enumerate() {
+            position[*old] = new;
+        }
+        let cycles = order.iter().map(|i| cycles[*i].clone()).collect();
+        let cycle_of = cycle_of.into_iter().map(|c| position[c]).collect();
+        Self {
+            cycles,
+            cycle_of,
+            tail,
+        }
+    }
+
+    /// The number of states that lie on a cycle.
+    pub fn cyclic_states(&self) -> usize {
+        self.cycles.iter().map(|c| c.len()).sum()
+    }
+
+    /// The number of states that reach each cycle, including the states on the cycle.
+    pub fn basin_sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.cycles.len()];
+        for c in self.cycle_of.iter() {
+            sizes[*c] += 1;
+        }
+        sizes
+    }
+
+    /// The longest tail of any state.
+    pub fn max_tail(&self) -> usize {
+        self.tail.iter().copied().max().unwrap_or(0)
+    }
+
+    /// How many cycles there are of each length.
+    pub fn cycle_length_counts(&self) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for c in self.cycles.iter() {
+            *counts.entry(c.len()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// The number of states that are not the image of any state. These can only appear at the start of a sequence.
+    pub fn unreachable_states<F: FnMut(usize) -> usize>(&self, mut step: F) -> usize {
+        let mut reached = vec![false; self.tail.len()];
+        for s in 0..self.tail.len() {
+            reached[step(s)] = true;
+        }
+        reached.iter().filter(|r| !**r).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Number, divisor::AliquotSequence, ducci::Ducci, pisano::PisanoPeriod};
+    use crate::{rngs::Lcg, utils::divisibility::aliquot_sum, weyl::WeylMarsaglia};
+
+    type Detector =
+        fn(Option<Number>, fn(&Option<Number>) -> Option<Number>, usize) -> Option<Cycle>;
+
+    fn aliquot_step(n: &Option<Number>) -> Option<Number> {
+        n.and_then(aliquot_sum)
+    }
+
+    #[test]
+    fn aliquot_cycles() {
+        // A failed aliquot sum stays failed, which never happens for these starting values
+        let detectors: [Detector; 3] = [floyd, brent, gosper];
+        for detect in detectors {
+            // 95, 25, 6, 6, 6...
+            assert_eq!(
+                detect(Some(95), aliquot_step, 1000),
+                Some(Cycle { tail: 2, period: 1 })
+            );
+            // An amicable pair
+            assert_eq!(
+                detect(Some(220), aliquot_step, 1000),
+                Some(Cycle { tail: 0, period: 2 })
+            );
+            // A sociable cycle
+            assert_eq!(
+                detect(Some(12496), aliquot_step, 1000),
+                Some(Cycle { tail: 0, period: 5 })
+            );
+            // 10, 8, 7, 1, 0, 0, 0...
+            assert_eq!(
+                detect(Some(10), aliquot_step, 1000),
+                Some(Cycle { tail: 4, period: 1 })
+            );
+            // A sequence that never repeats
+            assert_eq!(detect(Some(1), |n| n.map(|n| n + 1), 100), None);
+        }
+        assert_eq!(
+            AliquotSequence::new(95).take(4).collect::<Vec<_>>(),
+            vec![95, 25, 6, 6]
+        );
+    }
+
+    #[test]
+    fn pisano_periods() {
+        for (n, p) in (1..=60).zip(PisanoPeriod::new()) {
+            let step = |s: &(Number, Number)| (s.1, (s.0 + s.1) % n);
+            let cycle = brent((0, 1 % n), step, 10_000).unwrap();
+            assert_eq!(
+                cycle,
+                Cycle {
+                    tail: 0,
+                    period: p as usize
+                }
+            );
+            assert_eq!(gosper((0, 1 % n), step, 10_000), Some(cycle));
+        }
+    }
+
+    #[test]
+    fn ducci_tuples() {
+        // Tuples with a power of two length reach zero, other lengths become periodic
+        let step = |t: &Vec<Number>| Ducci::new(t.clone()).nth(1).unwrap();
+        let cycle = floyd(vec![0, 653, 1854, 4063], step, 1000).unwrap();
+        assert_eq!(cycle.period, 1);
+        assert_eq!(
+            Ducci::new(vec![0, 653, 1854, 4063]).nth(cycle.tail),
+            Some(vec![0; 4])
+        );
+        let cycle = brent(vec![1, 2, 3], step, 1000).unwrap();
+        assert_eq!(cycle, Cycle { tail: 2, period: 3 });
+    }
+
+    #[test]
+    fn weyl_period() {
+        // Every value is on the same cycle of length 16
+        let values: Vec<usize> = WeylMarsaglia::new(5, 16)
+            .take(17)
+            .map(|x| x as usize)
+            .collect();
+        let step = |x: &usize| (x + 5) % 16;
+        assert_eq!(
+            floyd(values[0], step, 1000),
+            Some(Cycle {
+                tail: 0,
+                period: 16
+            })
+        );
+        assert_eq!(values[16], values[0]);
+        let graph = FunctionalGraph::new(16, |x| step(&x));
+        assert_eq!(graph.cycles.len(), 1);
+        for w in values.windows(2) {
+            assert_eq!(step(&w[0]), w[1]);
+        }
+    }
+
+    #[test]
+    fn lcg_state_space() {
+        let m = 1 << 16;
+        let lcg_step = |a, c| move |x: usize| Lcg::new(x as u64, a, c, m).nth(1).unwrap() as usize;
+
+        // The Hull-Dobell theorem gives a single cycle through every state
+        let graph = FunctionalGraph::new(m as usize, lcg_step(5, 1));
+        assert_eq!(graph.cycle_length_counts(), BTreeMap::from([(1 << 16, 1)]));
+        assert_eq!(graph.max_tail(), 0);
+
+        // A multiplier that is 1 mod 4 without an increment splits the odd states into cycles of length 2^14 and the even states by their power of two
+        let graph = FunctionalGraph::new(m as usize, lcg_step(5, 0));
+        assert_eq!(graph.max_tail(), 0);
+        let counts = graph.cycle_length_counts();
+        assert_eq!(counts[&(1 << 14)], 2);
+        assert_eq!(graph.cyclic_states(), 1 << 16);
+
+        // An even multiplier eventually sends every state to zero
+        let graph = FunctionalGraph::new(m as usize, lcg_step(6, 0));
+        assert_eq!(graph.cycles, vec![vec![0]]);
+        assert_eq!(graph.max_tail(), 16);
+        assert_eq!(graph.basin_sizes(), vec![1 << 16]);
+        assert_eq!(graph.unreachable_states(lcg_step(6, 0)), 1 << 15);
+
+        // The detectors agree with the full analysis
+        let step = lcg_step(21, 0);
+        let graph = FunctionalGraph::new(m as usize, step);
+        for seed in [1, 2, 12, 1000, 4096, 65535] {
+            let cycle = Cycle {
+                tail: graph.tail[seed],
+                period: graph.cycles[graph.cycle_of[seed]].len(),
+            };
+            assert_eq!(floyd(seed, |x| step(*x), 1 << 20), Some(cycle));
+            assert_eq!(brent(seed, |x| step(*x), 1 << 20), Some(cycle));
+            assert_eq!(gosper(seed, |x| step(*x), 1 << 20), Some(cycle));
+        }
+    }
+
+    #[test]
+    fn functional_graph() {
+        // 0 -> 1 -> 2 -> 0, 3 -> 2, 4 -> 4, 5 -> 4, 6 -> 5
+        let f = [1, 2, 0, 2, 4, 4, 5];
+        let graph = FunctionalGraph::new(7, |x| f[x]);
+        assert_eq!(graph.cycles, vec![vec![0, 1, 2], vec![4]]);
+        assert_eq!(graph.cycle_of, vec![0, 0, 0, 0, 1, 1, 1]);
+        assert_eq!(graph.tail, vec![0, 0, 0, 1, 0, 1, 2]);
+        assert_eq!(graph.basin_sizes(), vec![4, 3]);
+        assert_eq!(graph.unreachable_states(|x| f[x]), 2);
+    }
+}
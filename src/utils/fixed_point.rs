@@ -0,0 +1,173 @@
+//! Arbitrary precision real arithmetic using BigInt values scaled by 2^bits, where bits is the number of fractional bits.
+//! Every function truncates its result, so the absolute error of each operation is less than a few units in the last place,
+//! except exp which has the same bound relative to its result.
+use num::{BigInt, One, Signed, Zero};
+
+/// The fixed point representation of num/den.
+pub fn from_ratio(num: i64, den: i64, bits: u64) -> BigInt {
+    (BigInt::from(num) << bits) / den
+}
+
+/// Change the number of fractional bits of a value, truncating if precision is lost.
+pub fn rescale(a: &BigInt, from: u64, to: u64) -> BigInt {
+    if to >= from {
+        a << (to - from)
+    } else {
+        a >> (from - to)
+    }
+}
+
+/// The nearest integer to a fixed point value, with ties rounded up.
+pub fn round(a: &BigInt, bits: u64) -> BigInt {
+    if bits == 0 {
+        return a.clone();
+    }
+    (a + (BigInt::one() << (bits - 1))) >> bits
+}
+
+pub fn mul(a: &BigInt, b: &BigInt, bits: u64) -> BigInt {
+    (a * b) >> bits
+}
+
+/// Panics if b is zero.
+pub fn div(a: &BigInt, b: &BigInt, bits: u64) -> BigInt {
+    (a << bits) / b
+}
+
+/// Panics if a is negative.
+pub fn sqrt(a: &BigInt, bits: u64) -> BigInt {
+    assert!(
+        !a.is_negative(),
+        "cannot take the square root of a negative number"
+    );
+    (a << bits).sqrt()
+}
+
+// arctan(1/m) by its Taylor series
+fn arctan_inverse(m: i64, bits: u64) -> BigInt {
+    let m2 = BigInt::from(m * m);
+    let mut power = (BigInt::one() << bits) / m;
+    let mut sum = BigInt::zero();
+    let mut j = 0_i64;
+    while !power.is_zero() {
+        let term = &power / (2 * j + 1);
+        if j % 2 == 0 {
+            sum += term;
+        } else {
+            sum -= term;
+        }
+        power /= &m2;
+        j += 1;
+    }
+    sum
+}
+
+/// Pi, using Machin's formula.
+pub fn pi(bits: u64) -> BigInt {
+    let guard = 16;
+    let p = (arctan_inverse(5, bits + guard) << 4) - (arctan_inverse(239, bits + guard) << 2);
+    p >> guard
+}
+
+// Number of bits in the integer part of |x|
+fn integer_bits(x: &BigInt, bits: u64) -> u64 {
+    (x.abs() >> bits).bits()
+}
+
+/// The exponential function. The error is bounded relative to the result, which makes it suitable for large arguments.
+pub fn exp(x: &BigInt, bits: u64) -> BigInt {
+    if x.is_negative() {
+        let one = BigInt::one() << bits;
+        return div(&one, &exp(&-x, bits), bits);
+    }
+    // Reduce the argument below 2^-8 then square the result back up, each squaring doubling the relative error
+    let halvings = integer_bits(x, bits) + 8;
+    let work = bits + halvings + 16;
+    let y = rescale(x, bits, work) >> halvings;
+    let mut term = BigInt::one() << work;
+    let mut sum = term.clone();
+    let mut j = 1_u64;
+    while !term.is_zero() {
+        term = mul(&term, &y, work) / j;
+        sum += &term;
+        j += 1;
+    }
+    for _ in 0..halvings {
+        sum = mul(&sum, &sum, work);
+    }
+    rescale(&sum, work, bits)
+}
+
+/// The cosine function.
+pub fn cos(x: &BigInt, bits: u64) -> BigInt {
+    // Reduce the argument below 2^-8 then apply the double angle formula, each step at most quadrupling the error
+    let halvings = integer_bits(x, bits) + 8;
+    let work = bits + 2 * halvings + 16;
+    let y = rescale(&x.abs(), bits, work) >> halvings;
+    let y2 = mul(&y, &y, work);
+    let mut term = BigInt::one() << work;
+    let mut sum = term.clone();
+    let mut j = 1_u64;
+    while !term.is_zero() {
+        term = -mul(&term, &y2, work) / ((2 * j - 1) * (2 * j));
+        sum += &term;
+        j += 1;
+    }
+    let one = BigInt::one() << work;
+    for _ in 0..halvings {
+        sum = (mul(&sum, &sum, work) << 1) - &one;
+    }
+    rescale(&sum, work, bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BITS: u64 = 200;
+
+    fn to_f64(a: &BigInt) -> f64 {
+        let shift = BITS - 60;
+        let top: i64 = (a >> shift).try_into().unwrap();
+        top as f64 / 2_f64.powi(60)
+    }
+
+    #[test]
+    fn constants() {
+        // The first 40 hexadecimal digits of the fractional part of pi
+        let frac = pi(160) - (BigInt::from(3) << 160);
+        assert_eq!(
+            format!("{frac:x}"),
+            "243f6a8885a308d313198a2e03707344a4093822"
+        );
+        let e = exp(&(BigInt::one() << BITS), BITS);
+        assert!((to_f64(&e) - std::f64::consts::E).abs() < 1e-15);
+        let root2 = sqrt(&(BigInt::from(2) << BITS), BITS);
+        assert!((to_f64(&root2) - std::f64::consts::SQRT_2).abs() < 1e-15);
+    }
+
+    #[test]
+    fn identities() {
+        let one = BigInt::one() << BITS;
+        let tolerance = BigInt::one() << 16;
+        for (num, den) in [(1, 3), (7, 2), (-5, 4), (100, 1), (-1000, 7)] {
+            let x = from_ratio(num, den, BITS);
+            // exp(x) exp(-x) = 1, checked relative to exp(|x|)
+            let e = exp(&x, BITS);
+            let product = mul(&e, &exp(&-&x, BITS), BITS);
+            let scale = exp(&x.abs(), BITS) >> BITS;
+            assert!((product - &one).abs() <= &tolerance * (scale + 1));
+            // cos(2x) = 2cos(x)^2 - 1
+            let c = cos(&x, BITS);
+            let double: BigInt = (mul(&c, &c, BITS) << 1) - &one;
+            let c2 = cos(&(x << 1), BITS);
+            assert!((c2 - double).abs() <= tolerance);
+        }
+        // cos(pi/3) = 1/2
+        let third = pi(BITS) / 3;
+        let half: BigInt = &one >> 1;
+        assert!((cos(&third, BITS) - half).abs() <= tolerance);
+        assert_eq!(round(&from_ratio(7, 2, BITS), BITS), BigInt::from(4));
+        assert_eq!(round(&from_ratio(-7, 3, BITS), BITS), BigInt::from(-2));
+    }
+}
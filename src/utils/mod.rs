@@ -1,8 +1,10 @@
 //! Widely used utility functions or those not obviously linked to a specific sequence.
 pub mod collatz;
+pub mod cycle_detection;
 pub mod divisibility;
 pub mod exp_by_squaring;
 pub mod factorial;
+pub mod fixed_point;
 pub mod goodstein;
 pub mod miller_rabin;
 pub mod modular_int;
@@ -12,4 +14,5 @@ pub mod polynomial;
 pub mod polynomial_arithmetic;
 pub mod polynomial_printing;
 pub mod recursive_factoring;
+pub mod special_functions;
 pub mod totient;